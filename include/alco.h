@@ -0,0 +1,17 @@
+#ifndef ALCO_H
+#define ALCO_H
+
+/* The colorscheme was applied to all targets. */
+#define ALCO_OK 0
+/* The colorscheme couldn't be applied. */
+#define ALCO_ERROR 1
+/* The colorscheme was applied, but some targets failed to reload. */
+#define ALCO_PARTIAL 2
+
+/* Applies a colorscheme, using the default files and the settings file. */
+int alco_apply(const char *colorscheme);
+
+/* Toggles to the next colorscheme, using the default files and the settings file. */
+int alco_toggle(void);
+
+#endif
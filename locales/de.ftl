@@ -0,0 +1,92 @@
+# Messages printed when applying, toggling and reloading colorschemes. The error messages end with a
+# colon, the cause is printed on the next line.
+
+error-applying-previous = Fehler beim Anwenden des vorherigen Farbschemas:
+error-applying = Fehler beim Anwenden des Farbschemas { $colorscheme }:
+error-overriding-accent = Fehler beim Überschreiben der Akzentfarbe von { $colorscheme }:
+error-rendering = Fehler beim Erzeugen des { $target }-Farbschemas:
+error-getting-current = Fehler beim Lesen des aktuellen Farbschemas:
+error-toggling = Fehler beim Wechseln des Farbschemas:
+error-reloading = Fehler beim Neuladen des Farbschemas:
+error-present-on = Fehler beim Einschalten des Präsentationsmodus:
+error-present-off = Fehler beim Ausschalten des Präsentationsmodus:
+error-setting-filter = Fehler beim Setzen des Filters { $filter }:
+error-setting-opacity = Fehler beim Setzen der Deckkraft:
+error-reloading-failed = Fehler beim Neuladen der fehlgeschlagenen Ziele:
+error-loading-script = Fehler beim Laden des Init-Skripts:
+error-transforming-palette = Fehler beim Umwandeln der Palette von { $colorscheme }:
+error-reloading-target = Fehler beim Neuladen des { $target }-Farbschemas:
+error-writing-audit-log = Fehler beim Schreiben des Änderungsprotokolls:
+error-rendering-template = Fehler beim Erzeugen der Vorlage { $template }:
+error-updating-terminal = Fehler beim Aktualisieren der Terminalfarben:
+error-saving-results = Fehler beim Speichern der Ergebnisse des Neuladens:
+error-refreshing-status-bar = Fehler beim Aktualisieren der Statusleiste:
+error-setting-temperature = Fehler beim Setzen der Bildschirmtemperatur:
+error-running-hook = Fehler beim Ausführen des Hooks { $hook }:
+error-running-hooks = Fehler beim Ausführen der Hooks:
+
+no-previous-colorscheme = Kein vorheriges Farbschema
+no-matching-colorscheme = Kein passendes Farbschema
+no-present-colorscheme = Kein present_colorscheme in der Einstellungsdatei
+unknown-filter = Unbekannter Filter
+no-failed-targets = Beim Neuladen ist kein Ziel fehlgeschlagen
+
+# Messages printed by the other subcommands and the daemon.
+
+error-writing-completion = Fehler beim Schreiben des Vervollständigungsskripts:
+error-reading-stdin = Fehler beim Lesen des Farbschemas von stdin:
+error-reading-scheme-file = Fehler beim Lesen der Farbschemadatei { $file }:
+error-generating-hook = Fehler beim Erzeugen des Shell-Hooks:
+error-starting-daemon = Fehler beim Starten des Daemons:
+error-reading-schedule = Fehler beim Lesen des Zeitplans:
+error-reloading-settings = Fehler beim Neuladen der Einstellungen:
+error-dimming = Fehler beim Abdunkeln des Hintergrunds von { $colorscheme }:
+error-receiving-daemon-events = Fehler beim Empfangen der Daemon-Ereignisse
+error-listing-terminals = Fehler beim Auflisten der Terminalfenster:
+error-loading-colors = Fehler beim Laden der Farben von { $colorscheme }:
+error-recoloring-terminal = Fehler beim Umfärben des Terminals { $tty }:
+error-following-system = Fehler beim Folgen des Systemerscheinungsbilds:
+error-receiving-appearance = Fehler beim Empfangen des Systemerscheinungsbilds
+error-starting-schedule = Fehler beim Starten des Zeitplans:
+error-receiving-schedule = Fehler beim Empfangen des Zeitplans
+error-creating-snapshot = Fehler beim Erstellen der Vorschau von { $colorscheme }:
+error-comparing = Fehler beim Vergleichen von { $a } und { $b }:
+error-ending-comparison = Fehler beim Beenden des Vergleichs:
+error-creating-gallery = Fehler beim Erstellen der Galerie:
+error-prerendering = Fehler beim Vorberechnen von { $target } für { $colorscheme }:
+error-prerendering-all = Fehler beim Vorberechnen der Farbschemas:
+error-pinging = Fehler beim Erreichen von { $target }:
+error-listing-files = Fehler beim Auflisten der Dateien in: { $dir }
+error-listing-colorschemes = Fehler beim Auflisten der Farbschemas:
+error-suggesting = Fehler beim Vorschlagen von Farbschemas:
+error-searching = Fehler beim Durchsuchen der Farbschemas:
+error-migrating-all = Fehler beim Migrieren der Farbschemas:
+error-migrating = Fehler beim Migrieren von { $colorscheme }:
+error-reading-palette = Fehler beim Lesen der Palette von stdin:
+error-converting-palette = Fehler beim Umwandeln der { $from }-Palette in { $to }:
+error-adding-colorscheme = Fehler beim Hinzufügen des Farbschemas { $colorscheme }:
+error-importing-theme = Fehler beim Importieren des alacritty-Themes:
+error-updating = Fehler beim Aktualisieren von { $colorscheme }:
+error-listing-imported = Fehler beim Auflisten der importierten Farbschemas:
+error-fetching = Fehler beim Abrufen von { $colorscheme }:
+error-importing = Fehler beim Importieren von { $colorscheme }:
+error-writing-settings = Fehler beim Schreiben der Einstellungsdatei:
+error-generating-selector = Fehler beim Erzeugen des { $target }-Selektors:
+error-writing-selector = Fehler beim Schreiben des { $target }-Selektors:
+error-editing-selector = Fehler beim Bearbeiten des { $target }-Selektors:
+error-looking-for-themes = Fehler beim Suchen nach { $target }-Themes:
+error-loading-roles = Fehler beim Laden der Rollen von { $colorscheme }:
+error-loading = Fehler beim Laden von { $colorscheme }:
+error-installing-pack = Fehler beim Installieren des Pakets:
+error-exporting-pack = Fehler beim Exportieren des Pakets:
+error-getting-previous = Fehler beim Lesen des vorherigen Farbschemas:
+
+no-system-colorschemes = Keine Farbschemas in der Einstellungsdatei
+no-schedule-colorschemes = Kein Tag- und Nachtfarbschema in der Einstellungsdatei
+no-audit-entry = Kein Apply { $n } im Audit-Log
+only-remote-commands = Nur apply, toggle, list und status können an den Daemon gesendet werden
+
+reloaded-settings = Einstellungen neu geladen
+
+warning-live-reload = Warnung: live_config_reload ist in { $file } ausgeschaltet, laufende alacritty-Instanzen behalten ihre Farben bis zum Neustart
+warning-failed-targets = Warnung: { $targets } konnte nicht neu geladen werden, `alco reload --failed` versucht es erneut
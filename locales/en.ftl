@@ -0,0 +1,92 @@
+# Messages printed when applying, toggling and reloading colorschemes. The error messages end with a
+# colon, the cause is printed on the next line.
+
+error-applying-previous = Error applying previous colorscheme:
+error-applying = Error applying colorscheme { $colorscheme }:
+error-overriding-accent = Error overriding { $colorscheme } accent color:
+error-rendering = Error rendering { $target } colorscheme:
+error-getting-current = Error getting current colorscheme:
+error-toggling = Error toggling colorscheme:
+error-reloading = Error reloading colorscheme:
+error-present-on = Error turning on presentation mode:
+error-present-off = Error turning off presentation mode:
+error-setting-filter = Error setting filter { $filter }:
+error-setting-opacity = Error setting opacity:
+error-reloading-failed = Error reloading failed targets:
+error-loading-script = Error loading init script:
+error-transforming-palette = Error transforming { $colorscheme } palette:
+error-reloading-target = Error reloading { $target } colorscheme:
+error-writing-audit-log = Error writing audit log:
+error-rendering-template = Error rendering { $template } template:
+error-updating-terminal = Error updating terminal colors:
+error-saving-results = Error saving reload results:
+error-refreshing-status-bar = Error refreshing status bar:
+error-setting-temperature = Error setting screen temperature:
+error-running-hook = Error running hook { $hook }:
+error-running-hooks = Error running hooks:
+
+no-previous-colorscheme = No previous colorscheme
+no-matching-colorscheme = No matching colorscheme
+no-present-colorscheme = No present_colorscheme in settings file
+unknown-filter = Unknown filter
+no-failed-targets = No targets failed to reload
+
+# Messages printed by the other subcommands and the daemon.
+
+error-writing-completion = Error writing completion script:
+error-reading-stdin = Error reading colorscheme from stdin:
+error-reading-scheme-file = Error reading colorscheme file { $file }:
+error-generating-hook = Error generating shell hook:
+error-starting-daemon = Error starting daemon:
+error-reading-schedule = Error reading schedule:
+error-reloading-settings = Error reloading settings:
+error-dimming = Error dimming { $colorscheme } background:
+error-receiving-daemon-events = Error receiving daemon events
+error-listing-terminals = Error listing terminal windows:
+error-loading-colors = Error loading { $colorscheme } colors:
+error-recoloring-terminal = Error recoloring terminal { $tty }:
+error-following-system = Error following system appearance:
+error-receiving-appearance = Error receiving system appearance
+error-starting-schedule = Error starting schedule:
+error-receiving-schedule = Error receiving schedule
+error-creating-snapshot = Error creating snapshot of { $colorscheme }:
+error-comparing = Error comparing { $a } and { $b }:
+error-ending-comparison = Error ending comparison:
+error-creating-gallery = Error creating gallery:
+error-prerendering = Error prerendering { $target } for { $colorscheme }:
+error-prerendering-all = Error prerendering colorschemes:
+error-pinging = Error pinging { $target }:
+error-listing-files = Error listing files in dir: { $dir }
+error-listing-colorschemes = Error listing colorschemes:
+error-suggesting = Error suggesting colorschemes:
+error-searching = Error searching colorschemes:
+error-migrating-all = Error migrating colorschemes:
+error-migrating = Error migrating { $colorscheme }:
+error-reading-palette = Error reading palette from stdin:
+error-converting-palette = Error converting { $from } palette to { $to }:
+error-adding-colorscheme = Error adding colorscheme { $colorscheme }:
+error-importing-theme = Error importing alacritty theme:
+error-updating = Error updating { $colorscheme }:
+error-listing-imported = Error listing imported colorschemes:
+error-fetching = Error fetching { $colorscheme }:
+error-importing = Error importing { $colorscheme }:
+error-writing-settings = Error writing settings file:
+error-generating-selector = Error generating { $target } selector:
+error-writing-selector = Error writing { $target } selector:
+error-editing-selector = Error editing { $target } selector:
+error-looking-for-themes = Error looking for { $target } themes:
+error-loading-roles = Error loading the roles of { $colorscheme }:
+error-loading = Error loading { $colorscheme }:
+error-installing-pack = Error installing pack:
+error-exporting-pack = Error exporting pack:
+error-getting-previous = Error getting previous colorscheme:
+
+no-system-colorschemes = No colorschemes in settings file
+no-schedule-colorschemes = No day and night colorscheme in settings file
+no-audit-entry = No apply { $n } in the audit log
+only-remote-commands = Only apply, toggle, list and status can be sent to the daemon
+
+reloaded-settings = Reloaded settings
+
+warning-live-reload = Warning: live_config_reload is disabled in { $file }, running alacritty instances keep their colors until they are restarted
+warning-failed-targets = Warning: { $targets } failed to reload, run `alco reload --failed` to retry
@@ -4,9 +4,12 @@ use std::path::Path;
 use anyhow::{anyhow, bail};
 use shellexpand::tilde;
 use yaml_rust::parser::{MarkedEventReceiver, Parser};
-use yaml_rust::scanner::Marker;
+use yaml_rust::scanner::{Marker, TScalarStyle};
+use yaml_rust::yaml::Hash;
 use yaml_rust::{Event, Yaml, YamlLoader};
 
+use crate::Font;
+
 struct ColorEventReceiver<T> {
     listener: T,
 }
@@ -27,69 +30,132 @@ pub fn reload_alacritty(
     config_file: impl AsRef<Path>,
     in_file: impl AsRef<Path>,
     selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
     colorscheme: impl AsRef<str>,
+    only_colors: &[String],
 ) -> anyhow::Result<()> {
+    let config_str = render_alacritty(in_file, selector, scheme_dir, colorscheme, only_colors)?;
+    fs::write(config_file, config_str)?;
+    Ok(())
+}
+
+pub fn render_alacritty(
+    in_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+    only_colors: &[String],
+) -> anyhow::Result<String> {
     let selector_str = fs::read_to_string(selector.as_ref())
         .map_err(|_| anyhow!("Error reading alacritty selector"))?;
     let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
 
-    match super::selector(&selector, colorscheme.as_ref()) {
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
         Some(s) => {
-            apply(config_file, in_file, tilde(s).as_ref())?;
-            Ok(())
+            let mut new_colors = parse_colors(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading alacritty colorscheme file"))?;
+            if let Some(o) = super::target_section(scheme_dir, colorscheme.as_ref(), "alacritty")? {
+                merge(&mut new_colors, o);
+            }
+            if !only_colors.is_empty() {
+                new_colors = retain_keys(new_colors, &mut Vec::new(), only_colors)
+                    .unwrap_or(Yaml::Hash(Default::default()));
+            }
+
+            let input_str = fs::read_to_string(in_file.as_ref())
+                .map_err(|_| anyhow!("Alacritty input file not found"))?;
+
+            apply_to_str(&input_str, &new_colors)
         }
         None => bail!("Missing mapping in alacritty selector"),
     }
 }
 
-fn apply(
-    config_file: impl AsRef<Path>,
-    in_file: impl AsRef<Path>,
-    scheme_file: impl AsRef<str>,
-) -> anyhow::Result<()> {
-    let new_colors = parse_colors(scheme_file.as_ref())
-        .map_err(|_| anyhow!("Error reading alacritty colorscheme file"))?;
+/// Sets the `window.opacity` value of a rendered alacritty config. Like colors, the value is only
+/// replaced if the input file already has it.
+pub fn alacritty_opacity(config_str: &str, opacity: f32) -> anyhow::Result<String> {
+    let mut window = Hash::new();
+    window.insert(Yaml::String("opacity".to_owned()), Yaml::Real(format!("{:?}", opacity)));
+    let mut config = Hash::new();
+    config.insert(Yaml::String("window".to_owned()), Yaml::Hash(window));
+    apply_to_str(config_str, &Yaml::Hash(config))
+}
+
+/// Sets the `font.normal.family` and `font.size` values of a rendered alacritty config, if the
+/// input file has them.
+pub fn alacritty_font(config_str: &str, font: &Font) -> anyhow::Result<String> {
+    let mut values = Hash::new();
+    if let Some(family) = &font.family {
+        let mut normal = Hash::new();
+        normal.insert(Yaml::String("family".to_owned()), Yaml::String(family.clone()));
+        values.insert(Yaml::String("normal".to_owned()), Yaml::Hash(normal));
+    }
+    if let Some(size) = font.size {
+        values.insert(Yaml::String("size".to_owned()), Yaml::Real(format!("{:?}", size)));
+    }
+    let mut config = Hash::new();
+    config.insert(Yaml::String("font".to_owned()), Yaml::Hash(values));
+    apply_to_str(config_str, &Yaml::Hash(config))
+}
+
+/// Whether alacritty watches `config_file` for changes, which is required to apply a colorscheme
+/// to running instances. Alacritty enables `live_config_reload` by default.
+pub fn alacritty_live_reload(config_file: impl AsRef<Path>) -> bool {
+    let config_file = config_file.as_ref();
+    let config_str = match fs::read_to_string(config_file) {
+        Ok(s) => s,
+        Err(_) => return true,
+    };
 
-    let input_str = fs::read_to_string(in_file.as_ref())?;
+    let enabled = match config_file.extension().and_then(|e| e.to_str()) {
+        Some("toml") => config_str.parse::<toml::Table>().ok().and_then(|t| {
+            let general = t.get("general").and_then(|g| g.get("live_config_reload"));
+            general.or(t.get("live_config_reload"))?.as_bool()
+        }),
+        _ => YamlLoader::load_from_str(&config_str)
+            .ok()
+            .and_then(|y| y.into_iter().next()?["live_config_reload"].as_bool()),
+    };
+    enabled.unwrap_or(true)
+}
+
+/// Replaces all values in the alacritty config `input_str` that are also present in `new_colors`.
+/// The line endings of `input_str` and whether it ends with a newline are preserved.
+pub fn apply_to_str(input_str: &str, new_colors: &Yaml) -> anyhow::Result<String> {
     let input_lines = input_str.lines().collect::<Vec<_>>();
+    let newline = if input_str.contains("\r\n") { "\r\n" } else { "\n" };
     let mut config_str = String::new();
     let mut line_index = 0;
 
+    // the keys leading to the current line and the columns they start at
     let mut current_path: Vec<String> = Vec::new();
+    let mut indents: Vec<usize> = Vec::new();
     let mut last_line = 0;
-    let mut last_col = 0;
 
     let mut parser = Parser::new(input_str.chars());
     let mut receiver = ColorEventReceiver::new(|event, mark| {
-        if let Event::Scalar(name, _, _, _) = event {
+        if let Event::Scalar(name, style, _, _) = event {
             if mark.line() != last_line {
-                if mark.col() == last_col {
+                while indents.last().is_some_and(|i| *i >= mark.col()) {
+                    indents.pop();
                     current_path.pop();
-                    current_path.push(name);
-                    last_line = mark.line();
-                    last_col = mark.col();
-                } else if mark.col() == last_col + 2 {
-                    current_path.push(name);
-                    last_line = mark.line();
-                    last_col = mark.col();
-                } else if mark.col() < last_col {
-                    let indent = mark.col() / 2;
-                    for _ in indent..current_path.len() {
-                        current_path.pop();
-                    }
-                    current_path.push(name);
-                    last_line = mark.line();
-                    last_col = mark.col();
                 }
-            } else if let Some(v) = value(&new_colors, &current_path) {
-                if let Some(stringified) = stringify(v) {
+                current_path.push(name);
+                indents.push(mark.col());
+                last_line = mark.line();
+            } else if let Some(v) = value(new_colors, &current_path) {
+                let line = input_lines[mark.line() - 1];
+                let start = line.char_indices().nth(mark.col()).map_or(line.len(), |(i, _)| i);
+                let len = scalar_len(&line[start..], style);
+                if let (Some(len), Some(stringified)) = (len, stringify(v, &name, style)) {
                     for line in input_lines.iter().take(mark.line() - 1).skip(line_index) {
                         config_str.push_str(line);
-                        config_str.push('\n');
+                        config_str.push_str(newline);
                     }
-                    config_str.push_str(&input_lines[mark.line() - 1][0..mark.col()]);
+                    config_str.push_str(&line[..start]);
                     config_str.push_str(&stringified);
-                    config_str.push('\n');
+                    config_str.push_str(&line[start + len..]);
+                    config_str.push_str(newline);
                     line_index = mark.line();
                 }
             }
@@ -99,12 +165,14 @@ fn apply(
 
     for line in input_lines.iter().skip(line_index) {
         config_str.push_str(line);
-        config_str.push('\n');
+        config_str.push_str(newline);
     }
 
-    fs::write(config_file, config_str)?;
+    if !input_str.ends_with('\n') {
+        config_str.truncate(config_str.len() - newline.len().min(config_str.len()));
+    }
 
-    Ok(())
+    Ok(config_str)
 }
 
 fn parse_colors(file: impl AsRef<Path>) -> anyhow::Result<Yaml> {
@@ -118,6 +186,51 @@ fn parse_colors(file: impl AsRef<Path>) -> anyhow::Result<Yaml> {
     bail!("Error parsing colors")
 }
 
+/// Recursively merges `other` into `base`, values of `other` take precedence.
+fn merge(base: &mut Yaml, other: Yaml) {
+    match (base, other) {
+        (Yaml::Hash(b), Yaml::Hash(o)) => {
+            for (k, v) in o.into_iter() {
+                match b.get_mut(&k) {
+                    Some(e) => merge(e, v),
+                    None => {
+                        b.insert(k, v);
+                    }
+                }
+            }
+        }
+        (b, o) => *b = o,
+    }
+}
+
+/// Removes all values whose key path isn't matched by one of the `patterns`. A pattern like
+/// `colors.primary.*` matches dot separated key paths, `*` matches any single key, and all values
+/// below a matched key are kept.
+fn retain_keys(yaml: Yaml, path: &mut Vec<String>, patterns: &[String]) -> Option<Yaml> {
+    let matches = |pattern: &String| {
+        let keys: Vec<_> = pattern.split('.').collect();
+        keys.len() <= path.len() && keys.iter().zip(path.iter()).all(|(k, p)| *k == "*" || k == p)
+    };
+    if patterns.iter().any(matches) {
+        return Some(yaml);
+    }
+
+    match yaml {
+        Yaml::Hash(h) => {
+            let mut retained = Hash::new();
+            for (k, v) in h.into_iter() {
+                path.push(k.as_str().unwrap_or_default().to_owned());
+                if let Some(v) = retain_keys(v, path, patterns) {
+                    retained.insert(k, v);
+                }
+                path.pop();
+            }
+            (!retained.is_empty()).then_some(Yaml::Hash(retained))
+        }
+        _ => None,
+    }
+}
+
 fn value<'a>(yaml: &'a Yaml, path: &[String]) -> Option<&'a Yaml> {
     let mut current = yaml;
 
@@ -135,11 +248,155 @@ fn value<'a>(yaml: &'a Yaml, path: &[String]) -> Option<&'a Yaml> {
     Some(current)
 }
 
-fn stringify(value: &Yaml) -> Option<String> {
-    match value {
-        Yaml::String(s) => Some(format!("'{}'", s)),
-        Yaml::Integer(i) => Some(i.to_string()),
-        Yaml::Boolean(b) => Some(b.to_string()),
+/// The length in bytes of the scalar written in `style` at the start of `str`, excluding trailing
+/// whitespace and comments. `None` if the scalar doesn't end on the same line.
+fn scalar_len(str: &str, style: TScalarStyle) -> Option<usize> {
+    match style {
+        TScalarStyle::Plain => {
+            let end = str.match_indices(" #").chain(str.match_indices("\t#")).map(|(i, _)| i).min();
+            Some(str[..end.unwrap_or(str.len())].trim_end().len())
+        }
+        TScalarStyle::SingleQuoted => {
+            let mut chars = str.char_indices().skip(1).peekable();
+            while let Some((i, c)) = chars.next() {
+                match (c, chars.peek()) {
+                    // a single quote is escaped by doubling it
+                    ('\'', Some((_, '\''))) => {
+                        chars.next();
+                    }
+                    ('\'', _) => return Some(i + 1),
+                    _ => (),
+                }
+            }
+            None
+        }
+        TScalarStyle::DoubleQuoted => {
+            let mut chars = str.char_indices().skip(1);
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '"' => return Some(i + 1),
+                    _ => (),
+                }
+            }
+            None
+        }
         _ => None,
     }
 }
+
+/// Formats `value` so that it can replace the scalar `original` written in `style`. The quoting
+/// style is kept, as well as the `#` or `0x` notation of hex colors.
+fn stringify(value: &Yaml, original: &str, style: TScalarStyle) -> Option<String> {
+    let text = match value {
+        Yaml::String(s) => s.clone(),
+        // hex colors written as bare numbers, like `0x1d2021`, are parsed as integers
+        Yaml::Integer(i) if original.parse::<i64>().is_ok() => i.to_string(),
+        Yaml::Integer(i) => format!("0x{:06x}", i),
+        Yaml::Real(r) => r.clone(),
+        Yaml::Boolean(b) => b.to_string(),
+        _ => return None,
+    };
+
+    let text = match (hex_color(&text), hex_color(original)) {
+        (Some((_, digits)), Some((prefix, _))) => format!("{}{}", prefix, digits),
+        _ => text,
+    };
+
+    let quoted = match style {
+        TScalarStyle::Plain if is_plain(&text) => text,
+        TScalarStyle::DoubleQuoted => {
+            format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+        _ => format!("'{}'", text.replace('\'', "''")),
+    };
+    Some(quoted)
+}
+
+/// Splits a hex color in `#rrggbb` or `0xrrggbb` notation into its prefix and digits.
+fn hex_color(str: &str) -> Option<(&str, &str)> {
+    let prefix_len = match str.get(..2) {
+        Some("0x" | "0X") => 2,
+        _ if str.starts_with('#') => 1,
+        _ => return None,
+    };
+    let (prefix, digits) = str.split_at(prefix_len);
+    match digits.len() == 6 && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        true => Some((prefix, digits)),
+        false => None,
+    }
+}
+
+/// Whether `str` can be written as a plain scalar without being read as something else.
+fn is_plain(str: &str) -> bool {
+    const INDICATORS: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@',
+        '`',
+    ];
+    !str.is_empty()
+        && !str.starts_with(INDICATORS)
+        && !str.starts_with(char::is_whitespace)
+        && !str.ends_with(char::is_whitespace)
+        && !str.ends_with(':')
+        && !str.contains(": ")
+        && !str.contains(" #")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::path::Path;
+
+    use yaml_rust::YamlLoader;
+
+    /// Runs `apply_to_str` on every `<case>/input.yml` in `tests/fixtures/alacritty` using the
+    /// shared `colors.yml`, or `<case>/colors.yml` if present, and compares the result with
+    /// `<case>/expected.yml`. Setting `ALCO_BLESS=1` overwrites the expected files with the
+    /// current output instead.
+    #[test]
+    fn golden_files() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/alacritty");
+        let load_colors = |path: &Path| {
+            let colors_str = fs::read_to_string(path).unwrap();
+            YamlLoader::load_from_str(&colors_str).unwrap().remove(0)
+        };
+        let shared_colors = load_colors(&fixtures.join("colors.yml"));
+        let bless = env::var_os("ALCO_BLESS").is_some();
+
+        let mut cases = fs::read_dir(&fixtures)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .filter(|p| p.is_dir())
+            .collect::<Vec<_>>();
+        cases.sort();
+        assert!(!cases.is_empty(), "no golden files found");
+
+        let mut failed = Vec::new();
+        for case in cases {
+            let input = fs::read_to_string(case.join("input.yml")).unwrap();
+            let colors = match case.join("colors.yml") {
+                p if p.exists() => load_colors(&p),
+                _ => shared_colors.clone(),
+            };
+            let actual = super::apply_to_str(&input, &colors).unwrap();
+
+            let expected_file = case.join("expected.yml");
+            if bless {
+                fs::write(&expected_file, &actual).unwrap();
+                continue;
+            }
+
+            let expected = fs::read_to_string(&expected_file).unwrap_or_default();
+            if actual != expected {
+                let name = case.file_name().unwrap().to_string_lossy().into_owned();
+                eprintln!("{}:\n--- expected\n{:?}\n--- actual\n{:?}", name, expected, actual);
+                failed.push(name);
+            }
+        }
+
+        assert!(failed.is_empty(), "golden files differ: {}", failed.join(", "));
+    }
+}
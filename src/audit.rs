@@ -0,0 +1,109 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use shellexpand::tilde;
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::{Hunk, Options, Target};
+
+/// An apply recorded in the audit log, with the changes it made to the configuration files.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub colorscheme: String,
+    pub time: SystemTime,
+    pub changes: Vec<FileChange>,
+}
+
+/// The diff of a configuration file that was rewritten by an apply.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileChange {
+    pub target: Target,
+    pub path: PathBuf,
+    pub hunks: Vec<Hunk>,
+}
+
+/// The contents of the configuration files of the enabled targets, read before they are reloaded
+/// so [`log_changes`] can diff them.
+pub struct FileContents(Vec<(Target, PathBuf, String)>);
+
+/// The audit log inside `$XDG_STATE_HOME/alco`, or `~/.local/state/alco` if it isn't set.
+pub fn audit_log_path() -> PathBuf {
+    let dir = match env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("alco"),
+        _ => PathBuf::from(tilde(crate::DEFAULT_STATE_DIR).as_ref()),
+    };
+    dir.join("audit.log")
+}
+
+pub fn read_files(opts: &Options) -> FileContents {
+    let files = Target::ALL
+        .into_iter()
+        .filter(|t| opts.reload(*t))
+        .filter_map(|t| {
+            let path = opts.file(t)?;
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            Some((t, path, content))
+        })
+        .collect();
+    FileContents(files)
+}
+
+/// Diffs the configuration files against their contents `before` the apply and appends an entry
+/// to the audit log, one json object per line.
+pub fn log_changes(
+    log_file: impl AsRef<Path>,
+    colorscheme: &str,
+    before: FileContents,
+) -> anyhow::Result<AuditEntry> {
+    let changes = before
+        .0
+        .into_iter()
+        .filter_map(|(target, path, old)| {
+            let new = fs::read_to_string(&path).unwrap_or_default();
+            let hunks = crate::diff(&old, &new);
+            (!hunks.is_empty()).then_some(FileChange { target, path, hunks })
+        })
+        .collect();
+    let entry = AuditEntry {
+        colorscheme: colorscheme.to_owned(),
+        time: SystemTime::now(),
+        changes,
+    };
+
+    let log_file = log_file.as_ref();
+    if let Some(dir) = log_file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_file)
+        .map_err(|e| anyhow!("Error opening audit log {}:\n{}", log_file.display(), e))?;
+    writeln!(file, "{}", serde_json::to_string(&entry)?)?;
+    Ok(entry)
+}
+
+/// The entries of the audit log, the most recent one first.
+pub fn audit_entries(log_file: impl AsRef<Path>) -> anyhow::Result<Vec<AuditEntry>> {
+    let log_file = log_file.as_ref();
+    let log = match fs::read_to_string(log_file) {
+        Ok(l) => l,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(anyhow!("Error reading audit log {}:\n{}", log_file.display(), e)),
+    };
+    let mut entries = log
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .enumerate()
+        .map(|(i, l)| {
+            serde_json::from_str(l)
+                .map_err(|e| anyhow!("Error parsing line {} of the audit log:\n{}", i + 1, e))
+        })
+        .collect::<anyhow::Result<Vec<AuditEntry>>>()?;
+    entries.reverse();
+    Ok(entries)
+}
@@ -1,41 +1,96 @@
 use anyhow::anyhow;
 use anyhow::bail;
 
+use shellexpand::tilde;
 use yaml_rust::YamlLoader;
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
 pub fn reload_bat(
     config_file: impl AsRef<Path>,
     in_file: impl AsRef<Path>,
     selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let config = render_bat(in_file, &selector, &scheme_dir, &colorscheme)?;
+    fs::write(config_file.as_ref(), config)?;
+    notify_bat(config_file, selector, &scheme_dir, colorscheme)
+}
+
+/// If the selector maps the colorscheme to a custom `.tmTheme` file, copies it into the `themes`
+/// directory next to `config_file` and rebuilds bat's theme cache, so the theme can be used.
+pub fn notify_bat(
+    config_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
     colorscheme: impl AsRef<str>,
 ) -> anyhow::Result<()> {
     let selector_str =
         fs::read_to_string(selector.as_ref()).map_err(|_| anyhow!("Error reading bat selector"))?;
     let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
 
-    match super::selector(&selector, colorscheme.as_ref()) {
-        Some(s) => {
-            write_config(config_file, in_file, s)?;
+    let theme_file = match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) if is_theme_file(&s) => PathBuf::from(tilde(&s).as_ref()),
+        Some(_) => return Ok(()),
+        None => bail!("Missing mapping in bat selector"),
+    };
+    let theme = fs::read(&theme_file).map_err(|_| anyhow!("Error reading bat theme file"))?;
+
+    let themes_dir = config_file.as_ref().with_file_name("themes");
+    let target = themes_dir.join(theme_file.file_name().unwrap_or_default());
+    // rebuilding the cache takes a while
+    if fs::read(&target).ok().as_ref() == Some(&theme) {
+        return Ok(());
+    }
+    fs::create_dir_all(&themes_dir)?;
+    fs::write(&target, theme)?;
+
+    let output = Command::new("bat")
+        .args(["cache", "--build"])
+        .output()
+        .map_err(|e| anyhow!("Error running bat:\n{}", e))?;
+    if !output.status.success() {
+        bail!("Error building bat cache:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+pub fn render_bat(
+    in_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str =
+        fs::read_to_string(selector.as_ref()).map_err(|_| anyhow!("Error reading bat selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
 
-            Ok(())
-        }
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => render_config(in_file, s),
         None => bail!("Missing mapping in bat selector"),
     }
 }
 
-fn write_config(
-    config_file: impl AsRef<Path>,
+fn render_config(
     in_file: impl AsRef<Path>,
     colorscheme: impl AsRef<str>,
-) -> anyhow::Result<()> {
-    let input_str = fs::read_to_string(in_file.as_ref()).map_err(|_| anyhow!("Bat input file not found"))?;
+) -> anyhow::Result<String> {
+    let input_str =
+        fs::read_to_string(in_file.as_ref()).map_err(|_| anyhow!("Bat input file not found"))?;
 
-    let new_config = input_str.replace("<theme>", colorscheme.as_ref());
+    // custom themes are named after their file
+    let colorscheme = colorscheme.as_ref();
+    let theme = match is_theme_file(colorscheme) {
+        true => Path::new(colorscheme).file_stem().and_then(|s| s.to_str()).unwrap_or_default(),
+        false => colorscheme,
+    };
 
-    fs::write(config_file.as_ref(), new_config)?;
+    Ok(input_str.replace("<theme>", theme))
+}
 
-    Ok(())
+fn is_theme_file(mapping: &str) -> bool {
+    mapping.ends_with(".tmTheme")
 }
@@ -0,0 +1,184 @@
+use anyhow::{anyhow, bail};
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use shellexpand::tilde;
+
+use std::fs;
+
+use crate::DaemonConfig;
+
+/// An event of an ics calendar, `end` is exclusive.
+struct CalendarEvent {
+    summary: String,
+    start: NaiveDate,
+    end: NaiveDate,
+    yearly: bool,
+}
+
+impl CalendarEvent {
+    fn is_active(&self, date: NaiveDate) -> bool {
+        if !self.yearly {
+            return self.start <= date && date < self.end;
+        }
+
+        // also check last year's occurrence for events spanning the new year
+        let duration = self.end - self.start;
+        [date.year() - 1, date.year()].into_iter().any(|y| match self.start.with_year(y) {
+            Some(start) => start >= self.start && start <= date && date < start + duration,
+            None => false,
+        })
+    }
+}
+
+/// The colorscheme that is pinned to today by the `dates` or the calendar of the daemon settings.
+/// Dates take precedence over calendar events.
+pub fn pinned_colorscheme(settings: &DaemonConfig) -> anyhow::Result<Option<String>> {
+    let today = Local::now().date_naive();
+
+    for (date, colorscheme) in settings.dates.iter() {
+        if matches_date(date, today)? {
+            return Ok(Some(colorscheme.clone()));
+        }
+    }
+
+    let file = match &settings.calendar.file {
+        Some(f) => f,
+        None => return Ok(None),
+    };
+    let calendar_str = fs::read_to_string(tilde(file).as_ref())
+        .map_err(|_| anyhow!("Error reading calendar file"))?;
+    for event in parse_calendar(&calendar_str) {
+        if !event.is_active(today) {
+            continue;
+        }
+        if let Some(c) = settings.calendar.events.get(&event.summary) {
+            return Ok(Some(c.clone()));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Matches dates in the `month-day` or `year-month-day` format.
+fn matches_date(date: &str, today: NaiveDate) -> anyhow::Result<bool> {
+    let full = NaiveDate::parse_from_str(date, "%Y-%m-%d");
+    let yearly = NaiveDate::parse_from_str(&format!("{}-{}", today.year(), date), "%Y-%m-%d");
+    match (full, yearly) {
+        (Ok(d), _) => Ok(d == today),
+        (_, Ok(d)) => Ok(d == today),
+        // february 29th in other years
+        _ if date.trim() == "02-29" => Ok(false),
+        _ => bail!("Invalid date {}", date),
+    }
+}
+
+/// Parses the events of an ics calendar. Only the `DTSTART`, `DTEND` and `SUMMARY` properties and
+/// yearly recurrence rules are supported, times are ignored.
+fn parse_calendar(calendar: &str) -> Vec<CalendarEvent> {
+    // long lines are folded by inserting a line break followed by a space or tab
+    let mut lines: Vec<String> = Vec::new();
+    for line in calendar.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continued), Some(last)) => last.push_str(continued),
+            _ => lines.push(line.to_owned()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut summary = None;
+    let mut start = None;
+    let mut end = None;
+    let mut yearly = false;
+    for line in lines.iter() {
+        let (name, value) = match line.split_once(':') {
+            Some((n, v)) => (n.split(';').next().unwrap_or(n), v),
+            None => continue,
+        };
+        match (name, value) {
+            ("BEGIN", "VEVENT") => {
+                summary = None;
+                start = None;
+                end = None;
+                yearly = false;
+            }
+            ("SUMMARY", v) => summary = Some(v.replace("\\,", ",").replace("\\;", ";")),
+            ("DTSTART", v) => start = parse_date(v),
+            ("DTEND", v) => {
+                // dates are exclusive, while times end on the day they are in
+                let timed = v.contains('T');
+                end = parse_date(v).map(|d| if timed { d + Duration::days(1) } else { d });
+            }
+            ("RRULE", v) => yearly = v.split(';').any(|p| p == "FREQ=YEARLY"),
+            ("END", "VEVENT") => {
+                if let (Some(summary), Some(start)) = (summary.take(), start) {
+                    let end = end.filter(|e| *e > start).unwrap_or(start + Duration::days(1));
+                    events.push(CalendarEvent { summary, start, end, yearly });
+                }
+            }
+            _ => (),
+        }
+    }
+
+    events
+}
+
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..8)?, "%Y%m%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn parse_event(properties: &str) -> CalendarEvent {
+        let calendar = format!(
+            "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\n{}END:VEVENT\r\nEND:VCALENDAR\r\n",
+            properties
+        );
+        let mut events = parse_calendar(&calendar);
+        assert_eq!(events.len(), 1);
+        events.remove(0)
+    }
+
+    #[test]
+    fn all_day_event() {
+        let event = parse_event(concat!(
+            "SUMMARY:Christmas\r\n",
+            "DTSTART;VALUE=DATE:20261224\r\n",
+            "DTEND;VALUE=DATE:20261226\r\n",
+            "RRULE:FREQ=YEARLY\r\n",
+        ));
+        assert_eq!(event.summary, "Christmas");
+        assert_eq!((event.start, event.end), (date(2026, 12, 24), date(2026, 12, 26)));
+        assert!(event.is_active(date(2027, 12, 25)));
+        assert!(!event.is_active(date(2027, 12, 26)));
+    }
+
+    #[test]
+    fn timed_event() {
+        let event = parse_event(concat!(
+            "SUMMARY:Halloween\r\n",
+            "DTSTART;TZID=Europe/Berlin:20261031T200000\r\n",
+            "DTEND;TZID=Europe/Berlin:20261031T235900\r\n",
+        ));
+        assert_eq!((event.start, event.end), (date(2026, 10, 31), date(2026, 11, 1)));
+
+        let utc = parse_event("SUMMARY:Standup\r\nDTSTART:20261102T090000Z\r\n");
+        assert_eq!((utc.start, utc.end), (date(2026, 11, 2), date(2026, 11, 3)));
+    }
+
+    #[test]
+    fn folded_line() {
+        let event = parse_event(concat!(
+            "SUMMARY:Movie night\\, with\r\n",
+            "  friends\r\n",
+            "DTSTART;VALUE=DATE:2026\r\n",
+            "\t1107\r\n",
+        ));
+        assert_eq!(event.summary, "Movie night, with friends");
+        assert_eq!(event.start, date(2026, 11, 7));
+    }
+}
@@ -0,0 +1,23 @@
+use chrono::{Local, Timelike};
+
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+use crate::Event;
+
+/// Sends an [`Event::Hour`] with the current local hour right away and then at the start of
+/// every hour.
+pub fn watch_clock(sender: Sender<Event>) -> anyhow::Result<()> {
+    thread::spawn(move || loop {
+        let now = Local::now();
+        if sender.send(Event::Hour(now.hour())).is_err() {
+            break;
+        }
+
+        let elapsed = now.minute() * 60 + now.second();
+        thread::sleep(Duration::from_secs((3600 - elapsed) as u64));
+    });
+
+    Ok(())
+}
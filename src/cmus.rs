@@ -8,28 +8,65 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
 
-const CMUS_AUTOSAVE_FILE: &str = "~/.config/cmus/autosave";
+use crate::CMUS_AUTOSAVE_FILE;
 
-pub fn reload_cmus(selector: impl AsRef<Path>, colorscheme: impl AsRef<str>) -> anyhow::Result<()> {
+pub fn reload_cmus(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let autosave_str = render_cmus(selector.as_ref(), &scheme_dir, colorscheme.as_ref())?;
+    notify_cmus(selector, &scheme_dir, colorscheme)?;
+    fs::write(tilde(CMUS_AUTOSAVE_FILE).as_ref(), autosave_str)?;
+
+    Ok(())
+}
+
+/// Makes a running cmus instance switch to the colorscheme mapped by the selector.
+pub fn notify_cmus(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
     let selector_str = fs::read_to_string(selector.as_ref())
         .map_err(|_| anyhow!("Error reading cmus selector"))?;
     let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
 
-    match super::selector(&selector, colorscheme.as_ref()) {
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
         Some(s) => {
-            Command::new("cmus-remote").arg("-C").arg(&format!("colorscheme {}", s)).output()?;
-
-            write_autosave(s)?;
-
+            Command::new("cmus-remote").arg("-C").arg(format!("colorscheme {}", s)).output()?;
             Ok(())
         }
         None => bail!("Missing mapping in cmus selector"),
     }
 }
 
-fn write_autosave(cmus_scheme: impl AsRef<str>) -> anyhow::Result<()> {
-    let autosave_path = tilde(CMUS_AUTOSAVE_FILE);
-    let original_autosave_str = fs::read_to_string(autosave_path.as_ref())?;
+/// Checks whether cmus is running.
+pub fn ping_cmus() -> anyhow::Result<()> {
+    let output = Command::new("cmus-remote").arg("-Q").output()?;
+    if !output.status.success() {
+        bail!("Cmus is not running");
+    }
+    Ok(())
+}
+
+pub fn render_cmus(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading cmus selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => render_autosave(s),
+        None => bail!("Missing mapping in cmus selector"),
+    }
+}
+
+fn render_autosave(cmus_scheme: impl AsRef<str>) -> anyhow::Result<String> {
+    let original_autosave_str = fs::read_to_string(tilde(CMUS_AUTOSAVE_FILE).as_ref())?;
     let new_scheme_str: String;
 
     let local_path =
@@ -71,7 +108,5 @@ fn write_autosave(cmus_scheme: impl AsRef<str>) -> anyhow::Result<()> {
         }
     }
 
-    fs::write(autosave_path.as_ref(), new_autosave_str)?;
-
-    Ok(())
+    Ok(new_autosave_str)
 }
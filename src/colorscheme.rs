@@ -0,0 +1,414 @@
+use anyhow::{anyhow, bail};
+use serde::Deserialize;
+use shellexpand::tilde;
+use yaml_rust::{Yaml, YamlLoader};
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::Options;
+
+/// The latest version of the scheme file format.
+pub const SCHEME_VERSION: i64 = 2;
+
+/// The names of the normal and bright colors in alacritty's `colors` section, in ansi order.
+pub const COLOR_NAMES: [&str; 8] =
+    ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// The perceptual distance to another color, the euclidean distance in the CIELAB color
+    /// space. A distance of around 2 is barely noticeable, 100 is the distance between black and
+    /// white.
+    pub fn distance(&self, other: &Color) -> f32 {
+        let (l1, a1, b1) = self.lab();
+        let (l2, a2, b2) = other.lab();
+        ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+    }
+
+    fn lab(&self) -> (f32, f32, f32) {
+        let linear = |c: u8| {
+            let c = c as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = (linear(self.r), linear(self.g), linear(self.b));
+
+        // xyz relative to the D65 white point
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+        let f = |t: f32| if t > 0.008856 { t.cbrt() } else { 7.787 * t + 16.0 / 116.0 };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+}
+
+impl FromStr for Color {
+    type Err = anyhow::Error;
+
+    /// Parses the `#rrggbb` and `0xrrggbb` notations.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let hex = s.strip_prefix('#').or_else(|| s.strip_prefix("0x")).unwrap_or(s);
+        if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            bail!("Invalid color {}", s);
+        }
+
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap();
+        Ok(Self::new(channel(0), channel(2), channel(4)))
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// The terminal colors of a colorscheme.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Colorscheme {
+    pub foreground: Option<Color>,
+    pub background: Option<Color>,
+    pub cursor: Option<Color>,
+    pub normal: [Option<Color>; 8],
+    pub bright: [Option<Color>; 8],
+}
+
+impl Colorscheme {
+    /// Reads the colors from an alacritty `colors` section.
+    pub fn from_yaml(colors: &Yaml) -> Self {
+        let color = |section: &str, key: &str| colors[section][key].as_str()?.parse().ok();
+        Self {
+            foreground: color("primary", "foreground"),
+            background: color("primary", "background"),
+            cursor: color("cursor", "cursor"),
+            normal: COLOR_NAMES.map(|n| color("normal", n)),
+            bright: COLOR_NAMES.map(|n| color("bright", n)),
+        }
+    }
+
+    /// Reads the colors from the `colors` section of a v2 scheme file.
+    pub fn from_v2_yaml(colors: &Yaml) -> Self {
+        let color = |c: &Yaml| c.as_str()?.parse().ok();
+        Self {
+            foreground: color(&colors["foreground"]),
+            background: color(&colors["background"]),
+            cursor: color(&colors["cursor"]),
+            normal: COLOR_NAMES.map(|n| color(&colors["normal"][n])),
+            bright: COLOR_NAMES.map(|n| color(&colors["bright"][n])),
+        }
+    }
+
+    /// The 16 ansi colors, the normal ones followed by the bright ones.
+    pub fn ansi(&self) -> impl Iterator<Item = Option<Color>> + '_ {
+        self.normal.iter().chain(self.bright.iter()).copied()
+    }
+
+    /// All colors that are set, together with their names.
+    pub fn colors(&self) -> impl Iterator<Item = (String, Color)> + '_ {
+        let named = [
+            ("foreground", self.foreground),
+            ("background", self.background),
+            ("cursor", self.cursor),
+        ];
+        let ansi = self.ansi().enumerate().map(|(i, c)| {
+            let prefix = if i < 8 { "" } else { "bright " };
+            (format!("{}{}", prefix, COLOR_NAMES[i % 8]), c)
+        });
+        named
+            .into_iter()
+            .map(|(n, c)| (n.to_owned(), c))
+            .chain(ansi)
+            .filter_map(|(n, c)| Some((n, c?)))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Descriptive information from the `meta` section of a scheme file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SchemeMeta {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    /// Whether the colorscheme is `dark` or `light`.
+    pub variant: Option<String>,
+    /// Free-form labels like `high-contrast` or `warm`.
+    pub tags: Vec<String>,
+    /// Disabled colorschemes are hidden by `list` and skipped by `toggle`.
+    pub disabled: bool,
+    pub source: Option<SchemeSource>,
+}
+
+/// Where an imported colorscheme came from, so it can be updated from there.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemeSource {
+    /// The format the colorscheme was imported from, like `alacritty-theme`.
+    pub format: String,
+    /// The url or absolute path of the imported file.
+    pub url: String,
+    /// The date of the import in the `year-month-day` format.
+    pub date: Option<String>,
+}
+
+impl SchemeMeta {
+    fn from_yaml(meta: &Yaml) -> Self {
+        let string = |y: &Yaml, k: &str| y[k].as_str().map(str::to_owned);
+        let source = &meta["source"];
+        let source = match (string(source, "format"), string(source, "url")) {
+            (Some(format), Some(url)) => {
+                Some(SchemeSource { format, url, date: string(source, "date") })
+            }
+            _ => None,
+        };
+        Self {
+            name: string(meta, "name"),
+            author: string(meta, "author"),
+            variant: string(meta, "variant"),
+            tags: meta["tags"]
+                .as_vec()
+                .map(|t| t.iter().filter_map(|t| t.as_str().map(str::to_owned)).collect())
+                .unwrap_or_default(),
+            disabled: meta["disabled"].as_bool().unwrap_or(false),
+            source,
+        }
+    }
+}
+
+/// A colorscheme file in the scheme directory.
+///
+/// Version 1 files contain an alacritty `colors` section, version 2 files are marked with
+/// `version: 2` and contain a `meta` section and a flat `colors` section:
+/// ```yaml
+/// version: 2
+/// meta:
+///   name: Dracula
+///   variant: dark
+///   tags: [high-contrast]
+/// colors:
+///   foreground: "#f8f8f2"
+///   background: "#282a36"
+///   normal:
+///     red: "#ff5555"
+/// ```
+/// Both versions can contain a `targets` section with per-target overrides and a `font` section.
+/// Imported version 1 files also contain a `meta` section recording their source.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SchemeFile {
+    pub version: i64,
+    pub meta: SchemeMeta,
+    pub colors: Colorscheme,
+}
+
+impl SchemeFile {
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let yaml = read_yaml(path).map_err(|_| anyhow!("Error reading colorscheme file"))?;
+        Self::parse(&yaml)
+    }
+
+    pub fn parse(yaml: &Yaml) -> anyhow::Result<Self> {
+        let version = match &yaml["version"] {
+            Yaml::BadValue => 1,
+            Yaml::Integer(v @ (1 | 2)) => *v,
+            Yaml::Integer(v) => bail!("Unsupported colorscheme file version {}", v),
+            _ => bail!("Invalid colorscheme file version"),
+        };
+
+        let meta = SchemeMeta::from_yaml(&yaml["meta"]);
+        let colors = match version {
+            1 => Colorscheme::from_yaml(&yaml["colors"]),
+            _ => Colorscheme::from_v2_yaml(&yaml["colors"]),
+        };
+        Ok(Self { version, meta, colors })
+    }
+}
+
+/// Shifts colors to make them warmer and darker or brighter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Filter {
+    /// Shifts colors towards red if positive or towards blue if negative, between -1 and 1.
+    pub warmth: f32,
+    /// Darkens colors if negative or brightens them if positive, between -1 and 1.
+    pub brightness: f32,
+}
+
+impl Filter {
+    pub fn apply(&self, color: Color) -> Color {
+        let scale = |channel: u8, factor: f32| {
+            let factor = factor * (1.0 + self.brightness);
+            (channel as f32 * factor).round().clamp(0.0, 255.0) as u8
+        };
+        Color::new(
+            scale(color.r, 1.0 + 0.1 * self.warmth),
+            scale(color.g, 1.0 - 0.1 * self.warmth),
+            scale(color.b, 1.0 - 0.4 * self.warmth),
+        )
+    }
+}
+
+impl Colorscheme {
+    pub fn map(&self, f: impl Fn(Color) -> Color) -> Self {
+        let f = |c: Option<Color>| c.map(&f);
+        Self {
+            foreground: f(self.foreground),
+            background: f(self.background),
+            cursor: f(self.cursor),
+            normal: self.normal.map(f),
+            bright: self.bright.map(f),
+        }
+    }
+}
+
+/// Maps all `#rrggbb` and `0xrrggbb` colors in a rendered configuration file using `f`.
+pub fn map_colors(text: &str, f: impl Fn(Color) -> Color) -> String {
+    let mut mapped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find(['#', '0']) {
+        mapped.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        let prefix_len = match rest.as_bytes() {
+            [b'#', ..] => 1,
+            [b'0', b'x', ..] => 2,
+            _ => 0,
+        };
+        let word = &rest[prefix_len..];
+        // the color must not be followed by other word characters
+        let word_len = word.bytes().take_while(|b| b.is_ascii_alphanumeric() || *b == b'_').count();
+
+        match word[..word_len.min(6)].parse::<Color>() {
+            Ok(c) if prefix_len > 0 && word_len == 6 => {
+                let hex = f(c).to_string();
+                mapped.push_str(&rest[..prefix_len]);
+                mapped.push_str(&hex[1..]);
+                rest = &rest[prefix_len + 6..];
+            }
+            _ => {
+                mapped.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    mapped.push_str(rest);
+    mapped
+}
+
+/// Loads the colors of a colorscheme from its scheme file in either format version, or if it
+/// doesn't contain any, from the file the alacritty selector maps the colorscheme to.
+pub fn load_colorscheme(opts: &Options, colorscheme: &str) -> anyhow::Result<Colorscheme> {
+    let scheme_file = crate::scheme_file(&opts.scheme_dir, colorscheme);
+    if scheme_file.exists() {
+        let scheme = SchemeFile::load(&scheme_file)?.colors;
+        if !scheme.is_empty() {
+            return Ok(scheme);
+        }
+    }
+
+    let selector = read_yaml(&opts.alacritty.selector)
+        .map_err(|_| anyhow!("Error reading alacritty selector"))?;
+    match crate::selector(&selector, &opts.scheme_dir, colorscheme) {
+        Some(s) => {
+            let colors = read_yaml(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading alacritty colorscheme file"))?;
+            Ok(Colorscheme::from_yaml(&colors["colors"]))
+        }
+        None => bail!("No colors found for {}", colorscheme),
+    }
+}
+
+/// Writes a scheme file that isn't part of the scheme directory, like one piped to
+/// `alco apply --stdin`, to `<cache dir>/adhoc/` and makes it the scheme directory of `opts`, so
+/// the colorscheme can be applied like an installed one. The colorscheme is named after
+/// `meta.name`, or `fallback_name` if it isn't set. Returns the name.
+pub fn load_adhoc_scheme(
+    opts: &mut Options,
+    scheme: &str,
+    fallback_name: &str,
+) -> anyhow::Result<String> {
+    let yaml = YamlLoader::load_from_str(scheme)
+        .map_err(|e| anyhow!("Error parsing colorscheme file:\n{}", e))?
+        .into_iter()
+        .next()
+        .unwrap_or(Yaml::Null);
+    let scheme_file = SchemeFile::parse(&yaml)?;
+    if scheme_file.colors.is_empty() {
+        bail!("No colors in colorscheme file");
+    }
+
+    let name = scheme_file.meta.name.unwrap_or_else(|| fallback_name.to_owned());
+    let name = name.replace(['/', '\0'], "-");
+    let dir = opts.cache_dir.join("adhoc");
+    fs::create_dir_all(&dir)?;
+    fs::write(crate::scheme_file(&dir, &name), scheme)?;
+    opts.scheme_dir = dir;
+
+    Ok(name)
+}
+
+/// A colorscheme containing a color near the searched one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FoundColor {
+    pub colorscheme: String,
+    /// The name of the nearest color in the colorscheme.
+    pub name: String,
+    pub color: Color,
+    pub distance: f32,
+}
+
+/// Searches all colorschemes in the colors file for colors within `tolerance` of `color`, or only
+/// their background colors if `background` is set. Returns the nearest color of every matching
+/// colorscheme, sorted by distance. Colorschemes whose colors couldn't be loaded are skipped.
+pub fn find_colorschemes(
+    opts: &Options,
+    colors_file: impl AsRef<Path>,
+    color: Color,
+    tolerance: f32,
+    background: bool,
+) -> anyhow::Result<Vec<FoundColor>> {
+    let mut found = Vec::new();
+    for colorscheme in crate::list(colors_file)? {
+        let scheme = match load_colorscheme(opts, &colorscheme) {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+
+        let nearest = scheme
+            .colors()
+            .filter(|(n, _)| !background || n == "background")
+            .map(|(n, c)| (n, c, c.distance(&color)))
+            .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+        if let Some((name, color, distance)) = nearest {
+            if distance <= tolerance {
+                found.push(FoundColor { colorscheme, name, color, distance });
+            }
+        }
+    }
+    found.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+    Ok(found)
+}
+
+fn read_yaml(path: impl AsRef<Path>) -> anyhow::Result<Yaml> {
+    let yaml_str = fs::read_to_string(path.as_ref())?;
+    Ok(YamlLoader::load_from_str(&yaml_str)?.into_iter().next().unwrap_or(Yaml::Null))
+}
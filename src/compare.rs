@@ -0,0 +1,128 @@
+use std::env;
+
+use crate::snapshot::{COLUMNS, FG, MOCK};
+use crate::{Color, Colorscheme, Options};
+
+/// How two colorschemes are shown next to each other.
+pub enum Comparison {
+    /// Both samples in true color, printed as two columns.
+    SideBySide(String),
+    /// The current tmux pane and a pane split off from it are recolored, each showing a sample in
+    /// the indexed colors of the pane. The sample of the current pane still has to be printed.
+    TmuxPanes { pane: String, split: String, sample: String },
+}
+
+/// Shows a terminal mock and the ansi colors of two colorschemes next to each other. Inside tmux
+/// the current pane is split and both panes are recolored, so the colorschemes are compared in the
+/// actual terminal colors. Otherwise the samples are rendered in true color.
+pub fn compare(opts: &Options, a: &str, b: &str) -> anyhow::Result<Comparison> {
+    let scheme_a = crate::load_colorscheme(opts, a)?.map(|c| opts.map_color(c));
+    let scheme_b = crate::load_colorscheme(opts, b)?.map(|c| opts.map_color(c));
+
+    match env::var("TMUX_PANE") {
+        Ok(pane) if !crate::is_ssh_session() => {
+            let sample_b = indexed_sample(b);
+            let command = ["sh", "-c", "printf '%s' \"$1\"; read _", "sh", &sample_b];
+            let split = crate::tmux::split_tmux_pane(&pane, &command)?;
+            crate::style_tmux_pane(&split, &scheme_b)?;
+            crate::style_tmux_pane(&pane, &scheme_a)?;
+            Ok(Comparison::TmuxPanes { pane, split, sample: indexed_sample(a) })
+        }
+        _ => {
+            let left = truecolor_sample(a, &scheme_a);
+            let right = truecolor_sample(b, &scheme_b);
+            let lines: String =
+                left.iter().zip(right.iter()).map(|(l, r)| format!("{}  {}\n", l, r)).collect();
+            Ok(Comparison::SideBySide(lines))
+        }
+    }
+}
+
+/// Closes the split pane and restores the colors of the current pane.
+pub fn end_comparison(comparison: &Comparison) -> anyhow::Result<()> {
+    if let Comparison::TmuxPanes { pane, split, .. } = comparison {
+        crate::tmux::kill_tmux_pane(split)?;
+        crate::tmux::unstyle_tmux_pane(pane)?;
+    }
+    Ok(())
+}
+
+fn indexed_sample(name: &str) -> String {
+    let sgr = |i: usize, bg: bool| {
+        let code = match i {
+            FG => 39,
+            0..=7 => 30 + i,
+            _ => 90 + i - 8,
+        };
+        format!("\x1b[{}m", if bg { code + 10 } else { code })
+    };
+    let mut sample = sample(name, "\x1b[39;49m", sgr).join("\n");
+    sample.push('\n');
+    sample
+}
+
+fn truecolor_sample(name: &str, colorscheme: &Colorscheme) -> Vec<String> {
+    let fg = colorscheme.foreground.unwrap_or(Color::new(0xff, 0xff, 0xff));
+    let bg = colorscheme.background.unwrap_or(Color::new(0x00, 0x00, 0x00));
+    let ansi: Vec<_> = colorscheme.ansi().map(|c| c.unwrap_or(fg)).collect();
+    let rgb = |c: Color, bg: bool| {
+        format!("\x1b[{};2;{};{};{}m", if bg { 48 } else { 38 }, c.r, c.g, c.b)
+    };
+    let sgr = |i: usize, bg: bool| rgb(if i == FG { fg } else { ansi[i] }, bg);
+    let base = format!("{}{}", rgb(fg, false), rgb(bg, true));
+    sample(name, &base, sgr)
+}
+
+/// The lines of a sample, each padded to the same width and starting with the `base` colors. `sgr`
+/// returns the escape sequence that sets the foreground, or background, to the color at an index.
+fn sample(name: &str, base: &str, sgr: impl Fn(usize, bool) -> String) -> Vec<String> {
+    let line = |content: String, columns: usize| {
+        let padding = " ".repeat(COLUMNS.saturating_sub(columns) + 1);
+        format!("{} {}{}{}\x1b[0m", base, content, base, padding)
+    };
+
+    let name: String = name.chars().take(COLUMNS).collect();
+    let columns = name.chars().count();
+    let mut lines = vec![line(format!("\x1b[1m{}\x1b[22m", name), columns), line(String::new(), 0)];
+    for mock in MOCK {
+        let content = mock.iter().map(|(c, text)| format!("{}{}", sgr(*c, false), text)).collect();
+        let columns = mock.iter().map(|(_, text)| text.chars().count()).sum();
+        lines.push(line(content, columns));
+    }
+    lines.push(line(String::new(), 0));
+    for row in [0..8, 8..16] {
+        let swatches = row.map(|i| format!("{}   ", sgr(i, true))).collect();
+        lines.push(line(swatches, 24));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn width(line: &str) -> usize {
+        let mut escape = false;
+        line.chars()
+            .filter(|c| match (escape, c) {
+                (false, '\x1b') => {
+                    escape = true;
+                    false
+                }
+                (true, 'm') => {
+                    escape = false;
+                    false
+                }
+                (escape, _) => !escape,
+            })
+            .count()
+    }
+
+    #[test]
+    fn equal_widths() {
+        let lines = truecolor_sample("gruvbox-dark", &Colorscheme::default());
+        assert_eq!(lines.len(), MOCK.len() + 5);
+        assert!(lines.iter().all(|l| width(l) == COLUMNS + 2));
+        assert!(indexed_sample("nord").lines().all(|l| width(l) == COLUMNS + 2));
+    }
+}
@@ -0,0 +1,569 @@
+use anyhow::{anyhow, bail};
+use serde::Deserialize;
+use shellexpand::tilde;
+use toml_edit::ImDocument;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use crate::{Filter, Font, Options, Strategy, Target};
+
+/// Alco's settings, which are read from a toml file.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The directory containing the scheme files.
+    pub scheme_dir: Option<String>,
+    /// The directory containing the `<name>.tmpl` files of the templates.
+    pub template_dir: Option<String>,
+    /// Templates that are rendered with the colors of the colorscheme on every apply, by name.
+    pub templates: BTreeMap<String, TemplateConfig>,
+    /// Rules that derive the colors of roles like `accent` from the colors of a colorscheme, by
+    /// role name.
+    pub roles: BTreeMap<String, String>,
+    /// The directory containing the `post-apply.d` directory of hook scripts.
+    pub hook_dir: Option<String>,
+    /// Targets that are reloaded by default, in addition to the ones passed as flags.
+    pub reload: Vec<Target>,
+    /// Append the changes every apply makes to the configuration files to the audit log.
+    pub audit_log: bool,
+    pub alacritty: TargetConfig,
+    pub kitty: TargetConfig,
+    pub tmux: TargetConfig,
+    pub neovim: TargetConfig,
+    pub starship: TargetConfig,
+    pub bat: TargetConfig,
+    pub delta: TargetConfig,
+    pub cmus: TargetConfig,
+    pub wezterm: TargetConfig,
+    pub foot: TargetConfig,
+    pub zellij: TargetConfig,
+    pub fish: TargetConfig,
+    pub fzf: TargetConfig,
+    pub rofi: TargetConfig,
+    pub dunst: TargetConfig,
+    pub waybar: TargetConfig,
+    pub polybar: TargetConfig,
+    pub picom: TargetConfig,
+    pub i3: TargetConfig,
+    pub hyprland: TargetConfig,
+    pub gtk: TargetConfig,
+    pub qutebrowser: TargetConfig,
+    pub vscode: TargetConfig,
+    pub zathura: TargetConfig,
+    pub mako: TargetConfig,
+    pub ghostty: TargetConfig,
+    pub konsole: TargetConfig,
+    pub emacs: TargetConfig,
+    pub vim: TargetConfig,
+    pub xresources: TargetConfig,
+    /// The maximum number of targets that are reloaded simultaneously, unlimited if `None`.
+    pub max_parallel: Option<usize>,
+    /// Check that rewritten configuration files can be parsed before reloading the targets.
+    pub safe: bool,
+    /// Update the terminal alco is run in, even if its target isn't selected. On by default.
+    pub detect_terminal: Option<bool>,
+    /// Patterns of the alacritty color keys that are rewritten, like `colors.primary.*`, all if
+    /// empty.
+    pub only_colors: Vec<String>,
+    /// Status bars that are refreshed after a colorscheme was applied.
+    pub refresh: RefreshConfig,
+    /// The screen temperature that is set together with a colorscheme.
+    pub gamma: GammaConfig,
+    /// The high contrast colorscheme that is applied by `alco present on`.
+    pub present_colorscheme: Option<String>,
+    pub follow_system: FollowSystemConfig,
+    pub schedule: ScheduleConfig,
+    /// Color filters that can be turned on using `alco filter <name> on`.
+    pub filters: BTreeMap<String, Filter>,
+    pub daemon: DaemonConfig,
+    /// Named sets of targets and options, which can be selected using `--profile`.
+    pub profiles: BTreeMap<String, Profile>,
+    /// The selectors and input files named in the settings that don't exist, which are only
+    /// reported once their target is reloaded, see [`Config::check_targets`].
+    #[serde(skip)]
+    missing_target_files: Vec<(Target, String)>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DaemonConfig {
+    /// Colorschemes that are applied while a window with this class or app id is focused.
+    pub focus: BTreeMap<String, String>,
+    /// Colorschemes that terminals on these workspaces are recolored with, by workspace name.
+    pub workspaces: BTreeMap<String, String>,
+    /// Colorschemes that terminals on these outputs are recolored with, by output name. Workspace
+    /// rules take precedence.
+    pub outputs: BTreeMap<String, String>,
+    /// Dims the background color depending on the time of day.
+    pub dim: DimConfig,
+    /// Colorschemes that are applied on dates in the `month-day` or `year-month-day` format.
+    pub dates: BTreeMap<String, String>,
+    pub calendar: CalendarConfig,
+    pub battery: BatteryConfig,
+    pub idle: IdleConfig,
+    /// The address the `/metrics` endpoint is served on, like `127.0.0.1:9464`.
+    pub metrics_address: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FollowSystemConfig {
+    /// The colorscheme that is applied when the desktop prefers a dark appearance.
+    pub dark: Option<String>,
+    /// The colorscheme that is applied when the desktop prefers a light appearance or has no
+    /// preference.
+    pub light: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScheduleConfig {
+    /// The colorscheme that is applied between sunrise and sunset or `day_start` and
+    /// `night_start`.
+    pub day: Option<String>,
+    pub night: Option<String>,
+    /// Fixed local times in the `hour:minute` format, used instead of a location.
+    pub day_start: Option<String>,
+    pub night_start: Option<String>,
+    /// The location in degrees, which sunrise and sunset are computed for.
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CalendarConfig {
+    /// An ics calendar whose events can pin colorschemes.
+    pub file: Option<String>,
+    /// Colorschemes that are applied during events with this summary.
+    pub events: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BatteryConfig {
+    /// The colorscheme that replaces dark colorschemes while running on battery, like one with a
+    /// black background for OLED screens.
+    pub dark: Option<String>,
+    /// The colorscheme that replaces light colorschemes while running on battery.
+    pub light: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IdleConfig {
+    /// The minutes without input after which the colors are dimmed.
+    pub minutes: Option<u64>,
+    /// The filter that is applied while idle, [`IdleConfig::DEFAULT_FILTER`] if it isn't set.
+    pub filter: Option<Filter>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DimConfig {
+    /// `[hour, brightness]` points of the brightness curve of the background color, the
+    /// brightness is interpolated linearly between them and wraps around at midnight.
+    pub curve: Vec<(f32, f32)>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// Targets that are reloaded in addition to the ones passed as flags.
+    pub targets: Vec<Target>,
+    /// Overrides the strategy, unless it's passed as a flag.
+    pub strategy: Option<Strategy>,
+    /// Overrides the global `max_parallel` setting.
+    pub max_parallel: Option<usize>,
+    /// Overrides the font of the scheme files.
+    pub font: Font,
+}
+
+/// Files of a target that differ from the defaults. Fields that the target doesn't use are ignored.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TargetConfig {
+    /// The configuration file that is written.
+    pub file: Option<String>,
+    /// The input file the configuration file is rendered from.
+    pub in_file: Option<String>,
+    pub selector: Option<String>,
+    /// The kitty remote control socket.
+    pub socket: Option<String>,
+    /// The wezterm or zellij configuration file that is updated to make them reload.
+    pub config: Option<String>,
+    /// The lua command that is run by neovim instances.
+    pub command: Option<String>,
+    /// Colorschemes of tmux sessions by session name.
+    pub sessions: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateConfig {
+    /// The file the template is rendered into.
+    pub output: String,
+    /// A shell command that is run after the file was rendered.
+    pub command: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RefreshConfig {
+    /// Redraw the status line of all tmux clients.
+    pub tmux: bool,
+    /// Process ids that are sent a `USR1` signal.
+    pub pids: Vec<u32>,
+    /// Process names whose processes are sent a `USR1` signal.
+    pub processes: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GammaConfig {
+    /// The program that sets the screen temperature.
+    pub program: GammaProgram,
+    /// The screen temperature in kelvin, by colorscheme.
+    pub temperatures: BTreeMap<String, u32>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GammaProgram {
+    #[default]
+    Gammastep,
+    Wlsunset,
+}
+
+impl DaemonConfig {
+    /// The colorscheme terminals on a workspace and output are recolored with, if any.
+    pub fn workspace_colorscheme(&self, workspace: &str, output: &str) -> Option<&String> {
+        self.workspaces.get(workspace).or_else(|| self.outputs.get(output))
+    }
+
+    /// Whether terminals are recolored depending on their workspace or output.
+    pub fn has_workspace_rules(&self) -> bool {
+        !self.workspaces.is_empty() || !self.outputs.is_empty()
+    }
+
+    /// Whether colorschemes are pinned to dates or calendar events.
+    pub fn is_scheduled(&self) -> bool {
+        !self.dates.is_empty() || self.calendar.file.is_some()
+    }
+}
+
+impl BatteryConfig {
+    pub fn is_set(&self) -> bool {
+        self.dark.is_some() || self.light.is_some()
+    }
+
+    /// The colorscheme that replaces colorschemes of `variant` while running on battery.
+    pub fn colorscheme(&self, variant: Option<&str>) -> Option<&String> {
+        match variant {
+            Some("dark") => self.dark.as_ref(),
+            Some("light") => self.light.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl IdleConfig {
+    pub const DEFAULT_FILTER: Filter = Filter { warmth: 0.0, brightness: -0.5 };
+
+    pub fn filter(&self) -> Filter {
+        self.filter.unwrap_or(Self::DEFAULT_FILTER)
+    }
+}
+
+impl DimConfig {
+    /// The brightness of the background color at `hour`, between 0 and 1.
+    pub fn brightness(&self, hour: f32) -> f32 {
+        let mut curve = self.curve.clone();
+        curve.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        let (first, last) = match (curve.first(), curve.last()) {
+            (Some(f), Some(l)) => (*f, *l),
+            _ => return 1.0,
+        };
+        let prev = match curve.iter().rev().find(|(h, _)| *h <= hour) {
+            Some(p) => *p,
+            None => (last.0 - 24.0, last.1),
+        };
+        let next = match curve.iter().find(|(h, _)| *h > hour) {
+            Some(n) => *n,
+            None => (first.0 + 24.0, first.1),
+        };
+
+        let t = match next.0 - prev.0 {
+            d if d > 0.0 => (hour - prev.0) / d,
+            _ => 0.0,
+        };
+        (prev.1 + t * (next.1 - prev.1)).clamp(0.0, 1.0)
+    }
+}
+
+impl Config {
+    /// Loads the settings file, a missing file results in the default settings. Unknown keys,
+    /// values of the wrong type and directories that don't exist are reported with their location
+    /// in the file. Missing selectors and input files are reported by [`Config::check_targets`].
+    pub fn load(settings_file: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let settings_str = match fs::read_to_string(settings_file.as_ref()) {
+            Ok(s) => s,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(_) => return Err(anyhow!("Error reading settings file")),
+        };
+
+        Self::parse(&settings_str)
+    }
+
+    fn parse(settings_str: &str) -> anyhow::Result<Self> {
+        let mut settings: Self = toml::from_str(settings_str)
+            .map_err(|e| anyhow!("Error parsing settings file:\n{}", e))?;
+        let mut missing = Vec::new();
+        for (target, m) in missing_paths(settings_str)? {
+            match target {
+                Some(t) => settings.missing_target_files.push((t, m)),
+                None => missing.push(m),
+            }
+        }
+        if !missing.is_empty() {
+            bail!("Error validating settings file:\n{}", missing.join("\n"));
+        }
+        Ok(settings)
+    }
+
+    /// Fails if a selector or input file of a target that is reloaded with `opts` doesn't exist.
+    /// Missing files of the other targets don't get in the way of subcommands that don't need them.
+    pub fn check_targets(&self, opts: &Options) -> anyhow::Result<()> {
+        let missing: Vec<_> = (self.missing_target_files.iter())
+            .filter(|(t, _)| opts.reload(*t))
+            .map(|(_, m)| m.as_str())
+            .collect();
+        if !missing.is_empty() {
+            bail!("Error validating settings file:\n{}", missing.join("\n"));
+        }
+        Ok(())
+    }
+
+    /// The default options with the directories, files and settings of the settings file applied,
+    /// and the targets in `reload` enabled. The active filters depend on the state file and aren't
+    /// set.
+    pub fn options(&self) -> Options {
+        fn set(path: &mut PathBuf, setting: &Option<String>) {
+            if let Some(s) = setting {
+                *path = PathBuf::from(tilde(s).as_ref());
+            }
+        }
+
+        let mut opts = Options::default();
+        set(&mut opts.scheme_dir, &self.scheme_dir);
+        set(&mut opts.template_dir, &self.template_dir);
+        opts.templates = self.templates.clone();
+        opts.roles = self.roles.clone();
+        set(&mut opts.hook_dir, &self.hook_dir);
+        opts.audit_log = self.audit_log.then(crate::audit_log_path);
+        for t in self.reload.iter() {
+            opts.set_reload(*t, true);
+        }
+        opts.max_parallel = self.max_parallel;
+        opts.safe = self.safe;
+        opts.detect_terminal = self.detect_terminal.unwrap_or(true);
+        opts.refresh = self.refresh.clone();
+        opts.gamma = self.gamma.clone();
+
+        let o = &mut opts.alacritty;
+        set(&mut o.file, &self.alacritty.file);
+        set(&mut o.in_file, &self.alacritty.in_file);
+        set(&mut o.selector, &self.alacritty.selector);
+        o.only_colors = self.only_colors.clone();
+
+        let o = &mut opts.kitty;
+        set(&mut o.file, &self.kitty.file);
+        set(&mut o.socket, &self.kitty.socket);
+        set(&mut o.selector, &self.kitty.selector);
+
+        set(&mut opts.tmux.file, &self.tmux.file);
+        set(&mut opts.tmux.selector, &self.tmux.selector);
+        opts.tmux.sessions = self.tmux.sessions.clone();
+
+        if let Some(c) = &self.neovim.command {
+            opts.neovim.command = c.clone();
+        }
+
+        let o = &mut opts.starship;
+        set(&mut o.file, &self.starship.file);
+        set(&mut o.in_file, &self.starship.in_file);
+        set(&mut o.selector, &self.starship.selector);
+
+        let o = &mut opts.bat;
+        set(&mut o.file, &self.bat.file);
+        set(&mut o.in_file, &self.bat.in_file);
+        set(&mut o.selector, &self.bat.selector);
+
+        set(&mut opts.delta.file, &self.delta.file);
+        set(&mut opts.delta.selector, &self.delta.selector);
+
+        set(&mut opts.cmus.selector, &self.cmus.selector);
+
+        let o = &mut opts.wezterm;
+        set(&mut o.file, &self.wezterm.file);
+        set(&mut o.selector, &self.wezterm.selector);
+        set(&mut o.config, &self.wezterm.config);
+
+        set(&mut opts.foot.file, &self.foot.file);
+        set(&mut opts.foot.selector, &self.foot.selector);
+
+        let o = &mut opts.zellij;
+        set(&mut o.file, &self.zellij.file);
+        set(&mut o.selector, &self.zellij.selector);
+        set(&mut o.config, &self.zellij.config);
+
+        set(&mut opts.fish.file, &self.fish.file);
+        set(&mut opts.fish.selector, &self.fish.selector);
+
+        set(&mut opts.fzf.file, &self.fzf.file);
+
+        set(&mut opts.rofi.file, &self.rofi.file);
+        set(&mut opts.rofi.selector, &self.rofi.selector);
+
+        set(&mut opts.dunst.file, &self.dunst.file);
+
+        set(&mut opts.waybar.file, &self.waybar.file);
+
+        set(&mut opts.polybar.file, &self.polybar.file);
+        set(&mut opts.polybar.selector, &self.polybar.selector);
+
+        set(&mut opts.picom.file, &self.picom.file);
+
+        set(&mut opts.i3.file, &self.i3.file);
+        set(&mut opts.i3.selector, &self.i3.selector);
+
+        set(&mut opts.hyprland.file, &self.hyprland.file);
+
+        set(&mut opts.gtk.selector, &self.gtk.selector);
+
+        set(&mut opts.qutebrowser.file, &self.qutebrowser.file);
+        set(&mut opts.qutebrowser.selector, &self.qutebrowser.selector);
+
+        set(&mut opts.vscode.file, &self.vscode.file);
+        set(&mut opts.vscode.selector, &self.vscode.selector);
+
+        set(&mut opts.zathura.file, &self.zathura.file);
+
+        set(&mut opts.mako.file, &self.mako.file);
+        set(&mut opts.mako.selector, &self.mako.selector);
+
+        set(&mut opts.ghostty.file, &self.ghostty.file);
+        set(&mut opts.ghostty.selector, &self.ghostty.selector);
+
+        set(&mut opts.konsole.selector, &self.konsole.selector);
+
+        set(&mut opts.emacs.selector, &self.emacs.selector);
+
+        set(&mut opts.vim.selector, &self.vim.selector);
+
+        set(&mut opts.xresources.file, &self.xresources.file);
+
+        opts
+    }
+
+    /// The filters that are turned on in the state file, filters that were removed from the
+    /// settings are ignored.
+    pub fn active_filters(&self, config_file: impl AsRef<Path>) -> Vec<(String, Filter)> {
+        crate::filters(config_file)
+            .into_iter()
+            .filter_map(|n| self.filters.get(&n).map(|f| (n, *f)))
+            .collect()
+    }
+
+    pub fn profile(&self, name: &str) -> anyhow::Result<&Profile> {
+        match self.profiles.get(name) {
+            Some(p) => Ok(p),
+            None => bail!("Unknown profile {}", name),
+        }
+    }
+}
+
+/// The files and directories named in the settings that don't exist, like
+/// `line 3, column 12: kitty.selector ~/.config/alco/kity.yml doesn't exist`, with the target they
+/// belong to. Only files alco reads are checked, the files it writes are created.
+fn missing_paths(settings_str: &str) -> anyhow::Result<Vec<(Option<Target>, String)>> {
+    let document = ImDocument::parse(settings_str)
+        .map_err(|e| anyhow!("Error parsing settings file:\n{}", e))?;
+
+    let mut keys = vec![
+        (None, vec!["scheme_dir"]),
+        (None, vec!["template_dir"]),
+        (None, vec!["hook_dir"]),
+        (None, vec!["daemon", "calendar", "file"]),
+    ];
+    for target in Target::ALL {
+        keys.push((Some(target), vec![target.name(), "in_file"]));
+        keys.push((Some(target), vec![target.name(), "selector"]));
+    }
+
+    let mut missing = Vec::new();
+    for (target, key) in keys {
+        let item = key.iter().try_fold(document.as_item(), |item, k| item.get(k));
+        let value = match item.and_then(|i| i.as_value()) {
+            Some(v) => v,
+            None => continue,
+        };
+        let path = match value.as_str() {
+            Some(p) => p,
+            None => continue,
+        };
+        if Path::new(tilde(path).as_ref()).exists() {
+            continue;
+        }
+
+        let start = value.span().map_or(0, |s| s.start);
+        let line_start = settings_str[..start].rfind('\n').map_or(0, |n| n + 1);
+        let line = settings_str[..start].matches('\n').count() + 1;
+        let column = settings_str[line_start..start].chars().count() + 1;
+        let message =
+            format!("line {}, column {}: {} {} doesn't exist", line, column, key.join("."), path);
+        missing.push((target, message));
+    }
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_keys() {
+        let error = Config::parse("[kitty]\nselectr = \"~/kitty.yml\"\n").unwrap_err().to_string();
+        assert!(error.contains("line 2, column 1"), "{}", error);
+        assert!(error.contains("unknown field `selectr`"), "{}", error);
+
+        let error = Config::parse("max_parallel = \"4\"\n").unwrap_err().to_string();
+        assert!(error.contains("line 1, column 16"), "{}", error);
+    }
+
+    #[test]
+    fn missing_files() {
+        let settings = "audit_log = true\n\n[tmux]\nselector = \"/nonexistent/tmux.yml\"\n";
+        let missing = missing_paths(settings).unwrap();
+        assert_eq!(
+            missing,
+            [(
+                Some(Target::Tmux),
+                "line 4, column 12: tmux.selector /nonexistent/tmux.yml doesn't exist".to_owned()
+            )]
+        );
+        assert!(missing_paths("[tmux]\nselector = \"/\"\n").unwrap().is_empty());
+        assert!(Config::parse("scheme_dir = \"/nonexistent\"\n").is_err());
+
+        // a missing selector only fails once its target is reloaded
+        let config = Config::parse(settings).unwrap();
+        let mut opts = Options::default();
+        assert!(config.check_targets(&opts).is_ok());
+        opts.set_reload(Target::Tmux, true);
+        assert!(config.check_targets(&opts).is_err());
+    }
+}
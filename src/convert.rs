@@ -0,0 +1,213 @@
+use anyhow::bail;
+use yaml_rust::{Yaml, YamlLoader};
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::colorscheme::COLOR_NAMES;
+use crate::{Color, Colorscheme, SchemeFile, SCHEME_VERSION};
+
+/// The base16 colors used for the 16 ansi colors, as in base16-shell.
+const BASE16_ANSI: [&str; 16] = [
+    "base00", "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base05", "base03",
+    "base08", "base0B", "base0A", "base0D", "base0E", "base0C", "base07",
+];
+
+/// A palette format that can be converted from and to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// An alco scheme file.
+    Alco,
+    /// An alacritty theme in toml or yaml format, written as toml.
+    Alacritty,
+    /// A base16 scheme in yaml format.
+    Base16,
+    /// A kitty configuration file with color options.
+    Kitty,
+}
+
+impl PaletteFormat {
+    pub const ALL: [PaletteFormat; 4] = [
+        PaletteFormat::Alco,
+        PaletteFormat::Alacritty,
+        PaletteFormat::Base16,
+        PaletteFormat::Kitty,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            PaletteFormat::Alco => "alco",
+            PaletteFormat::Alacritty => "alacritty",
+            PaletteFormat::Base16 => "base16",
+            PaletteFormat::Kitty => "kitty",
+        }
+    }
+}
+
+impl FromStr for PaletteFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match PaletteFormat::ALL.iter().find(|f| f.name() == s) {
+            Some(f) => Ok(*f),
+            None => bail!("Unknown palette format {}", s),
+        }
+    }
+}
+
+impl fmt::Display for PaletteFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Converts a palette from one format to another. Only the terminal colors are kept, colors that
+/// the target format has no place for are dropped.
+pub fn convert(input: &str, from: PaletteFormat, to: PaletteFormat) -> anyhow::Result<String> {
+    let colors = parse(input, from)?;
+    if colors.is_empty() {
+        bail!("No colors in {} palette", from);
+    }
+
+    let out = match to {
+        PaletteFormat::Alco => {
+            format!("version: {}\n{}", SCHEME_VERSION, crate::migrate::render_v2_colors(&colors))
+        }
+        PaletteFormat::Alacritty => render_alacritty(&colors),
+        PaletteFormat::Base16 => render_base16(&colors),
+        PaletteFormat::Kitty => render_kitty(&colors),
+    };
+    Ok(out)
+}
+
+fn parse(input: &str, format: PaletteFormat) -> anyhow::Result<Colorscheme> {
+    let colors = match format {
+        PaletteFormat::Alco => SchemeFile::parse(&parse_yaml(input)?)?.colors,
+        PaletteFormat::Alacritty => {
+            Colorscheme::from_yaml(&crate::import::parse_alacritty_theme(input)?)
+        }
+        PaletteFormat::Base16 => parse_base16(&parse_yaml(input)?),
+        PaletteFormat::Kitty => parse_kitty(input),
+    };
+    Ok(colors)
+}
+
+fn parse_yaml(input: &str) -> anyhow::Result<Yaml> {
+    let yaml = YamlLoader::load_from_str(input)?;
+    Ok(yaml.into_iter().next().unwrap_or(Yaml::Null))
+}
+
+/// Reads the colors of a base16 scheme, either at the top level or in a `palette` section.
+fn parse_base16(yaml: &Yaml) -> Colorscheme {
+    let palette = match &yaml["palette"] {
+        p @ Yaml::Hash(_) => p,
+        _ => yaml,
+    };
+    // the colors are usually written without a `#`
+    let color = |name: &str| -> Option<Color> { palette[name].as_str()?.parse().ok() };
+    Colorscheme {
+        foreground: color("base05"),
+        background: color("base00"),
+        cursor: color("base05"),
+        normal: std::array::from_fn(|i| color(BASE16_ANSI[i])),
+        bright: std::array::from_fn(|i| color(BASE16_ANSI[i + 8])),
+    }
+}
+
+/// Reads the `foreground`, `background`, `cursor` and `color0` to `color15` options.
+fn parse_kitty(input: &str) -> Colorscheme {
+    let mut colors = Colorscheme::default();
+    for line in input.lines().map(str::trim) {
+        let (key, value) = match line.split_once(char::is_whitespace) {
+            Some(kv) if !line.starts_with('#') => kv,
+            _ => continue,
+        };
+        let color = value.trim().parse().ok();
+        match key {
+            "foreground" => colors.foreground = color,
+            "background" => colors.background = color,
+            "cursor" => colors.cursor = color,
+            _ => match key.strip_prefix("color").and_then(|i| i.parse::<usize>().ok()) {
+                Some(i @ 0..=7) => colors.normal[i] = color,
+                Some(i @ 8..=15) => colors.bright[i - 8] = color,
+                _ => (),
+            },
+        }
+    }
+    colors
+}
+
+fn render_alacritty(colors: &Colorscheme) -> String {
+    let mut out = String::new();
+    let primary = [("foreground", colors.foreground), ("background", colors.background)];
+    let sections = [
+        ("primary", primary.to_vec()),
+        ("cursor", vec![("cursor", colors.cursor)]),
+        ("normal", COLOR_NAMES.into_iter().zip(colors.normal).collect()),
+        ("bright", COLOR_NAMES.into_iter().zip(colors.bright).collect()),
+    ];
+    for (section, colors) in sections {
+        if colors.iter().all(|(_, c)| c.is_none()) {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("[colors.{}]\n", section));
+        for (name, color) in colors {
+            if let Some(c) = color {
+                out.push_str(&format!("{} = \"{}\"\n", name, c));
+            }
+        }
+    }
+    out
+}
+
+/// Base16 has more shades than a terminal palette has, the missing ones are approximated with
+/// the closest terminal colors.
+fn render_base16(colors: &Colorscheme) -> String {
+    let (normal, bright) = (colors.normal, colors.bright);
+    let base16 = [
+        ("base00", colors.background),
+        ("base01", normal[0]),
+        ("base02", bright[0]),
+        ("base03", bright[0]),
+        ("base04", normal[7]),
+        ("base05", colors.foreground),
+        ("base06", normal[7]),
+        ("base07", bright[7]),
+        ("base08", normal[1]),
+        ("base09", bright[1]),
+        ("base0A", normal[3]),
+        ("base0B", normal[2]),
+        ("base0C", normal[6]),
+        ("base0D", normal[4]),
+        ("base0E", normal[5]),
+        ("base0F", bright[5]),
+    ];
+
+    let mut out = String::new();
+    for (name, color) in base16 {
+        if let Some(c) = color {
+            out.push_str(&format!("{}: \"{}\"\n", name, c.to_string().trim_start_matches('#')));
+        }
+    }
+    out
+}
+
+fn render_kitty(colors: &Colorscheme) -> String {
+    let named = [
+        ("foreground".to_owned(), colors.foreground),
+        ("background".to_owned(), colors.background),
+        ("cursor".to_owned(), colors.cursor),
+    ];
+    let ansi = colors.ansi().enumerate().map(|(i, c)| (format!("color{}", i), c));
+
+    let mut out = String::new();
+    for (name, color) in named.into_iter().chain(ansi) {
+        if let Some(c) = color {
+            out.push_str(&format!("{} {}\n", name, c));
+        }
+    }
+    out
+}
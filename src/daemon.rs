@@ -0,0 +1,47 @@
+use std::sync::mpsc::Sender;
+
+/// The channel a remote event is answered on, with the text of the reply or an error.
+pub type Reply = Sender<Result<String, String>>;
+
+/// Events `alco daemon` reacts to.
+#[derive(Clone, Debug)]
+pub enum Event {
+    /// The window class or app id of the newly focused window, empty if no window is focused.
+    Focus(String),
+    /// A window was opened, closed or moved to another workspace, or a workspace was moved to
+    /// another output.
+    Workspaces,
+    /// Whether there was no input for the configured idle time, sent when the user becomes idle or
+    /// active again.
+    Idle(bool),
+    /// Whether the computer runs on battery, sent when it's plugged in or unplugged.
+    Power(bool),
+    /// The current hour of the local time, sent at the start of every hour.
+    Hour(u32),
+    /// Apply a colorscheme, like `alco apply`.
+    Apply(String),
+    /// Toggle the colorscheme, like `alco toggle`.
+    Toggle,
+    /// Print the current colorscheme.
+    Status,
+    /// List the available colorschemes.
+    List,
+    /// The settings file or a selector file changed.
+    Reload,
+    /// An event sent by a client connected to the socket.
+    Remote(Box<Event>, Reply),
+}
+
+/// Whether `alco schedule` applies the day or the night colorscheme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeOfDay {
+    Day,
+    Night,
+}
+
+/// The color scheme preference of the desktop, which `alco follow-system` applies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Appearance {
+    Dark,
+    Light,
+}
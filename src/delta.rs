@@ -8,16 +8,28 @@ use std::path::Path;
 pub fn reload_delta(
     config_file: impl AsRef<Path>,
     selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
     colorscheme: impl AsRef<str>,
 ) -> anyhow::Result<()> {
+    let config = render_delta(selector, &scheme_dir, colorscheme)?;
+    fs::write(config_file.as_ref(), config)?;
+    Ok(())
+}
+
+pub fn render_delta(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
     let selector_str = fs::read_to_string(selector.as_ref())
         .map_err(|_| anyhow!("Error reading delta selector"))?;
     let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
 
-    match super::selector(&selector, colorscheme.as_ref()) {
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
         Some(s) => {
-            fs::copy(tilde(s).as_ref(), config_file.as_ref())?;
-            Ok(())
+            let config = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading delta colorscheme file"))?;
+            Ok(config)
         }
         None => bail!("Missing mapping in delta selector"),
     }
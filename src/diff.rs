@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
+
+/// The number of unchanged lines that are shown around changed lines.
+const CONTEXT: usize = 3;
+
+/// A group of changed lines with some context, like a hunk of a unified diff. Line numbers start
+/// at 1, the start of an empty range is the line before it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    /// The lines of the hunk, prefixed with ` ` if unchanged, `-` if removed or `+` if added.
+    pub lines: Vec<String>,
+}
+
+impl fmt::Display for Hunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_lines, self.new_start, self.new_lines
+        )?;
+        for l in self.lines.iter() {
+            writeln!(f, "{}", l)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Same,
+    Remove,
+    Insert,
+}
+
+/// Computes the hunks of a line diff between `old` and `new`, empty if they're equal.
+pub fn diff(old: &str, new: &str) -> Vec<Hunk> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // the lengths of the longest common subsequences of all suffixes
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    // the edit script with the old and new line index before each operation
+    let mut ops = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push((Op::Same, i, j, old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push((Op::Remove, i, j, old[i]));
+            i += 1;
+        } else {
+            ops.push((Op::Insert, i, j, new[j]));
+            j += 1;
+        }
+    }
+
+    let changes: Vec<usize> = (0..ops.len()).filter(|k| ops[*k].0 != Op::Same).collect();
+    let mut hunks = Vec::new();
+    let mut k = 0;
+    while k < changes.len() {
+        let start = changes[k].saturating_sub(CONTEXT);
+        let mut last = changes[k];
+        while k + 1 < changes.len() && changes[k + 1] - last <= 2 * CONTEXT + 1 {
+            k += 1;
+            last = changes[k];
+        }
+        let end = (last + CONTEXT + 1).min(ops.len());
+        hunks.push(hunk(&ops[start..end]));
+        k += 1;
+    }
+    hunks
+}
+
+fn hunk(ops: &[(Op, usize, usize, &str)]) -> Hunk {
+    let (_, old_pos, new_pos, _) = ops[0];
+    let old_lines = ops.iter().filter(|o| o.0 != Op::Insert).count();
+    let new_lines = ops.iter().filter(|o| o.0 != Op::Remove).count();
+    let lines = ops
+        .iter()
+        .map(|(op, _, _, line)| match op {
+            Op::Same => format!(" {}", line),
+            Op::Remove => format!("-{}", line),
+            Op::Insert => format!("+{}", line),
+        })
+        .collect();
+
+    Hunk {
+        old_start: old_pos + (old_lines > 0) as usize,
+        old_lines,
+        new_start: new_pos + (new_lines > 0) as usize,
+        new_lines,
+        lines,
+    }
+}
@@ -0,0 +1,64 @@
+use anyhow::{anyhow, bail};
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::{Color, Colorscheme};
+
+pub fn reload_dunst(file: impl AsRef<Path>, colors: &Colorscheme) -> anyhow::Result<()> {
+    let config = render_dunst(colors)?;
+    fs::write(file.as_ref(), config)?;
+    notify_dunst()
+}
+
+/// Makes dunst read its configuration again. Versions of dunst without `dunstctl reload` are
+/// stopped instead, D-Bus starts them again on the next notification.
+pub fn notify_dunst() -> anyhow::Result<()> {
+    let reload = Command::new("dunstctl").arg("reload").output();
+    if reload.is_ok_and(|o| o.status.success()) {
+        return Ok(());
+    }
+
+    let output = Command::new("pkill")
+        .args(["-x", "dunst"])
+        .output()
+        .map_err(|e| anyhow!("Error running pkill:\n{}", e))?;
+    // pkill exits with 1 if no process matched
+    match output.status.code() {
+        Some(0 | 1) => Ok(()),
+        _ => bail!("Error restarting dunst:\n{}", String::from_utf8_lossy(&output.stderr).trim()),
+    }
+}
+
+/// Renders a drop-in configuration with the `urgency_low`, `urgency_normal` and
+/// `urgency_critical` sections. The frame color shows the urgency, colors that aren't set are left
+/// out.
+pub fn render_dunst(colors: &Colorscheme) -> anyhow::Result<String> {
+    let c = colors;
+    let sections = [
+        ("urgency_low", c.bright[0].or(c.foreground), c.bright[0]),
+        ("urgency_normal", c.foreground, c.normal[4]),
+        ("urgency_critical", c.foreground, c.normal[1]),
+    ];
+
+    let mut config = String::new();
+    for (section, foreground, frame) in sections {
+        let colors =
+            [("background", c.background), ("foreground", foreground), ("frame_color", frame)];
+        let colors: Vec<(&str, Color)> =
+            colors.into_iter().filter_map(|(k, c)| Some((k, c?))).collect();
+        if colors.is_empty() {
+            continue;
+        }
+
+        if !config.is_empty() {
+            config.push('\n');
+        }
+        config.push_str(&format!("[{}]\n", section));
+        for (key, color) in colors {
+            config.push_str(&format!("    {} = \"{}\"\n", key, color));
+        }
+    }
+    Ok(config)
+}
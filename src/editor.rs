@@ -0,0 +1,356 @@
+use anyhow::{anyhow, bail};
+use yaml_rust::YamlLoader;
+
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::Command;
+
+use crate::pack::{quote, read_selector};
+use crate::themes::{contract_home, selector_values};
+use crate::{Options, Target};
+
+const HELP: &str = "↑↓ move  tab switch  enter assign  x remove  w write  q quit";
+
+/// Lets the colorschemes be mapped to the values found by [`selector_values`] in a small
+/// interactive editor on the controlling terminal, and writes the changed entries to the selector
+/// file of the target. Entries are replaced in place, so comments and the order are kept. Returns
+/// the number of entries that were written.
+pub fn edit_selector(
+    opts: &Options,
+    target: Target,
+    mut colorschemes: Vec<String>,
+) -> anyhow::Result<usize> {
+    let values = selector_values(opts, target)?;
+    let path = opts.selector(target).map(Path::to_path_buf).unwrap_or_default();
+    let mapping: BTreeMap<String, String> = match path.exists() {
+        true => read_selector(&path)?.into_iter().collect(),
+        false => BTreeMap::new(),
+    };
+    // entries of colorschemes that aren't in the list, like `else`
+    for key in mapping.keys() {
+        if !colorschemes.contains(key) {
+            colorschemes.push(key.clone());
+        }
+    }
+
+    let mut editor = Editor {
+        colorschemes,
+        values,
+        mapping,
+        changes: BTreeMap::new(),
+        column: 0,
+        cursors: [0, 0],
+    };
+    let title = format!("{} selector {}", target, contract_home(&path));
+    let mut terminal = RawTerminal::enable()?;
+    let mut status = String::new();
+    let mut written = 0;
+    loop {
+        let (rows, cols) = terminal.size();
+        let status_line = if status.is_empty() { HELP } else { &status };
+        terminal.tty.write_all(editor.draw(&title, status_line, rows, cols).as_bytes())?;
+        terminal.tty.flush()?;
+
+        let mut buf = [0; 8];
+        let n = terminal.tty.read(&mut buf)?;
+        let key = match parse_key(&buf[..n]) {
+            Some(k) => k,
+            None => continue,
+        };
+        let quit_pending = status.starts_with("Unsaved");
+        status.clear();
+        match key {
+            Key::Write => {
+                written += editor.changes.len();
+                editor.write(&path)?;
+                status = format!("Wrote {}", contract_home(&path));
+            }
+            Key::Quit if editor.changes.is_empty() || quit_pending => break,
+            Key::Quit => status = "Unsaved changes, press q again to discard them".to_owned(),
+            _ => editor.handle(key),
+        }
+    }
+    Ok(written)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Tab,
+    Enter,
+    Remove,
+    Write,
+    Quit,
+}
+
+fn parse_key(bytes: &[u8]) -> Option<Key> {
+    let key = match bytes {
+        b"\x1b[A" | b"k" => Key::Up,
+        b"\x1b[B" | b"j" => Key::Down,
+        b"\x1b[D" | b"h" => Key::Left,
+        b"\x1b[C" | b"l" => Key::Right,
+        b"\t" => Key::Tab,
+        b"\r" | b"\n" => Key::Enter,
+        b"x" | b"\x7f" => Key::Remove,
+        b"w" => Key::Write,
+        // ctrl-c and escape
+        b"q" | b"\x03" | b"\x1b" => Key::Quit,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// The state of the selector editor, the colorschemes are listed in the left column and the
+/// values in the right one.
+struct Editor {
+    colorschemes: Vec<String>,
+    values: Vec<String>,
+    /// The entries of the selector file.
+    mapping: BTreeMap<String, String>,
+    /// Entries that were changed since the selector file was written, `None` if they were removed.
+    changes: BTreeMap<String, Option<String>>,
+    column: usize,
+    cursors: [usize; 2],
+}
+
+impl Editor {
+    fn entry(&self, colorscheme: &str) -> Option<&str> {
+        match self.changes.get(colorscheme) {
+            Some(change) => change.as_deref(),
+            None => self.mapping.get(colorscheme).map(String::as_str),
+        }
+    }
+
+    fn handle(&mut self, key: Key) {
+        let len = [self.colorschemes.len(), self.values.len()][self.column];
+        let cursor = &mut self.cursors[self.column];
+        match key {
+            Key::Up => *cursor = cursor.saturating_sub(1),
+            Key::Down => *cursor = (*cursor + 1).min(len.saturating_sub(1)),
+            Key::Left => self.column = 0,
+            Key::Right => self.column = 1,
+            Key::Tab => self.column = 1 - self.column,
+            Key::Enter if self.column == 0 => self.column = 1,
+            Key::Enter => {
+                let (colorscheme, value) = match (
+                    self.colorschemes.get(self.cursors[0]),
+                    self.values.get(self.cursors[1]),
+                ) {
+                    (Some(c), Some(v)) => (c.clone(), v.clone()),
+                    _ => return,
+                };
+                self.changes.insert(colorscheme, Some(value));
+                // continue with the next colorscheme
+                self.cursors[0] = (self.cursors[0] + 1).min(self.colorschemes.len() - 1);
+            }
+            Key::Remove => {
+                if let Some(c) = self.colorschemes.get(self.cursors[0]) {
+                    self.changes.insert(c.clone(), None);
+                }
+            }
+            Key::Write | Key::Quit => (),
+        }
+    }
+
+    /// Writes the changed entries to the selector file.
+    fn write(&mut self, path: &Path) -> anyhow::Result<()> {
+        let selector = fs::read_to_string(path).unwrap_or_default();
+        let selector = set_entries(&selector, &self.changes)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, selector)
+            .map_err(|e| anyhow!("Error writing {}:\n{}", path.display(), e))?;
+
+        for (colorscheme, change) in std::mem::take(&mut self.changes) {
+            match change {
+                Some(v) => self.mapping.insert(colorscheme, v),
+                None => self.mapping.remove(&colorscheme),
+            };
+        }
+        Ok(())
+    }
+
+    /// Renders the whole screen. Changed entries are marked with a `*`, and the value of the
+    /// selected colorscheme with a `>`.
+    fn draw(&self, title: &str, status: &str, rows: usize, cols: usize) -> String {
+        let height = rows.saturating_sub(2).max(1);
+        let width = cols / 2;
+        let selected = self.colorschemes.get(self.cursors[0]).and_then(|c| self.entry(c));
+
+        let left: Vec<String> = self
+            .colorschemes
+            .iter()
+            .map(|c| {
+                let marker = if self.changes.contains_key(c) { '*' } else { ' ' };
+                format!("{}{}: {}", marker, c, self.entry(c).unwrap_or_default())
+            })
+            .collect();
+        let right: Vec<String> = self
+            .values
+            .iter()
+            .map(|v| {
+                let marker = if Some(v.as_str()) == selected { '>' } else { ' ' };
+                format!("{}{}", marker, v)
+            })
+            .collect();
+
+        let mut screen = format!("\x1b[H\x1b[2J\x1b[1m{}\x1b[0m\n", fit(title, cols));
+        let columns = [(&left, width), (&right, cols - width)];
+        let starts = [0, 1].map(|i| self.cursors[i].saturating_sub(height - 1));
+        for row in 0..height {
+            for (i, (lines, width)) in columns.iter().enumerate() {
+                let line = lines.get(starts[i] + row).map(String::as_str).unwrap_or_default();
+                let line = fit(line, *width);
+                if starts[i] + row != self.cursors[i] || lines.is_empty() {
+                    screen.push_str(&line);
+                } else if i == self.column {
+                    screen.push_str(&format!("\x1b[7m{}\x1b[0m", line));
+                } else {
+                    screen.push_str(&format!("\x1b[4m{}\x1b[0m", line));
+                }
+            }
+            screen.push('\n');
+        }
+        screen.push_str(&fit(status, cols));
+        screen
+    }
+}
+
+/// Truncates or pads a line to `width` characters.
+fn fit(line: &str, width: usize) -> String {
+    format!("{:width$}", line.chars().take(width).collect::<String>(), width = width)
+}
+
+/// Replaces, removes or appends the changed entries of a selector file, keeping all other lines.
+pub(crate) fn set_entries(
+    selector: &str,
+    changes: &BTreeMap<String, Option<String>>,
+) -> anyhow::Result<String> {
+    let mut lines = Vec::new();
+    let mut replaced = Vec::new();
+    for line in selector.lines() {
+        let key = top_level_key(line);
+        match key.as_ref().and_then(|k| Some((k, changes.get(k)?))) {
+            Some((key, Some(value))) => {
+                lines.push(format!("{}: {}", quote(key)?, quote(value)?));
+                replaced.push(key.clone());
+            }
+            Some((_, None)) => (),
+            None => lines.push(line.to_owned()),
+        }
+    }
+    for (key, value) in changes {
+        if let (false, Some(value)) = (replaced.contains(key), value) {
+            lines.push(format!("{}: {}", quote(key)?, quote(value)?));
+        }
+    }
+
+    let mut selector = lines.join("\n");
+    if !selector.is_empty() {
+        selector.push('\n');
+    }
+    Ok(selector)
+}
+
+/// The key of a line with a top level entry, like `my-dark-theme: ~/themes/dark.conf`.
+fn top_level_key(line: &str) -> Option<String> {
+    if line.starts_with(|c: char| c.is_whitespace() || c == '#' || c == '-') {
+        return None;
+    }
+    let doc = YamlLoader::load_from_str(line).ok()?.into_iter().next()?;
+    let (key, _) = doc.into_hash()?.into_iter().next()?;
+    key.into_string()
+}
+
+/// The controlling terminal with echo and line buffering turned off, showing the alternate
+/// screen. The previous settings are restored when it's dropped.
+struct RawTerminal {
+    tty: File,
+    settings: String,
+}
+
+impl RawTerminal {
+    fn enable() -> anyhow::Result<Self> {
+        let tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .map_err(|_| anyhow!("Error opening the controlling terminal"))?;
+        let settings = stty(&tty, &["-g"])?;
+        stty(&tty, &["-icanon", "-echo", "-isig", "min", "1"])?;
+        let mut terminal = RawTerminal { tty, settings };
+        terminal.tty.write_all(b"\x1b[?1049h\x1b[?25l")?;
+        Ok(terminal)
+    }
+
+    /// The rows and columns of the terminal, 24 by 80 if they can't be read.
+    fn size(&self) -> (usize, usize) {
+        let size = stty(&self.tty, &["size"]).unwrap_or_default();
+        let mut size = size.split_whitespace().filter_map(|s| s.parse().ok());
+        match (size.next(), size.next()) {
+            (Some(rows), Some(cols)) if rows > 0 && cols > 0 => (rows, cols),
+            _ => (24, 80),
+        }
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        self.tty.write_all(b"\x1b[?25h\x1b[?1049l").ok();
+        stty(&self.tty, &[&self.settings]).ok();
+    }
+}
+
+fn stty(tty: &File, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(tty.try_clone()?)
+        .output()
+        .map_err(|e| anyhow!("Error running stty:\n{}", e))?;
+    if !output.status.success() {
+        bail!("Error setting up the terminal:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_selector_entries() {
+        let selector = "# themes\nlight: ~/themes/light.conf\ndark: ~/themes/old.conf\nelse: x\n";
+        let changes = BTreeMap::from([
+            ("dark".to_owned(), Some("~/themes/dark.conf".to_owned())),
+            ("else".to_owned(), None),
+            ("new".to_owned(), Some("~/themes/new.conf".to_owned())),
+        ]);
+        let expected = "# themes\nlight: ~/themes/light.conf\ndark: ~/themes/dark.conf\n\
+                        new: ~/themes/new.conf\n";
+        assert_eq!(set_entries(selector, &changes).unwrap(), expected);
+    }
+
+    #[test]
+    fn assign() {
+        let mut editor = Editor {
+            colorschemes: vec!["light".to_owned(), "dark".to_owned()],
+            values: vec!["a".to_owned(), "b".to_owned()],
+            mapping: BTreeMap::from([("light".to_owned(), "a".to_owned())]),
+            changes: BTreeMap::new(),
+            column: 0,
+            cursors: [0, 0],
+        };
+        for key in [Key::Down, Key::Enter, Key::Down, Key::Enter] {
+            editor.handle(key);
+        }
+        assert_eq!(editor.entry("dark"), Some("b"));
+        assert_eq!(editor.entry("light"), Some("a"));
+        assert_eq!(editor.changes.len(), 1);
+    }
+}
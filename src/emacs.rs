@@ -0,0 +1,55 @@
+use anyhow::{anyhow, bail};
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Loads the theme mapped by the selector in the running Emacs server, using `emacsclient`.
+/// Nothing is done if no server is running.
+pub fn reload_emacs(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let theme = emacs_theme(selector, scheme_dir, colorscheme)?;
+    let output = Command::new("emacsclient")
+        .args(["--eval", &load_theme(&theme)])
+        .output()
+        .map_err(|e| anyhow!("Error running emacsclient:\n{}", e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // emacsclient fails to connect to the socket if no server is running
+    if stderr.contains("socket") || stderr.contains("server") {
+        return Ok(());
+    }
+    bail!("Error loading emacs theme:\n{}", stderr.trim())
+}
+
+/// The name of the Emacs theme the selector maps the colorscheme to.
+fn emacs_theme(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading emacs selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(t) => Ok(t),
+        None => bail!("Missing mapping in emacs selector"),
+    }
+}
+
+/// The expression that disables the enabled themes and loads `theme` without asking whether it's
+/// safe. The name is interned, so it can't be mistaken for other expressions.
+pub(crate) fn load_theme(theme: &str) -> String {
+    let name = theme.replace('\\', "\\\\").replace('"', "\\\"");
+    format!(
+        "(progn (mapc #'disable-theme custom-enabled-themes) (load-theme (intern \"{}\") t))",
+        name
+    )
+}
@@ -0,0 +1,103 @@
+//! C entry points for window managers and status bars that link alco directly. All functions use
+//! the default files and the settings file, and reload all targets.
+
+use shellexpand::tilde;
+
+use std::ffi::{c_char, c_int, CStr};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::{Config, Target};
+
+/// The colorscheme was applied to all targets.
+pub const ALCO_OK: c_int = 0;
+/// The colorscheme couldn't be applied.
+pub const ALCO_ERROR: c_int = 1;
+/// The colorscheme was applied, but some targets failed to reload.
+pub const ALCO_PARTIAL: c_int = 2;
+
+/// Applies a colorscheme.
+///
+/// # Safety
+///
+/// `colorscheme` has to be a valid pointer to a nul terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn alco_apply(colorscheme: *const c_char) -> c_int {
+    if colorscheme.is_null() {
+        return ALCO_ERROR;
+    }
+    let colorscheme = match CStr::from_ptr(colorscheme).to_str() {
+        Ok(c) => c.to_owned(),
+        Err(_) => return ALCO_ERROR,
+    };
+
+    catch_panic(|| {
+        let config_file = path(crate::DEFAULT_CONFIG_FILE);
+        let colors_file = path(crate::DEFAULT_COLORSCHEME_FILE);
+        match crate::apply(colors_file, &config_file, colorscheme.clone()) {
+            Ok(_) => reload(&config_file, &colorscheme),
+            Err(_) => ALCO_ERROR,
+        }
+    })
+}
+
+/// Toggles to the next colorscheme.
+#[no_mangle]
+pub extern "C" fn alco_toggle() -> c_int {
+    catch_panic(|| {
+        let config_file = path(crate::DEFAULT_CONFIG_FILE);
+        let colors_file = path(crate::DEFAULT_COLORSCHEME_FILE);
+        let scheme_dir = match Config::load(path(crate::DEFAULT_SETTINGS_FILE)) {
+            Ok(s) => s.options().scheme_dir,
+            Err(_) => return ALCO_ERROR,
+        };
+        match crate::toggle(colors_file, &config_file, scheme_dir, false) {
+            Ok(colorscheme) => reload(&config_file, &colorscheme),
+            Err(_) => ALCO_ERROR,
+        }
+    })
+}
+
+/// Runs `f` and returns [`ALCO_ERROR`] if it panics, since unwinding into the C caller is undefined
+/// behavior.
+fn catch_panic(f: impl FnOnce() -> c_int) -> c_int {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(ALCO_ERROR)
+}
+
+fn reload(config_file: &Path, colorscheme: &str) -> c_int {
+    let settings = match Config::load(path(crate::DEFAULT_SETTINGS_FILE)) {
+        Ok(s) => s,
+        Err(_) => return ALCO_ERROR,
+    };
+
+    let mut opts = settings.options();
+    for t in Target::ALL {
+        opts.set_reload(t, true);
+    }
+    if settings.check_targets(&opts).is_err() {
+        return ALCO_ERROR;
+    }
+    opts.filters = settings.active_filters(config_file);
+    let refresh = opts.refresh.clone();
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread().enable_io().build() {
+        Ok(r) => r,
+        Err(_) => return ALCO_ERROR,
+    };
+    let results = runtime.block_on(crate::reload_targets(Arc::new(opts), colorscheme));
+    let failed: Vec<_> = results.into_iter().filter(|(_, r)| r.is_err()).map(|(t, _)| t).collect();
+
+    crate::set_failed(config_file, &failed).ok();
+    crate::refresh_status_bars(&refresh);
+
+    if failed.is_empty() {
+        ALCO_OK
+    } else {
+        ALCO_PARTIAL
+    }
+}
+
+fn path(path: &str) -> PathBuf {
+    PathBuf::from(tilde(path).as_ref())
+}
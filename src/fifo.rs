@@ -0,0 +1,62 @@
+use anyhow::{anyhow, bail};
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::os::unix::fs::FileTypeExt;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use crate::Event;
+
+/// The path of the named pipe `alco daemon` reads commands from.
+pub fn fifo_path() -> anyhow::Result<PathBuf> {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => Ok(PathBuf::from(dir).join("alco.fifo")),
+        None => bail!("XDG_RUNTIME_DIR is not set"),
+    }
+}
+
+/// Creates the named pipe if it doesn't exist and sends an event for every `apply <colorscheme>`,
+/// `toggle` or `status` line written to it. Other lines are ignored.
+pub fn watch_fifo(sender: Sender<Event>) -> anyhow::Result<()> {
+    let path = fifo_path()?;
+    match fs::metadata(&path) {
+        Ok(m) if m.file_type().is_fifo() => (),
+        Ok(_) => bail!("{} exists but isn't a named pipe", path.display()),
+        Err(_) => {
+            let status = Command::new("mkfifo")
+                .arg("-m")
+                .arg("600")
+                .arg(&path)
+                .status()
+                .map_err(|_| anyhow!("Error running mkfifo"))?;
+            if !status.success() {
+                bail!("Error creating named pipe {}", path.display());
+            }
+        }
+    }
+
+    thread::spawn(move || loop {
+        // opening blocks until a writer connects, reading ends once all writers are closed
+        let file = match File::open(&path) {
+            Ok(f) => f,
+            Err(_) => break,
+        };
+        for line in BufReader::new(file).lines().map_while(Result::ok) {
+            let event = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["apply", colorscheme] => Event::Apply((*colorscheme).to_owned()),
+                ["toggle"] => Event::Toggle,
+                ["status"] => Event::Status,
+                _ => continue,
+            };
+            if sender.send(event).is_err() {
+                return;
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+pub fn reload_fish(
+    theme_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let theme = render_fish(selector, &scheme_dir, colorscheme)?;
+    fs::write(theme_file.as_ref(), theme)?;
+    notify_fish(theme_file)
+}
+
+/// Sets the `fish_color_*` and `fish_pager_color_*` variables of the fish theme file `theme_file`
+/// as universal variables, which running fish sessions pick up immediately.
+pub fn notify_fish(theme_file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let theme = fs::read_to_string(theme_file.as_ref())
+        .map_err(|_| anyhow!("Error reading fish theme file"))?;
+    let script = fish_script(&theme);
+    if script.is_empty() {
+        return Ok(());
+    }
+
+    let output = Command::new("fish")
+        .args(["--no-config", "-c", &script])
+        .output()
+        .map_err(|e| anyhow!("Error running fish:\n{}", e))?;
+    if !output.status.success() {
+        bail!("Error setting fish colors:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+pub fn render_fish(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading fish selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => {
+            let theme = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading fish theme file"))?;
+            Ok(theme)
+        }
+        None => bail!("Missing mapping in fish selector"),
+    }
+}
+
+/// A fish script that sets the color variables of a theme file universally. Theme files contain
+/// lines like `fish_color_command blue --bold`, the first definition of a variable is used if the
+/// file has `[light]` and `[dark]` sections.
+pub(crate) fn fish_script(theme: &str) -> String {
+    let mut names = Vec::new();
+    let mut script = String::new();
+    for line in theme.lines().map(str::trim) {
+        let name = line.split_whitespace().next().unwrap_or_default();
+        let is_color = name.starts_with("fish_color_") || name.starts_with("fish_pager_color_");
+        if !is_color || names.contains(&name) {
+            continue;
+        }
+        names.push(name);
+        script.push_str("set -U ");
+        script.push_str(line);
+        script.push('\n');
+    }
+    script
+}
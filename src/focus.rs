@@ -0,0 +1,104 @@
+use anyhow::{anyhow, bail};
+
+use std::env;
+use std::io::{BufRead, BufReader, Read};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use crate::Event;
+
+/// Watches the focused window using the ipc of hyprland or sway, or `xprop` on X11, and sends an
+/// [`Event::Focus`] every time it changes.
+pub fn watch_focus(sender: Sender<Event>) -> anyhow::Result<()> {
+    if let Ok(signature) = env::var("HYPRLAND_INSTANCE_SIGNATURE") {
+        let stream = UnixStream::connect(hyprland_socket(&signature))
+            .map_err(|_| anyhow!("Error connecting to hyprland socket"))?;
+        spawn_reader(stream, sender, |l| hyprland_focus(l).map(Event::Focus));
+    } else if env::var_os("SWAYSOCK").is_some() {
+        let stdout = spawn("swaymsg", &["-r", "-m", "-t", "subscribe", "[\"window\"]"])?;
+        spawn_reader(stdout, sender, |l| sway_focus(l).map(Event::Focus));
+    } else if env::var_os("DISPLAY").is_some() {
+        let stdout = spawn("xprop", &["-root", "-spy", "_NET_ACTIVE_WINDOW"])?;
+        spawn_reader(stdout, sender, |l| x11_focus(l).map(Event::Focus));
+    } else {
+        bail!("No supported window system found, supported are X11, sway and hyprland");
+    }
+
+    Ok(())
+}
+
+pub(crate) fn spawn(program: &str, args: &[&str]) -> anyhow::Result<impl Read + Send + 'static> {
+    let child = Command::new(program)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|_| anyhow!("Error running {}", program))?;
+    child.stdout.ok_or_else(|| anyhow!("Error reading output of {}", program))
+}
+
+/// Sends the events parsed from the lines of `reader`.
+pub(crate) fn spawn_reader(
+    reader: impl Read + Send + 'static,
+    sender: Sender<Event>,
+    parse: fn(&str) -> Option<Event>,
+) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(Result::ok) {
+            if let Some(event) = parse(&line) {
+                if sender.send(event).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+}
+
+pub(crate) fn hyprland_socket(signature: &str) -> PathBuf {
+    let socket = PathBuf::from(env::var("XDG_RUNTIME_DIR").unwrap_or_default())
+        .join("hypr")
+        .join(signature)
+        .join(".socket2.sock");
+    if socket.exists() {
+        socket
+    } else {
+        // older versions of hyprland
+        PathBuf::from("/tmp/hypr").join(signature).join(".socket2.sock")
+    }
+}
+
+/// Parses `activewindow>>class,title` events.
+fn hyprland_focus(line: &str) -> Option<String> {
+    let (event, data) = line.split_once(">>")?;
+    let class = data.split(',').next().unwrap_or_default();
+    (event == "activewindow").then(|| class.to_owned())
+}
+
+fn sway_focus(line: &str) -> Option<String> {
+    let event: serde_json::Value = serde_json::from_str(line).ok()?;
+    if event["change"] != "focus" {
+        return None;
+    }
+
+    let container = &event["container"];
+    let class = container["app_id"].as_str().or(container["window_properties"]["class"].as_str());
+    Some(class.unwrap_or_default().to_owned())
+}
+
+/// Parses `_NET_ACTIVE_WINDOW(WINDOW): window id # 0x1e00003` lines and looks up the class of
+/// the window.
+fn x11_focus(line: &str) -> Option<String> {
+    let id = line.rsplit(' ').next()?;
+    if id == "0x0" {
+        return Some(String::new());
+    }
+
+    let output = Command::new("xprop").args(["-id", id, "WM_CLASS"]).output().ok()?;
+    // WM_CLASS(STRING) = "instance", "class"
+    let output = String::from_utf8_lossy(&output.stdout);
+    let class = output.rsplit('"').nth(1).unwrap_or_default();
+    Some(class.to_owned())
+}
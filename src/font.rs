@@ -0,0 +1,52 @@
+use anyhow::anyhow;
+use serde::Deserialize;
+use yaml_rust::{Yaml, YamlLoader};
+
+use std::fs;
+
+use crate::Options;
+
+/// A terminal font, set in the `font` section of a scheme file or a profile.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Font {
+    pub family: Option<String>,
+    /// The size in points.
+    pub size: Option<f32>,
+}
+
+impl Font {
+    fn from_yaml(font: &Yaml) -> Self {
+        let size = match &font["size"] {
+            Yaml::Integer(i) => Some(*i as f32),
+            Yaml::Real(r) => r.parse().ok(),
+            _ => None,
+        };
+        Self {
+            family: font["family"].as_str().map(str::to_owned),
+            size,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.family.is_none() && self.size.is_none()
+    }
+}
+
+/// The font of a colorscheme, the font of the profile in `opts` takes precedence.
+pub fn font(opts: &Options, colorscheme: &str) -> anyhow::Result<Font> {
+    let path = crate::scheme_file(&opts.scheme_dir, colorscheme);
+    let scheme_font = if path.exists() {
+        let scheme_str =
+            fs::read_to_string(&path).map_err(|_| anyhow!("Error reading colorscheme file"))?;
+        let scheme = YamlLoader::load_from_str(&scheme_str)?.into_iter().next();
+        scheme.map(|s| Font::from_yaml(&s["font"])).unwrap_or_default()
+    } else {
+        Font::default()
+    };
+
+    Ok(Font {
+        family: opts.font.family.clone().or(scheme_font.family),
+        size: opts.font.size.or(scheme_font.size),
+    })
+}
@@ -0,0 +1,127 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::YamlLoader;
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::Colorscheme;
+
+pub fn reload_foot(
+    config_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let config = render_foot(selector, &scheme_dir, colorscheme)?;
+    fs::write(config_file.as_ref(), config)?;
+    notify_foot(config_file)
+}
+
+/// Recolors the terminals of running foot instances with the colors of the ini file
+/// `config_file`, by writing escape sequences to their ttys. Foot only reads its configuration on
+/// startup, so new terminals pick up the file itself.
+pub fn notify_foot(config_file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let config = fs::read_to_string(config_file.as_ref())
+        .map_err(|_| anyhow!("Error reading foot configuration file"))?;
+    let seq = crate::osc_sequences(&parse_colors(&config));
+
+    for tty in foot_ttys() {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(&tty)
+            .map_err(|e| anyhow!("Error opening {}:\n{}", tty.display(), e))?;
+        file.write_all(seq.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+pub fn render_foot(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading foot selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => {
+            let config = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading foot colorscheme file"))?;
+            Ok(config)
+        }
+        None => bail!("Missing mapping in foot selector"),
+    }
+}
+
+/// Reads the colors of the `[colors]` section of a foot ini file, where colors are written
+/// without a `#` and `cursor` contains the text and the cursor color.
+fn parse_colors(config: &str) -> Colorscheme {
+    let mut colors = Colorscheme::default();
+    let mut in_colors = false;
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            // `colors-dark` replaced `colors` in foot 1.22
+            in_colors = matches!(line, "[colors]" | "[colors-dark]");
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((k, v)) if in_colors => (k.trim(), v.trim()),
+            _ => continue,
+        };
+
+        let color = |v: &str| v.parse().ok();
+        match key {
+            "foreground" => colors.foreground = color(value),
+            "background" => colors.background = color(value),
+            "cursor" => colors.cursor = value.split_whitespace().nth(1).and_then(color),
+            _ => {
+                let (palette, i) = match (key.strip_prefix("regular"), key.strip_prefix("bright")) {
+                    (Some(i), _) => (&mut colors.normal, i),
+                    (_, Some(i)) => (&mut colors.bright, i),
+                    _ => continue,
+                };
+                if let Some(c) = i.parse::<usize>().ok().and_then(|i| palette.get_mut(i)) {
+                    *c = color(value);
+                }
+            }
+        }
+    }
+    colors
+}
+
+/// The ttys of the shells running in foot terminals, which are the processes whose parent is a
+/// foot process, either a standalone terminal or a server serving `footclient`.
+fn foot_ttys() -> Vec<PathBuf> {
+    let mut foot_pids = Vec::new();
+    let mut parents = Vec::new();
+    for entry in fs::read_dir("/proc").into_iter().flatten().flatten() {
+        let pid = entry.file_name().to_string_lossy().into_owned();
+        if !pid.bytes().all(|b| b.is_ascii_digit()) {
+            continue;
+        }
+        let status = fs::read_to_string(entry.path().join("status")).unwrap_or_default();
+        let field = |name: &str| {
+            status.lines().find_map(|l| l.strip_prefix(name)).map(|v| v.trim().to_owned())
+        };
+        if field("Name:").as_deref() == Some("foot") {
+            foot_pids.push(pid.clone());
+        }
+        if let Some(ppid) = field("PPid:") {
+            parents.push((pid, ppid));
+        }
+    }
+
+    let mut ttys: Vec<PathBuf> = parents
+        .into_iter()
+        .filter(|(_, ppid)| foot_pids.contains(ppid))
+        .filter_map(|(pid, _)| fs::read_link(format!("/proc/{}/fd/0", pid)).ok())
+        .filter(|tty| tty.starts_with("/dev/pts"))
+        .collect();
+    ttys.sort();
+    ttys.dedup();
+    ttys
+}
@@ -0,0 +1,38 @@
+use std::fs;
+use std::path::Path;
+
+use crate::Colorscheme;
+
+pub fn reload_fzf(file: impl AsRef<Path>, colors: &Colorscheme) -> anyhow::Result<()> {
+    let snippet = render_fzf(colors)?;
+    fs::write(file.as_ref(), snippet)?;
+
+    Ok(())
+}
+
+/// Renders a shell snippet that adds the colors to `FZF_DEFAULT_OPTS` as a `--color` option,
+/// since fzf doesn't have a configuration file. Colors that aren't set are left out.
+pub fn render_fzf(colors: &Colorscheme) -> anyhow::Result<String> {
+    let c = colors;
+    let colors = [
+        ("fg", c.foreground),
+        ("bg", c.background),
+        ("hl", c.normal[4]),
+        ("fg+", c.foreground),
+        ("bg+", c.bright[0].or(c.normal[0])),
+        ("hl+", c.bright[4].or(c.normal[4])),
+        ("info", c.normal[3]),
+        ("prompt", c.normal[4]),
+        ("pointer", c.normal[1]),
+        ("marker", c.normal[2]),
+        ("spinner", c.normal[5]),
+        ("header", c.normal[6]),
+    ];
+    let colors: Vec<_> =
+        colors.into_iter().filter_map(|(k, c)| Some(format!("{}:{}", k, c?))).collect();
+
+    if colors.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(format!("export FZF_DEFAULT_OPTS=\"$FZF_DEFAULT_OPTS --color={}\"\n", colors.join(",")))
+}
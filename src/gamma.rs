@@ -0,0 +1,43 @@
+use anyhow::{anyhow, bail};
+
+use std::process::{Command, Stdio};
+
+use crate::{GammaConfig, GammaProgram};
+
+/// Sets the screen temperature configured for a colorscheme, by restarting gammastep or wlsunset
+/// with a fixed temperature. Nothing is changed if no temperature is configured.
+pub fn set_temperature(config: &GammaConfig, colorscheme: &str) -> anyhow::Result<()> {
+    let temperature = match config.temperatures.get(colorscheme) {
+        Some(t) => *t,
+        None => return Ok(()),
+    };
+
+    let (program, args) = match config.program {
+        GammaProgram::Gammastep => {
+            ("gammastep", vec!["-P".into(), "-O".into(), temperature.to_string()])
+        }
+        // wlsunset requires the day temperature to be higher than the night temperature
+        GammaProgram::Wlsunset => (
+            "wlsunset",
+            vec!["-t".into(), temperature.to_string(), "-T".into(), (temperature + 1).to_string()],
+        ),
+    };
+
+    // the previous instance would keep resetting the temperature
+    let output = Command::new("pkill").arg("-x").arg(program).output()?;
+    // pkill exits with 1 if no process matched
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        bail!("Error stopping {}", program);
+    }
+
+    // on wayland the temperature is only kept as long as the program is running
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|_| anyhow!("Error running {}", program))?;
+
+    Ok(())
+}
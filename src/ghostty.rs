@@ -0,0 +1,72 @@
+use anyhow::{anyhow, bail};
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+pub fn reload_ghostty(
+    config_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let config = render_ghostty(&config_file, selector, &scheme_dir, colorscheme)?;
+    fs::write(config_file.as_ref(), config)?;
+    notify_ghostty()
+}
+
+/// Sends `SIGUSR2` to all ghostty instances, which makes them reload their configuration.
+pub fn notify_ghostty() -> anyhow::Result<()> {
+    let output = Command::new("pkill").args(["-USR2", "-x", "ghostty"]).output()?;
+    // pkill exits with 1 if no process matched
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        bail!("Error sending USR2 to ghostty");
+    }
+    Ok(())
+}
+
+/// Sets the `theme` key of the configuration file to the theme mapped by the selector. Only the
+/// value of the key is replaced, or the key is added if it's missing, so comments and all other
+/// settings are kept.
+pub fn render_ghostty(
+    config_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading ghostty selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+    let theme = match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(t) => t,
+        None => bail!("Missing mapping in ghostty selector"),
+    };
+
+    let config = match fs::read_to_string(config_file.as_ref()) {
+        Ok(s) => s,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => bail!("Error reading ghostty config file:\n{}", e),
+    };
+    Ok(set_theme(&config, &theme))
+}
+
+/// Replaces the value of the last `theme` key, which is the one ghostty uses, or adds the key to
+/// the end.
+fn set_theme(config: &str, theme: &str) -> String {
+    let mut lines: Vec<String> = config.lines().map(str::to_owned).collect();
+    let key = lines.iter().rposition(|l| {
+        let l = l.trim_start();
+        !l.starts_with('#') && l.split_once('=').is_some_and(|(k, _)| k.trim() == "theme")
+    });
+    let setting = format!("theme = {}", theme);
+    match key {
+        Some(i) => lines[i] = setting,
+        None => lines.push(setting),
+    }
+
+    let mut config = lines.join("\n");
+    config.push('\n');
+    config
+}
@@ -0,0 +1,63 @@
+use anyhow::{anyhow, bail};
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::SchemeFile;
+
+/// The gsettings schema containing the `color-scheme` and `gtk-theme` keys.
+pub(crate) const SCHEMA: &str = "org.gnome.desktop.interface";
+
+/// Sets the gsettings keys of [`gtk_settings`], which running GTK applications follow.
+pub fn reload_gtk(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    for (key, value) in gtk_settings(selector, scheme_dir, colorscheme)? {
+        let output = Command::new("gsettings")
+            .args(["set", SCHEMA, key, &value])
+            .output()
+            .map_err(|e| anyhow!("Error running gsettings:\n{}", e))?;
+        if !output.status.success() {
+            bail!("Error setting {}:\n{}", key, String::from_utf8_lossy(&output.stderr).trim());
+        }
+    }
+    Ok(())
+}
+
+/// The keys of the [`SCHEMA`] that are set for a colorscheme. `color-scheme` is set if the scheme
+/// file has a `dark` or `light` variant or tag, and `gtk-theme` if the selector maps the
+/// colorscheme to a theme name. The selector is optional.
+pub fn gtk_settings(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<Vec<(&'static str, String)>> {
+    let mut settings = Vec::new();
+
+    let scheme_file = crate::scheme_file(&scheme_dir, colorscheme.as_ref());
+    if scheme_file.exists() {
+        let meta = SchemeFile::load(&scheme_file)?.meta;
+        let is = |v: &str| meta.variant.as_deref() == Some(v) || meta.tags.iter().any(|t| t == v);
+        // `prefer-light` isn't supported by older versions of GNOME
+        if is("dark") {
+            settings.push(("color-scheme", "prefer-dark".to_owned()));
+        } else if is("light") {
+            settings.push(("color-scheme", "default".to_owned()));
+        }
+    }
+
+    if selector.as_ref().exists() {
+        let selector_str = fs::read_to_string(selector.as_ref())
+            .map_err(|_| anyhow!("Error reading gtk selector"))?;
+        let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+        if let Some(theme) = super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+            settings.push(("gtk-theme", theme));
+        }
+    }
+
+    Ok(settings)
+}
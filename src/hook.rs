@@ -0,0 +1,66 @@
+use anyhow::bail;
+
+/// Recolors the terminal with the colorscheme named in the closest `.alco` file whenever the
+/// working directory changes, and goes back to the current colorscheme when there is none.
+const ZSH_HOOK: &str = r#"
+_alco_hook() {
+    local dir=$PWD colorscheme=
+    while true; do
+        if [[ -f $dir/.alco ]]; then
+            read -r colorscheme <$dir/.alco
+            break
+        fi
+        [[ $dir == / ]] && break
+        dir=${dir:h}
+    done
+
+    [[ $colorscheme == "$_alco_colorscheme" ]] && return
+    if [[ -n $colorscheme ]]; then
+        alco apply --here -- "$colorscheme"
+    else
+        alco reload --here
+    fi
+    _alco_colorscheme=$colorscheme
+}
+
+typeset -g _alco_colorscheme=
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd _alco_hook
+_alco_hook
+"#;
+
+const FISH_HOOK: &str = r#"
+function _alco_hook --on-variable PWD
+    set -l dir $PWD
+    set -l colorscheme
+    while true
+        if test -f $dir/.alco
+            read -l line <$dir/.alco
+            set colorscheme (string trim -- $line)
+            break
+        end
+        test $dir = /; and break
+        set dir (dirname $dir)
+    end
+
+    test "$colorscheme" = "$_alco_colorscheme"; and return
+    if test -n "$colorscheme"
+        alco apply --here -- $colorscheme
+    else
+        alco reload --here
+    end
+    set -g _alco_colorscheme $colorscheme
+end
+
+set -g _alco_colorscheme
+_alco_hook
+"#;
+
+/// The script `alco hook <shell>` prints, which is meant to be evaluated in the shell's rc file.
+pub fn shell_hook(shell: &str) -> anyhow::Result<&'static str> {
+    match shell {
+        "zsh" => Ok(ZSH_HOOK.trim_start()),
+        "fish" => Ok(FISH_HOOK.trim_start()),
+        _ => bail!("Unsupported shell {}", shell),
+    }
+}
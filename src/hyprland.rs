@@ -0,0 +1,114 @@
+use anyhow::{anyhow, bail};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::colorscheme::COLOR_NAMES;
+use crate::{Color, Colorscheme};
+
+pub fn reload_hyprland(
+    file: impl AsRef<Path>,
+    colors: &Colorscheme,
+    roles: &BTreeMap<String, Color>,
+) -> anyhow::Result<()> {
+    let config = render_hyprland(colors, roles)?;
+    fs::write(file.as_ref(), config)?;
+    notify_hyprland(file)
+}
+
+/// Sets the border colors of the rendered config in the running Hyprland instance using
+/// `hyprctl keyword`, which doesn't reload the whole configuration. Nothing is done if Hyprland
+/// isn't running.
+pub fn notify_hyprland(file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let running = Command::new("pgrep")
+        .args(["-x", "Hyprland"])
+        .output()
+        .map_err(|e| anyhow!("Error running pgrep:\n{}", e))?;
+    if !running.status.success() {
+        return Ok(());
+    }
+
+    let config = fs::read_to_string(file.as_ref())
+        .map_err(|_| anyhow!("Error reading hyprland colors file"))?;
+    let batch = hyprctl_batch(&config);
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    let output = Command::new("hyprctl")
+        .args(["--batch", &batch])
+        .output()
+        .map_err(|e| anyhow!("Error running hyprctl:\n{}", e))?;
+    if !output.status.success() {
+        bail!("Error setting hyprland colors:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// The `hyprctl --batch` commands that set the options inside the sections of a rendered config,
+/// like `keyword general:col.active_border rgb(458588)`. Variables are skipped.
+pub(crate) fn hyprctl_batch(config: &str) -> String {
+    let mut section = None;
+    let mut commands = Vec::new();
+    for line in config.lines().map(str::trim) {
+        if let Some(s) = line.strip_suffix('{') {
+            section = Some(s.trim());
+        } else if line == "}" {
+            section = None;
+        } else if let (Some(s), Some((key, value))) = (section, line.split_once('=')) {
+            commands.push(format!("keyword {}:{} {}", s, key.trim(), value.trim()));
+        }
+    }
+    commands.join(" ; ")
+}
+
+/// Renders the colors and the color roles as variables, like `$bright_red = rgb(fb4934)`, and
+/// the border colors of windows and groups, which use the `accent` and `muted` roles. Colors that
+/// aren't set are left out.
+pub fn render_hyprland(
+    colors: &Colorscheme,
+    roles: &BTreeMap<String, Color>,
+) -> anyhow::Result<String> {
+    let named = [
+        ("foreground".to_owned(), colors.foreground),
+        ("background".to_owned(), colors.background),
+        ("cursor".to_owned(), colors.cursor),
+    ];
+    let ansi = colors.ansi().enumerate().map(|(i, c)| {
+        let prefix = if i < 8 { "" } else { "bright_" };
+        (format!("{}{}", prefix, COLOR_NAMES[i % 8]), c)
+    });
+    let variables = roles.iter().map(|(n, c)| (n.replace('-', "_"), Some(*c)));
+
+    let mut config = String::new();
+    for (name, color) in named.into_iter().chain(ansi).chain(variables) {
+        if let Some(c) = color {
+            config.push_str(&format!("${} = {}\n", name, rgb(c)));
+        }
+    }
+
+    let (accent, muted) = (roles.get("accent"), roles.get("muted"));
+    let sections = [
+        ("general", [("col.active_border", accent), ("col.inactive_border", muted)]),
+        ("group", [("col.border_active", accent), ("col.border_inactive", muted)]),
+    ];
+    for (section, options) in sections {
+        if options.iter().all(|(_, c)| c.is_none()) {
+            continue;
+        }
+        config.push_str(&format!("\n{} {{\n", section));
+        for (key, color) in options {
+            if let Some(c) = color {
+                config.push_str(&format!("    {} = {}\n", key, rgb(*c)));
+            }
+        }
+        config.push_str("}\n");
+    }
+    Ok(config)
+}
+
+fn rgb(color: Color) -> String {
+    format!("rgb({})", color.to_string().trim_start_matches('#'))
+}
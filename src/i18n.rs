@@ -0,0 +1,86 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+use std::env;
+use std::sync::OnceLock;
+
+/// The translations by language, English has to be complete.
+const LOCALES: [(&str, &str); 2] =
+    [("en", include_str!("../locales/en.ftl")), ("de", include_str!("../locales/de.ftl"))];
+
+static BUNDLE: OnceLock<FluentBundle<FluentResource>> = OnceLock::new();
+
+/// Translates a message into the language of `LC_ALL`, `LC_MESSAGES` or `LANG`, falling back to
+/// English. The arguments replace the placeables of the message, like `{ $colorscheme }`.
+pub fn tr(id: &str, args: &[(&str, &str)]) -> String {
+    let bundle = BUNDLE.get_or_init(|| bundle(&language()));
+    format(bundle, id, args)
+}
+
+/// The language of the first locale variable that is set, like `de` for `de_DE.UTF-8`.
+fn language() -> String {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|v| env::var(v).ok())
+        .find(|l| !l.is_empty())
+        .unwrap_or_default();
+    let locale = locale.split(['.', '@']).next().unwrap_or_default().replace('_', "-");
+    match locale.parse::<LanguageIdentifier>() {
+        Ok(l) => l.language.as_str().to_owned(),
+        Err(_) => "en".to_owned(),
+    }
+}
+
+/// A bundle with the messages of a language, and the English ones for messages that aren't
+/// translated.
+fn bundle(language: &str) -> FluentBundle<FluentResource> {
+    let (language, ftl) = LOCALES.iter().find(|(l, _)| *l == language).unwrap_or(&LOCALES[0]);
+    let langid: LanguageIdentifier = language.parse().expect("invalid language identifier");
+    let mut bundle = FluentBundle::new_concurrent(vec![langid]);
+    // the arguments are printed as is, without unicode isolation marks around them
+    bundle.set_use_isolating(false);
+
+    for ftl in [*ftl, LOCALES[0].1] {
+        let resource = FluentResource::try_new(ftl.to_owned()).expect("invalid translation");
+        // messages that are already translated are kept
+        let _ = bundle.add_resource(resource);
+    }
+    bundle
+}
+
+fn format(bundle: &FluentBundle<FluentResource>, id: &str, args: &[(&str, &str)]) -> String {
+    let pattern = match bundle.get_message(id).and_then(|m| m.value()) {
+        Some(p) => p,
+        None => return id.to_owned(),
+    };
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, *value);
+    }
+    let mut errors = Vec::new();
+    bundle.format_pattern(pattern, Some(&fluent_args), &mut errors).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translations() {
+        let args = [("colorscheme", "nord")];
+        assert_eq!(
+            format(&bundle("en"), "error-applying", &args),
+            "Error applying colorscheme nord:"
+        );
+        assert_eq!(
+            format(&bundle("de"), "error-applying", &args),
+            "Fehler beim Anwenden des Farbschemas nord:"
+        );
+        assert_eq!(
+            format(&bundle("de"), "error-comparing", &[("a", "nord"), ("b", "gruvbox-dark")]),
+            "Fehler beim Vergleichen von nord und gruvbox-dark:"
+        );
+        assert_eq!(format(&bundle("fr"), "unknown-filter", &[]), "Unknown filter");
+    }
+}
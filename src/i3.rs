@@ -0,0 +1,121 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::{Color, Colorscheme};
+
+pub fn reload_i3(
+    file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+    colors: &Colorscheme,
+) -> anyhow::Result<()> {
+    let config = render_i3(selector, &scheme_dir, colorscheme, colors)?;
+    fs::write(file.as_ref(), config)?;
+    notify_i3()
+}
+
+/// Makes sway or i3, whichever is running, read its configuration again. Nothing is done if
+/// neither is running.
+pub fn notify_i3() -> anyhow::Result<()> {
+    for (process, program) in [("sway", "swaymsg"), ("i3", "i3-msg")] {
+        let running = Command::new("pgrep")
+            .args(["-x", process])
+            .output()
+            .map_err(|e| anyhow!("Error running pgrep:\n{}", e))?;
+        if !running.status.success() {
+            continue;
+        }
+
+        let output = Command::new(program)
+            .arg("reload")
+            .output()
+            .map_err(|e| anyhow!("Error running {}:\n{}", program, e))?;
+        if !output.status.success() {
+            bail!(
+                "Error reloading {}:\n{}",
+                process,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        return Ok(());
+    }
+    Ok(())
+}
+
+/// Renders the `client.*` colors of the window borders and title bars. The selector is optional,
+/// it maps colorschemes to files whose `client.*` lines replace the rendered ones of the same
+/// class, so single classes can be tuned by hand. Other lines of these files are appended.
+pub fn render_i3(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+    colors: &Colorscheme,
+) -> anyhow::Result<String> {
+    let mut lines = client_colors(colors);
+    for line in overrides(selector, &scheme_dir, colorscheme)?.lines() {
+        let class = line.split_whitespace().next().filter(|c| c.starts_with("client."));
+        let rendered =
+            class.and_then(|c| lines.iter_mut().find(|l| l.split_whitespace().next() == Some(c)));
+        match rendered {
+            Some(l) => *l = line.to_owned(),
+            None => lines.push(line.to_owned()),
+        }
+    }
+
+    let mut config = lines.join("\n");
+    config.push('\n');
+    Ok(config)
+}
+
+/// The lines of the file the selector maps `colorscheme` to, empty if there is no selector or it
+/// has no mapping.
+fn overrides(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    if !selector.as_ref().exists() {
+        return Ok(String::new());
+    }
+
+    let selector_str =
+        fs::read_to_string(selector.as_ref()).map_err(|_| anyhow!("Error reading i3 selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => fs::read_to_string(tilde(&s).as_ref())
+            .map_err(|_| anyhow!("Error reading i3 overrides file")),
+        None => Ok(String::new()),
+    }
+}
+
+/// The border, background, text, indicator and child border colors of each class. Classes missing
+/// one of the first three colors, which i3 requires, are left out.
+fn client_colors(c: &Colorscheme) -> Vec<String> {
+    let (blue, red, muted) = (c.normal[4], c.normal[1], c.bright[0]);
+    let bg = c.background;
+    let classes = [
+        ("client.focused", [blue, blue, bg, c.normal[6], blue]),
+        ("client.focused_inactive", [muted, muted, c.foreground, muted, muted]),
+        ("client.unfocused", [bg, bg, muted.or(c.foreground), bg, bg]),
+        ("client.urgent", [red, red, bg, red, red]),
+    ];
+
+    classes
+        .into_iter()
+        .filter_map(|(class, colors)| {
+            let colors: Vec<Color> = colors.into_iter().map_while(|c| c).collect();
+            if colors.len() < 3 {
+                return None;
+            }
+            let colors = colors.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+            Some(format!("{:<23} {}", class, colors.join(" ")))
+        })
+        .collect()
+}
@@ -0,0 +1,28 @@
+use anyhow::bail;
+
+use std::env;
+use std::sync::mpsc::Sender;
+
+use crate::focus::{spawn, spawn_reader};
+use crate::Event;
+
+/// Sends an [`Event::Idle`] when there was no input for `minutes` and when there is input again,
+/// using swayidle on wayland or xidlehook on X11.
+pub fn watch_idle(minutes: u64, sender: Sender<Event>) -> anyhow::Result<()> {
+    let seconds = (minutes * 60).to_string();
+    let stdout = if env::var_os("WAYLAND_DISPLAY").is_some() {
+        let args = ["-w", "timeout", &seconds, "echo idle", "resume", "echo active"];
+        spawn("swayidle", &args)?
+    } else if env::var_os("DISPLAY").is_some() {
+        spawn("xidlehook", &["--timer", &seconds, "echo idle", "echo active"])?
+    } else {
+        bail!("No supported window system found, supported are wayland and X11");
+    };
+    spawn_reader(stdout, sender, |line| match line.trim() {
+        "idle" => Some(Event::Idle(true)),
+        "active" => Some(Event::Idle(false)),
+        _ => None,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,411 @@
+use anyhow::{anyhow, bail};
+use yaml_rust::yaml::Hash;
+use yaml_rust::{Yaml, YamlEmitter, YamlLoader};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{SchemeFile, SchemeSource};
+
+/// The source format of themes from the alacritty-theme repository.
+const ALACRITTY_THEME: &str = "alacritty-theme";
+
+/// Color names used by some hand written themes and their alacritty equivalents.
+const COLOR_ALIASES: &[(&str, &str)] = &[("purple", "magenta")];
+
+/// Imports a theme from a file or an http(s) url into the scheme directory. The source is recorded
+/// in the `meta` section of the scheme file, so it can be updated later.
+pub fn import_alacritty_theme(
+    source: &str,
+    scheme_dir: impl AsRef<Path>,
+    name: Option<&str>,
+) -> anyhow::Result<(String, PathBuf)> {
+    let (name, out_str) = render_alacritty_theme(source, name)?;
+
+    let scheme_dir = scheme_dir.as_ref();
+    fs::create_dir_all(scheme_dir)?;
+    let path = scheme_dir.join(format!("{}.yml", name));
+    fs::write(&path, out_str)?;
+
+    Ok((name, path))
+}
+
+/// Fetches an alacritty theme and returns the colorscheme name and the scheme file contents.
+fn render_alacritty_theme(source: &str, name: Option<&str>) -> anyhow::Result<(String, String)> {
+    let theme_file = Path::new(source.trim_end_matches('/').rsplit('/').next().unwrap_or_default());
+    let (theme_str, url) = if is_url(source) {
+        (download(source)?, source.to_owned())
+    } else {
+        let path =
+            fs::canonicalize(source).map_err(|_| anyhow!("Error reading alacritty theme file"))?;
+        let theme_str =
+            fs::read_to_string(&path).map_err(|_| anyhow!("Error reading alacritty theme file"))?;
+        (theme_str, path.to_string_lossy().into_owned())
+    };
+
+    let theme = match theme_file.extension().and_then(|e| e.to_str()) {
+        Some("toml") => parse_toml(&theme_str)?,
+        _ => parse_yaml(&theme_str)?,
+    };
+
+    let name = match name {
+        Some(n) => n.to_owned(),
+        None => match theme_file.file_stem().and_then(|s| s.to_str()) {
+            Some(s) if !s.is_empty() => s.to_owned(),
+            _ => bail!("Unable to derive a colorscheme name from the theme file"),
+        },
+    };
+    // the name becomes the file name inside the scheme directory
+    let name = name.replace(['/', '\0'], "-");
+
+    let mut source = Hash::new();
+    source.insert(Yaml::String("format".into()), Yaml::String(ALACRITTY_THEME.into()));
+    source.insert(Yaml::String("url".into()), Yaml::String(url));
+    source.insert(Yaml::String("date".into()), Yaml::String(today()));
+    let mut meta = Hash::new();
+    meta.insert(Yaml::String("source".into()), Yaml::Hash(source));
+
+    let mut colors = Hash::new();
+    colors.insert(Yaml::String("meta".into()), Yaml::Hash(meta));
+    colors.insert(Yaml::String("colors".into()), normalize_colors(theme)?);
+
+    let mut out_str = String::new();
+    YamlEmitter::new(&mut out_str).dump(&Yaml::Hash(colors))?;
+    out_str.push('\n');
+
+    Ok((name, out_str))
+}
+
+/// Imports an imported colorscheme again from its source, returns the source.
+pub fn update_scheme(
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: &str,
+) -> anyhow::Result<SchemeSource> {
+    let update = fetch_update(scheme_dir, colorscheme)?;
+    update.write()?;
+    Ok(update.source)
+}
+
+/// A scheme file fetched again from the source of an imported colorscheme.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemeUpdate {
+    pub colorscheme: String,
+    pub path: PathBuf,
+    pub source: SchemeSource,
+    /// The contents of the current scheme file.
+    pub old: String,
+    /// The contents of the fetched scheme file.
+    pub new: String,
+    /// Whether the colors differ from the current scheme file.
+    pub changed: bool,
+}
+
+impl SchemeUpdate {
+    /// A line diff of the current and the fetched scheme file, with lines prefixed by `-`, `+` or
+    /// a space.
+    pub fn diff(&self) -> String {
+        diff_lines(&self.old, &self.new)
+    }
+
+    /// Replaces the scheme file with the fetched one.
+    pub fn write(&self) -> anyhow::Result<()> {
+        fs::write(&self.path, &self.new).map_err(|_| anyhow!("Error writing colorscheme file"))
+    }
+}
+
+/// Fetches an imported colorscheme from its source without changing the scheme file.
+pub fn fetch_update(
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: &str,
+) -> anyhow::Result<SchemeUpdate> {
+    let path = crate::scheme_file(&scheme_dir, colorscheme);
+    let old =
+        fs::read_to_string(&path).map_err(|_| anyhow!("No scheme file for {}", colorscheme))?;
+    let old_yaml = parse_yaml(&old).map_err(|_| anyhow!("Error parsing colorscheme file"))?;
+    let old_scheme = SchemeFile::parse(&old_yaml)?;
+    let source = match old_scheme.meta.source {
+        Some(s) => s,
+        None => bail!("{} wasn't imported, its scheme file has no source", colorscheme),
+    };
+
+    let new = match source.format.as_str() {
+        ALACRITTY_THEME => render_alacritty_theme(&source.url, Some(colorscheme))?.1,
+        f => bail!("Unsupported colorscheme source format {}", f),
+    };
+    let new_yaml = parse_yaml(&new)?;
+    // the import date always differs, migrated scheme files only contain the mapped colors
+    let changed = match old_scheme.version {
+        1 => old_yaml["colors"] != new_yaml["colors"],
+        _ => old_scheme.colors != SchemeFile::parse(&new_yaml)?.colors,
+    };
+
+    Ok(SchemeUpdate {
+        colorscheme: colorscheme.to_owned(),
+        path,
+        source,
+        old,
+        new,
+        changed,
+    })
+}
+
+/// The colorschemes in the scheme directory that were imported and record their source.
+pub fn imported_schemes(scheme_dir: impl AsRef<Path>) -> anyhow::Result<Vec<String>> {
+    let entries =
+        fs::read_dir(scheme_dir.as_ref()).map_err(|_| anyhow!("Error reading scheme directory"))?;
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| Some(e.ok()?.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "yml"))
+        .collect();
+    paths.sort();
+
+    let imported = paths
+        .into_iter()
+        .filter(|p| SchemeFile::load(p).is_ok_and(|s| s.meta.source.is_some()))
+        .filter_map(|p| Some(p.file_stem()?.to_str()?.to_owned()))
+        .collect();
+    Ok(imported)
+}
+
+/// A line diff based on the longest common subsequence of lines.
+fn diff_lines(old: &str, new: &str) -> String {
+    let old: Vec<_> = old.lines().collect();
+    let new: Vec<_> = new.lines().collect();
+
+    // the length of the longest common subsequence of `old[i..]` and `new[j..]`
+    let mut lcs = vec![vec![0; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = match old[i] == new[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+
+    let mut diff = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            diff.push_str(&format!(" {}\n", old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            diff.push_str(&format!("-{}\n", old[i]));
+            i += 1;
+        } else {
+            diff.push_str(&format!("+{}\n", new[j]));
+            j += 1;
+        }
+    }
+    diff
+}
+
+fn is_url(source: &str) -> bool {
+    source.starts_with("http://") || source.starts_with("https://")
+}
+
+fn download(url: &str) -> anyhow::Result<String> {
+    let output = Command::new("curl")
+        .arg("-fsSL")
+        .arg(url)
+        .output()
+        .map_err(|_| anyhow!("Error running curl"))?;
+    if !output.status.success() {
+        bail!("Error downloading alacritty theme");
+    }
+    String::from_utf8(output.stdout).map_err(|_| anyhow!("Error reading alacritty theme"))
+}
+
+/// The current date in UTC in the `year-month-day` format.
+fn today() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    // civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Parses an alacritty theme in toml or yaml format into its normalized `colors` section.
+pub(crate) fn parse_alacritty_theme(str: &str) -> anyhow::Result<Yaml> {
+    let theme = match parse_toml(str) {
+        Ok(t) => t,
+        Err(_) => parse_yaml(str)?,
+    };
+    normalize_colors(theme)
+}
+
+fn parse_yaml(str: &str) -> anyhow::Result<Yaml> {
+    let yaml = YamlLoader::load_from_str(str)?;
+    match yaml.into_iter().next() {
+        Some(y @ Yaml::Hash(_)) => Ok(y),
+        _ => bail!("Error parsing alacritty theme"),
+    }
+}
+
+fn parse_toml(str: &str) -> anyhow::Result<Yaml> {
+    let value = str.parse::<toml::Value>().map_err(|_| anyhow!("Error parsing alacritty theme"))?;
+    Ok(toml_to_yaml(value))
+}
+
+fn toml_to_yaml(value: toml::Value) -> Yaml {
+    match value {
+        toml::Value::String(s) => Yaml::String(s),
+        toml::Value::Integer(i) => Yaml::Integer(i),
+        toml::Value::Float(f) => Yaml::Real(f.to_string()),
+        toml::Value::Boolean(b) => Yaml::Boolean(b),
+        toml::Value::Datetime(d) => Yaml::String(d.to_string()),
+        toml::Value::Array(a) => Yaml::Array(a.into_iter().map(toml_to_yaml).collect()),
+        toml::Value::Table(t) => {
+            Yaml::Hash(t.into_iter().map(|(k, v)| (Yaml::String(k), toml_to_yaml(v))).collect())
+        }
+    }
+}
+
+/// Extracts the `colors` section, which some older themes omit, and fixes up key names and color
+/// values so that the result is a valid alacritty colors section.
+fn normalize_colors(theme: Yaml) -> anyhow::Result<Yaml> {
+    let colors = match theme {
+        Yaml::Hash(mut h) => match h.remove(&Yaml::String("colors".into())) {
+            Some(c @ Yaml::Hash(_)) => c,
+            Some(_) => bail!("Error parsing alacritty theme colors"),
+            None => Yaml::Hash(h),
+        },
+        _ => bail!("Error parsing alacritty theme"),
+    };
+
+    Ok(normalize(colors, None))
+}
+
+fn normalize(yaml: Yaml, section: Option<&str>) -> Yaml {
+    match yaml {
+        Yaml::Hash(h) => {
+            let mut normalized = Hash::new();
+            for (k, v) in h.into_iter() {
+                let key = match k {
+                    Yaml::String(s) => normalize_key(&s, section),
+                    k => {
+                        normalized.insert(k, v);
+                        continue;
+                    }
+                };
+                let value = normalize(v, Some(&key));
+                normalized.insert(Yaml::String(key), value);
+            }
+            Yaml::Hash(normalized)
+        }
+        Yaml::Array(a) => Yaml::Array(a.into_iter().map(|v| normalize(v, section)).collect()),
+        Yaml::String(s) => Yaml::String(normalize_color(s)),
+        y => y,
+    }
+}
+
+fn normalize_key(key: &str, section: Option<&str>) -> String {
+    let key = key.trim().to_lowercase().replace('-', "_");
+
+    // the cursor sections use `text` and `cursor` instead of `foreground` and `background`
+    match (section, key.as_str()) {
+        (Some("cursor" | "vi_mode_cursor" | "selection"), "foreground") => return "text".into(),
+        (Some("cursor" | "vi_mode_cursor"), "background") => return "cursor".into(),
+        _ => (),
+    }
+
+    match COLOR_ALIASES.iter().find(|(alias, _)| *alias == key) {
+        Some((_, name)) => (*name).to_owned(),
+        None => key,
+    }
+}
+
+/// Converts `0xRRGGBB` and `#RRGGBB` notations into the lowercase `#rrggbb` form.
+fn normalize_color(value: String) -> String {
+    let trimmed = value.trim();
+    let hex = trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X"));
+
+    match hex {
+        Some(h) if h.chars().all(|c| c.is_ascii_hexdigit()) => format!("#{}", h.to_lowercase()),
+        _ if trimmed.starts_with('#') => trimmed.to_lowercase(),
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Imports the theme into a temporary scheme directory and returns the `colors` section.
+    fn import(file_name: &str, theme: &str) -> anyhow::Result<Yaml> {
+        let dir = std::env::temp_dir().join(format!(
+            "alco-import-{}-{}",
+            std::process::id(),
+            file_name.replace('.', "-")
+        ));
+        fs::create_dir_all(&dir)?;
+        let theme_file = dir.join(file_name);
+        fs::write(&theme_file, theme)?;
+
+        let scheme_dir = dir.join("schemes");
+        let imported = import_alacritty_theme(&theme_file.to_string_lossy(), &scheme_dir, None);
+        let scheme_str = imported.and_then(|(_, path)| Ok(fs::read_to_string(path)?));
+        let scheme_written = scheme_dir.exists();
+        fs::remove_dir_all(&dir)?;
+
+        let scheme = match scheme_str {
+            Ok(s) => YamlLoader::load_from_str(&s)?.remove(0),
+            Err(e) => {
+                assert!(!scheme_written);
+                return Err(e);
+            }
+        };
+        Ok(scheme["colors"].clone())
+    }
+
+    #[test]
+    fn yaml_theme() {
+        let theme = concat!(
+            "colors:\n",
+            "  primary:\n",
+            "    background: '0x1D1F21'\n",
+            "    foreground: '#C5C8C6'\n",
+            "  cursor:\n",
+            "    foreground: '#000000'\n",
+            "  normal:\n",
+            "    purple: '0xb294bb'\n",
+        );
+        let colors = import("tomorrow.yml", theme).unwrap();
+        assert_eq!(colors["primary"]["background"].as_str(), Some("#1d1f21"));
+        assert_eq!(colors["primary"]["foreground"].as_str(), Some("#c5c8c6"));
+        assert_eq!(colors["cursor"]["text"].as_str(), Some("#000000"));
+        assert_eq!(colors["normal"]["magenta"].as_str(), Some("#b294bb"));
+    }
+
+    #[test]
+    fn toml_theme() {
+        let theme = concat!(
+            "[colors.primary]\n",
+            "background = \"#282828\"\n",
+            "\n",
+            "[colors.normal]\n",
+            "red = \"0xCC241D\"\n",
+        );
+        let colors = import("gruvbox.toml", theme).unwrap();
+        assert_eq!(colors["primary"]["background"].as_str(), Some("#282828"));
+        assert_eq!(colors["normal"]["red"].as_str(), Some("#cc241d"));
+    }
+
+    #[test]
+    fn malformed_theme() {
+        assert!(import("broken.toml", "[colors.primary\nbackground = \"#282828\"\n").is_err());
+        assert!(import("broken.yml", "colors:\n  primary: [\n").is_err());
+        assert!(import("list.yml", "- '#282828'\n").is_err());
+    }
+}
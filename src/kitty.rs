@@ -3,34 +3,83 @@ use shellexpand::tilde;
 use yaml_rust::YamlLoader;
 
 use std::fs;
+use std::io::{Read, Write};
 use std::os::unix::net::UnixStream;
 use std::path::Path;
 use std::process::Command;
+use std::time::Duration;
+
+const PING_COMMAND: &[u8] = b"\x1bP@kitty-cmd{\"cmd\":\"ls\",\"version\":[0,14,2]}\x1b\\";
 
 pub fn reload_kitty(
     config_file: impl AsRef<Path>,
     socket_file: impl AsRef<Path>,
     selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
     colorscheme: impl AsRef<str>,
 ) -> anyhow::Result<()> {
+    let config = render_kitty(selector, scheme_dir, colorscheme)?;
+    fs::write(config_file.as_ref(), config)?;
+    notify_kitty(socket_file)
+}
+
+/// Makes the kitty instance listening on `socket_file` reload its configuration.
+pub fn notify_kitty(socket_file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let socket_file = socket_file.as_ref();
+    if Path::exists(socket_file) {
+        let unix_stream = UnixStream::connect(socket_file)?;
+        let (pid, _, _) = unix_cred::get_peer_pid_ids(&unix_stream)?;
+        if let Some(pid) = pid {
+            Command::new("kill").arg("-s").arg("USR1").arg(pid.to_string()).output()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Sends a harmless remote control command to the kitty instance listening on `socket_file`.
+pub fn ping_kitty(socket_file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let mut stream = UnixStream::connect(socket_file.as_ref())
+        .map_err(|_| anyhow!("Error connecting to kitty socket"))?;
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    stream.write_all(PING_COMMAND)?;
+
+    let mut buf = [0; 256];
+    let n = stream.read(&mut buf)?;
+    let response = String::from_utf8_lossy(&buf[..n]);
+    let response = response.replace(' ', "");
+    if !response.starts_with("\x1bP@kitty-cmd") {
+        bail!("Unexpected response from kitty");
+    } else if !response.contains("\"ok\":true") {
+        bail!("Kitty refused the remote control command");
+    }
+
+    Ok(())
+}
+
+pub fn render_kitty(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
     let selector_str = fs::read_to_string(selector.as_ref())
         .map_err(|_| anyhow!("Error reading kitty selector"))?;
     let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
 
-    match super::selector(&selector, colorscheme.as_ref()) {
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
         Some(s) => {
-            fs::copy(tilde(s).as_ref(), config_file.as_ref())?;
-
-            let socket_file = socket_file.as_ref();
-            if Path::exists(socket_file) {
-                let unix_stream = UnixStream::connect(socket_file)?;
-                let (pid, _, _) = unix_cred::get_peer_pid_ids(&unix_stream)?;
-                if let Some(pid) = pid {
-                    Command::new("kill").arg("-s").arg("USR1").arg(pid.to_string()).output()?;
-                }
+            let mut config = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading kitty colorscheme file"))?;
+
+            let overrides = super::target_overrides(scheme_dir, colorscheme.as_ref(), "kitty")?;
+            if !overrides.is_empty() && !config.ends_with('\n') {
+                config.push('\n');
+            }
+            for (k, v) in overrides {
+                config.push_str(&format!("{} {}\n", k, v));
             }
 
-            Ok(())
+            Ok(config)
         }
         None => bail!("Missing mapping in kitty selector"),
     }
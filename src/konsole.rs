@@ -0,0 +1,80 @@
+use anyhow::{anyhow, bail};
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// The prefix of the bus names Konsole instances own, which is followed by their pid unless
+/// Konsole runs as a single instance.
+const SERVICE_PREFIX: &str = "org.kde.konsole";
+
+/// Switches the sessions of all running Konsole instances to the profile mapped by the selector,
+/// which applies its color scheme. The profile also becomes the default profile of their windows,
+/// so new tabs use it as well. Nothing is done if no instance is running.
+pub fn reload_konsole(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let profile = konsole_profile(selector, &scheme_dir, colorscheme)?;
+
+    // without a session bus there are no instances that could be reached
+    let services = match qdbus(&[]) {
+        Ok(o) if o.status.success() => lines(&o),
+        _ => return Ok(()),
+    };
+    let services = services.into_iter().filter(|s| {
+        s.strip_prefix(SERVICE_PREFIX).is_some_and(|p| p.is_empty() || p.starts_with('-'))
+    });
+    for service in services {
+        let output = qdbus(&[&service])?;
+        for path in lines(&output) {
+            let method = if path.starts_with("/Sessions/") {
+                "org.kde.konsole.Session.setProfile"
+            } else if path.starts_with("/Windows/") {
+                "org.kde.konsole.Window.setDefaultProfile"
+            } else {
+                continue;
+            };
+            let output = qdbus(&[&service, &path, method, &profile])?;
+            if !output.status.success() {
+                bail!(
+                    "Error setting konsole profile:\n{}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The name of the Konsole profile the selector maps the colorscheme to.
+fn konsole_profile(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading konsole selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(p) => Ok(p.to_owned()),
+        None => bail!("Missing mapping in konsole selector"),
+    }
+}
+
+/// Runs `qdbus6`, which replaced `qdbus` in Plasma 6, or `qdbus` if it isn't installed.
+fn qdbus(args: &[&str]) -> anyhow::Result<Output> {
+    match Command::new("qdbus6").args(args).output() {
+        Err(e) if e.kind() == ErrorKind::NotFound => (),
+        output => return output.map_err(|e| anyhow!("Error running qdbus6:\n{}", e)),
+    }
+    Command::new("qdbus").args(args).output().map_err(|e| anyhow!("Error running qdbus:\n{}", e))
+}
+
+fn lines(output: &Output) -> Vec<String> {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_owned).collect()
+}
@@ -2,21 +2,119 @@ use anyhow::{anyhow, bail};
 use serde::{Deserialize, Serialize};
 use yaml_rust::Yaml;
 
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
-pub use alacritty::reload_alacritty;
-pub use bat::reload_bat;
-pub use cmus::reload_cmus;
-pub use delta::reload_delta;
-pub use kitty::reload_kitty;
-pub use nvim::reload_neovim;
-pub use starship::reload_starship;
-pub use tmux::reload_tmux;
+pub use alacritty::{
+    alacritty_font, alacritty_live_reload, alacritty_opacity, reload_alacritty, render_alacritty,
+};
+pub use audit::{
+    audit_entries, audit_log_path, log_changes, read_files, AuditEntry, FileChange, FileContents,
+};
+pub use bat::{notify_bat, reload_bat, render_bat};
+pub use calendar::pinned_colorscheme;
+pub use clock::watch_clock;
+pub use cmus::{notify_cmus, ping_cmus, reload_cmus, render_cmus};
+pub use colorscheme::{
+    find_colorschemes, load_adhoc_scheme, load_colorscheme, map_colors, Color, Colorscheme, Filter,
+    FoundColor, SchemeFile, SchemeMeta, SchemeSource, SCHEME_VERSION,
+};
+pub use compare::{compare, end_comparison, Comparison};
+pub use config::{
+    BatteryConfig, CalendarConfig, Config, DaemonConfig, DimConfig, FollowSystemConfig,
+    GammaConfig, GammaProgram, IdleConfig, Profile, RefreshConfig, ScheduleConfig, TargetConfig,
+    TemplateConfig,
+};
+pub use convert::{convert, PaletteFormat};
+pub use daemon::{Appearance, Event, Reply, TimeOfDay};
+pub use delta::{reload_delta, render_delta};
+pub use diff::{diff, Hunk};
+pub use dunst::{notify_dunst, reload_dunst, render_dunst};
+pub use editor::edit_selector;
+pub use emacs::reload_emacs;
+pub use fifo::{fifo_path, watch_fifo};
+pub use fish::{notify_fish, reload_fish, render_fish};
+pub use focus::watch_focus;
+pub use font::Font;
+pub use foot::{notify_foot, reload_foot, render_foot};
+pub use fzf::{reload_fzf, render_fzf};
+pub use gamma::set_temperature;
+pub use ghostty::{notify_ghostty, reload_ghostty, render_ghostty};
+pub use gtk::{gtk_settings, reload_gtk};
+pub use hook::shell_hook;
+pub use hyprland::{notify_hyprland, reload_hyprland, render_hyprland};
+pub use i18n::tr;
+pub use i3::{notify_i3, reload_i3, render_i3};
+pub use idle::watch_idle;
+pub use import::{
+    fetch_update, import_alacritty_theme, imported_schemes, update_scheme, SchemeUpdate,
+};
+pub use kitty::{notify_kitty, ping_kitty, reload_kitty, render_kitty};
+pub use konsole::reload_konsole;
+pub use mako::{notify_mako, reload_mako, render_mako};
+pub use metrics::serve_metrics;
+pub use migrate::migrate_schemes;
+pub use nvim::{ping_neovim, reload_neovim, reload_neovim_instance};
+pub use osc::{is_ssh_session, osc_sequences, write_osc, write_osc_tty, Passthrough};
+pub use pack::{export_pack, install_pack, InstalledPack};
+pub use picom::{notify_picom, reload_picom, render_picom};
+pub use polybar::{notify_polybar, reload_polybar, render_polybar};
+pub use portal::watch_appearance;
+pub use post_apply::run_post_apply_hooks;
+pub use power::watch_power;
+pub use qutebrowser::{notify_qutebrowser, reload_qutebrowser, render_qutebrowser};
+pub use refresh::refresh_status_bars;
+pub use rofi::{reload_rofi, render_rofi};
+pub use roles::color_roles;
+pub use schedule::watch_schedule;
+pub use script::Script;
+pub use setup::{detect_targets, import_starter_schemes, write_reload_targets, STARTER_SCHEMES};
+pub use snapshot::{gallery, snapshot, snapshot_svg};
+pub use socket::{send_command, socket_path, watch_socket};
+pub use starship::{reload_starship, render_starship, starship_module};
+pub use targets::{
+    apply_here, ping, plan, prerender, reload_targets, render_target, render_to_dir, resolve,
+    Change, Resolution, BLOCK_BEGIN, BLOCK_END,
+};
+pub use targets::{
+    AlacrittyOptions, BatOptions, CmusOptions, DeltaOptions, DunstOptions, EmacsOptions,
+    FishOptions, FootOptions, FzfOptions, GhosttyOptions, GtkOptions, HyprlandOptions, I3Options,
+    KittyOptions, KonsoleOptions, MakoOptions, NeovimOptions, Options, PicomOptions,
+    PolybarOptions, QutebrowserOptions, RofiOptions, StarshipOptions, Strategy, Target,
+    TmuxOptions, VimOptions, VscodeOptions, WaybarOptions, WeztermOptions, XresourcesOptions,
+    ZathuraOptions, ZellijOptions,
+};
+pub use template::{render_template, render_templates};
+pub use terminal::{terminal_delivery, Delivery, Terminal};
+pub use themes::{add_selector_entries, generate_selector, selector_values, Proposal};
+pub use tmux::{
+    notify_tmux, ping_tmux, refresh_tmux, reload_tmux, render_tmux, style_tmux_pane,
+    style_tmux_session, tmux_sessions,
+};
+pub use vim::{ping_vim, reload_vim};
+pub use vscode::{reload_vscode, render_vscode};
+pub use wallpaper::{extract_palette, suggest_colorschemes};
+pub use watch::watch_files;
+pub use waybar::{notify_waybar, reload_waybar, render_waybar};
+pub use wezterm::{notify_wezterm, reload_wezterm, render_wezterm};
+pub use workspace::{terminal_windows, watch_workspaces, TerminalWindow};
+pub use xresources::{notify_xresources, reload_xresources, render_xresources};
+pub use zathura::{notify_zathura, reload_zathura, render_zathura};
+pub use zellij::{notify_zellij, reload_zellij, render_zellij};
 
 pub const DEFAULT_COLORSCHEME_FILE: &str = "~/.config/alco/colors.yml";
 pub const DEFAULT_CONFIG_FILE: &str = "~/.config/alco/alco.yml";
+pub const DEFAULT_SETTINGS_FILE: &str = "~/.config/alco/config.toml";
+pub const DEFAULT_SCRIPT_FILE: &str = "~/.config/alco/init.lua";
+pub const DEFAULT_TEMPLATE_DIR: &str = "~/.config/alco/templates";
+pub const DEFAULT_HOOK_DIR: &str = "~/.config/alco/hooks";
+pub const DEFAULT_SCHEME_DIR: &str = "~/.config/alco/schemes";
+pub const DEFAULT_CACHE_DIR: &str = "~/.cache/alco";
+pub const DEFAULT_PACK_DIR: &str = "~/.config/alco/packs";
+pub const DEFAULT_STATE_DIR: &str = "~/.local/state/alco";
 
 pub const DEFAULT_ALACRITTY_FILE: &str = "~/.config/alacritty/alacritty.yml";
 pub const DEFAULT_ALACRITTY_IN_FILE: &str = "~/.config/alacritty/alacritty.yml.in";
@@ -44,6 +142,42 @@ pub const DEFAULT_DELTA_FILE: &str = "~/.config/delta/colors/current.gitconfig";
 pub const DEFAULT_DELTA_SELECTOR: &str = "~/.config/alco/delta-selector.yml";
 
 pub const DEFAULT_CMUS_SELECTOR: &str = "~/.config/alco/cmus-selector.yml";
+pub const CMUS_AUTOSAVE_FILE: &str = "~/.config/cmus/autosave";
+pub const DEFAULT_WEZTERM_FILE: &str = "~/.config/wezterm/colors/current.toml";
+pub const DEFAULT_WEZTERM_SELECTOR: &str = "~/.config/alco/wezterm-selector.yml";
+pub const DEFAULT_WEZTERM_CONFIG: &str = "~/.config/wezterm/wezterm.lua";
+pub const DEFAULT_FOOT_FILE: &str = "~/.config/foot/colors/current.ini";
+pub const DEFAULT_FOOT_SELECTOR: &str = "~/.config/alco/foot-selector.yml";
+pub const DEFAULT_ZELLIJ_FILE: &str = "~/.config/zellij/themes/alco.kdl";
+pub const DEFAULT_ZELLIJ_SELECTOR: &str = "~/.config/alco/zellij-selector.yml";
+pub const DEFAULT_ZELLIJ_CONFIG: &str = "~/.config/zellij/config.kdl";
+pub const DEFAULT_FISH_FILE: &str = "~/.config/fish/themes/alco.theme";
+pub const DEFAULT_FISH_SELECTOR: &str = "~/.config/alco/fish-selector.yml";
+pub const DEFAULT_FZF_FILE: &str = "~/.config/alco/fzf-colors.sh";
+pub const DEFAULT_ROFI_FILE: &str = "~/.config/rofi/colors/current.rasi";
+pub const DEFAULT_ROFI_SELECTOR: &str = "~/.config/alco/rofi-selector.yml";
+pub const DEFAULT_DUNST_FILE: &str = "~/.config/dunst/dunstrc.d/alco.conf";
+pub const DEFAULT_WAYBAR_FILE: &str = "~/.config/waybar/colors.css";
+pub const DEFAULT_POLYBAR_FILE: &str = "~/.config/polybar/colors.ini";
+pub const DEFAULT_PICOM_FILE: &str = "~/.config/picom/alco.conf";
+pub const DEFAULT_HYPRLAND_FILE: &str = "~/.config/hypr/colors.conf";
+pub const DEFAULT_GTK_SELECTOR: &str = "~/.config/alco/gtk-selector.yml";
+pub const DEFAULT_POLYBAR_SELECTOR: &str = "~/.config/alco/polybar-selector.yml";
+pub const DEFAULT_I3_FILE: &str = "~/.config/i3/colors.conf";
+pub const DEFAULT_I3_SELECTOR: &str = "~/.config/alco/i3-selector.yml";
+pub const DEFAULT_QUTEBROWSER_FILE: &str = "~/.config/qutebrowser/colors.py";
+pub const DEFAULT_QUTEBROWSER_SELECTOR: &str = "~/.config/alco/qutebrowser-selector.yml";
+pub const DEFAULT_VSCODE_FILE: &str = "~/.config/Code/User/settings.json";
+pub const DEFAULT_VSCODE_SELECTOR: &str = "~/.config/alco/vscode-selector.yml";
+pub const DEFAULT_ZATHURA_FILE: &str = "~/.config/zathura/alco";
+pub const DEFAULT_MAKO_FILE: &str = "~/.config/mako/config";
+pub const DEFAULT_MAKO_SELECTOR: &str = "~/.config/alco/mako-selector.yml";
+pub const DEFAULT_GHOSTTY_FILE: &str = "~/.config/ghostty/config";
+pub const DEFAULT_GHOSTTY_SELECTOR: &str = "~/.config/alco/ghostty-selector.yml";
+pub const DEFAULT_KONSOLE_SELECTOR: &str = "~/.config/alco/konsole-selector.yml";
+pub const DEFAULT_EMACS_SELECTOR: &str = "~/.config/alco/emacs-selector.yml";
+pub const DEFAULT_VIM_SELECTOR: &str = "~/.config/alco/vim-selector.yml";
+pub const DEFAULT_XRESOURCES_FILE: &str = "~/.config/alco/Xresources";
 
 #[cfg(feature = "alacritty")]
 mod alacritty;
@@ -53,12 +187,37 @@ mod alacritty {
     use std::path::Path;
 
     pub fn reload_alacritty(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
         _: impl AsRef<Path>,
         _: impl AsRef<Path>,
         _: impl AsRef<str>,
+        _: &[String],
     ) -> anyhow::Result<()> {
         bail!("alco was compiled without the alacritty feature flag")
     }
+
+    pub fn render_alacritty(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+        _: &[String],
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the alacritty feature flag")
+    }
+
+    pub fn alacritty_live_reload(_: impl AsRef<Path>) -> bool {
+        true
+    }
+
+    pub fn alacritty_opacity(_: &str, _: f32) -> anyhow::Result<String> {
+        bail!("alco was compiled without the alacritty feature flag")
+    }
+
+    pub fn alacritty_font(_: &str, _: &crate::Font) -> anyhow::Result<String> {
+        bail!("alco was compiled without the alacritty feature flag")
+    }
 }
 
 #[cfg(feature = "kitty")]
@@ -68,120 +227,1181 @@ mod kitty {
     use anyhow::bail;
     use std::path::Path;
 
-    pub fn reload_kitty(
+    pub fn reload_kitty(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the kitty feature flag")
+    }
+
+    pub fn render_kitty(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the kitty feature flag")
+    }
+
+    pub fn ping_kitty(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the kitty feature flag")
+    }
+
+    pub fn notify_kitty(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the kitty feature flag")
+    }
+}
+
+#[cfg(feature = "tmux")]
+mod tmux;
+#[cfg(not(feature = "tmux"))]
+mod tmux {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_tmux(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub fn render_tmux(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub fn ping_tmux() -> anyhow::Result<()> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub fn notify_tmux(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub fn refresh_tmux() -> anyhow::Result<()> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub fn style_tmux_pane(_: &str, _: &crate::Colorscheme) -> anyhow::Result<()> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub fn style_tmux_session(_: &str, _: &crate::Colorscheme) -> anyhow::Result<()> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub(crate) fn unstyle_tmux_pane(_: &str) -> anyhow::Result<()> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub(crate) fn split_tmux_pane(_: &str, _: &[&str]) -> anyhow::Result<String> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub(crate) fn kill_tmux_pane(_: &str) -> anyhow::Result<()> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+
+    pub fn tmux_sessions() -> anyhow::Result<Vec<String>> {
+        bail!("alco was compiled without the tmux feature flag")
+    }
+}
+
+#[cfg(feature = "neovim")]
+mod nvim;
+#[cfg(not(feature = "neovim"))]
+mod nvim {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub async fn reload_neovim(_: impl AsRef<str>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the neovim feature flag")
+    }
+
+    pub async fn ping_neovim() -> anyhow::Result<usize> {
+        bail!("alco was compiled without the neovim feature flag")
+    }
+
+    pub async fn reload_neovim_instance(
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+        _: &str,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the neovim feature flag")
+    }
+}
+
+#[cfg(feature = "starship")]
+mod starship;
+#[cfg(not(feature = "starship"))]
+mod starship {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_starship(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the starship feature flag")
+    }
+
+    pub fn render_starship(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the starship feature flag")
+    }
+
+    pub fn starship_module(_: &str, _: Option<&crate::Colorscheme>) -> String {
+        String::new()
+    }
+}
+
+#[cfg(feature = "bat")]
+mod bat;
+#[cfg(not(feature = "bat"))]
+mod bat {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_bat(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the bat feature flag")
+    }
+
+    pub fn render_bat(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the bat feature flag")
+    }
+
+    pub fn notify_bat(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the bat feature flag")
+    }
+}
+
+#[cfg(feature = "delta")]
+mod delta;
+#[cfg(not(feature = "delta"))]
+mod delta {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_delta(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the delta feature flag")
+    }
+
+    pub fn render_delta(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the delta feature flag")
+    }
+}
+
+#[cfg(feature = "cmus")]
+mod cmus;
+#[cfg(not(feature = "cmus"))]
+mod cmus {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_cmus(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the cmus feature flag")
+    }
+
+    pub fn render_cmus(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the cmus feature flag")
+    }
+
+    pub fn ping_cmus() -> anyhow::Result<()> {
+        bail!("alco was compiled without the cmus feature flag")
+    }
+
+    pub fn notify_cmus(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the cmus feature flag")
+    }
+}
+
+#[cfg(feature = "wezterm")]
+mod wezterm;
+#[cfg(not(feature = "wezterm"))]
+mod wezterm {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_wezterm(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the wezterm feature flag")
+    }
+
+    pub fn render_wezterm(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the wezterm feature flag")
+    }
+
+    pub fn notify_wezterm(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the wezterm feature flag")
+    }
+}
+
+#[cfg(feature = "foot")]
+mod foot;
+#[cfg(not(feature = "foot"))]
+mod foot {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_foot(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the foot feature flag")
+    }
+
+    pub fn render_foot(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the foot feature flag")
+    }
+
+    pub fn notify_foot(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the foot feature flag")
+    }
+}
+
+#[cfg(feature = "zellij")]
+mod zellij;
+#[cfg(not(feature = "zellij"))]
+mod zellij {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_zellij(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the zellij feature flag")
+    }
+
+    pub fn render_zellij(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the zellij feature flag")
+    }
+
+    pub fn notify_zellij(_: impl AsRef<Path>, _: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the zellij feature flag")
+    }
+}
+
+#[cfg(feature = "fish")]
+mod fish;
+#[cfg(not(feature = "fish"))]
+mod fish {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_fish(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the fish feature flag")
+    }
+
+    pub fn render_fish(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the fish feature flag")
+    }
+
+    pub fn notify_fish(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the fish feature flag")
+    }
+
+    pub(crate) fn fish_script(_: &str) -> String {
+        String::new()
+    }
+}
+
+#[cfg(feature = "fzf")]
+mod fzf;
+#[cfg(not(feature = "fzf"))]
+mod fzf {
+    use anyhow::bail;
+    use std::path::Path;
+
+    use crate::Colorscheme;
+
+    pub fn reload_fzf(_: impl AsRef<Path>, _: &Colorscheme) -> anyhow::Result<()> {
+        bail!("alco was compiled without the fzf feature flag")
+    }
+
+    pub fn render_fzf(_: &Colorscheme) -> anyhow::Result<String> {
+        bail!("alco was compiled without the fzf feature flag")
+    }
+}
+
+#[cfg(feature = "rofi")]
+mod rofi;
+#[cfg(not(feature = "rofi"))]
+mod rofi {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_rofi(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the rofi feature flag")
+    }
+
+    pub fn render_rofi(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the rofi feature flag")
+    }
+}
+
+#[cfg(feature = "dunst")]
+mod dunst;
+#[cfg(not(feature = "dunst"))]
+mod dunst {
+    use anyhow::bail;
+    use std::path::Path;
+
+    use crate::Colorscheme;
+
+    pub fn reload_dunst(_: impl AsRef<Path>, _: &Colorscheme) -> anyhow::Result<()> {
+        bail!("alco was compiled without the dunst feature flag")
+    }
+
+    pub fn notify_dunst() -> anyhow::Result<()> {
+        bail!("alco was compiled without the dunst feature flag")
+    }
+
+    pub fn render_dunst(_: &Colorscheme) -> anyhow::Result<String> {
+        bail!("alco was compiled without the dunst feature flag")
+    }
+}
+
+#[cfg(feature = "waybar")]
+mod waybar;
+#[cfg(not(feature = "waybar"))]
+mod waybar {
+    use anyhow::bail;
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use crate::{Color, Colorscheme};
+
+    pub fn reload_waybar(
+        _: impl AsRef<Path>,
+        _: &Colorscheme,
+        _: &BTreeMap<String, Color>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the waybar feature flag")
+    }
+
+    pub fn notify_waybar() -> anyhow::Result<()> {
+        bail!("alco was compiled without the waybar feature flag")
+    }
+
+    pub fn render_waybar(_: &Colorscheme, _: &BTreeMap<String, Color>) -> anyhow::Result<String> {
+        bail!("alco was compiled without the waybar feature flag")
+    }
+}
+
+#[cfg(feature = "polybar")]
+mod polybar;
+#[cfg(not(feature = "polybar"))]
+mod polybar {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_polybar(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the polybar feature flag")
+    }
+
+    pub fn notify_polybar() -> anyhow::Result<()> {
+        bail!("alco was compiled without the polybar feature flag")
+    }
+
+    pub fn render_polybar(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the polybar feature flag")
+    }
+}
+
+#[cfg(feature = "picom")]
+mod picom;
+#[cfg(not(feature = "picom"))]
+mod picom {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_picom(_: impl AsRef<Path>, _: Option<f32>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the picom feature flag")
+    }
+
+    pub fn notify_picom() -> anyhow::Result<()> {
+        bail!("alco was compiled without the picom feature flag")
+    }
+
+    pub fn render_picom(_: Option<f32>) -> anyhow::Result<String> {
+        bail!("alco was compiled without the picom feature flag")
+    }
+}
+
+#[cfg(feature = "i3")]
+mod i3;
+#[cfg(not(feature = "i3"))]
+mod i3 {
+    use anyhow::bail;
+    use std::path::Path;
+
+    use crate::Colorscheme;
+
+    pub fn reload_i3(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+        _: &Colorscheme,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the i3 feature flag")
+    }
+
+    pub fn notify_i3() -> anyhow::Result<()> {
+        bail!("alco was compiled without the i3 feature flag")
+    }
+
+    pub fn render_i3(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+        _: &Colorscheme,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the i3 feature flag")
+    }
+}
+
+#[cfg(feature = "hyprland")]
+mod hyprland;
+#[cfg(not(feature = "hyprland"))]
+mod hyprland {
+    use anyhow::bail;
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use crate::{Color, Colorscheme};
+
+    pub fn reload_hyprland(
+        _: impl AsRef<Path>,
+        _: &Colorscheme,
+        _: &BTreeMap<String, Color>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the hyprland feature flag")
+    }
+
+    pub fn notify_hyprland(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the hyprland feature flag")
+    }
+
+    pub(crate) fn hyprctl_batch(_: &str) -> String {
+        String::new()
+    }
+
+    pub fn render_hyprland(_: &Colorscheme, _: &BTreeMap<String, Color>) -> anyhow::Result<String> {
+        bail!("alco was compiled without the hyprland feature flag")
+    }
+}
+
+#[cfg(feature = "gtk")]
+mod gtk;
+#[cfg(not(feature = "gtk"))]
+mod gtk {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub(crate) const SCHEMA: &str = "org.gnome.desktop.interface";
+
+    pub fn reload_gtk(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the gtk feature flag")
+    }
+
+    pub fn gtk_settings(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<Vec<(&'static str, String)>> {
+        bail!("alco was compiled without the gtk feature flag")
+    }
+}
+
+#[cfg(feature = "qutebrowser")]
+mod qutebrowser;
+#[cfg(not(feature = "qutebrowser"))]
+mod qutebrowser {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_qutebrowser(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the qutebrowser feature flag")
+    }
+
+    pub fn notify_qutebrowser(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the qutebrowser feature flag")
+    }
+
+    pub fn render_qutebrowser(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the qutebrowser feature flag")
+    }
+}
+
+#[cfg(feature = "vscode")]
+mod vscode;
+#[cfg(not(feature = "vscode"))]
+mod vscode {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_vscode(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the vscode feature flag")
+    }
+
+    pub fn render_vscode(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the vscode feature flag")
+    }
+}
+
+#[cfg(feature = "zathura")]
+mod zathura;
+#[cfg(not(feature = "zathura"))]
+mod zathura {
+    use anyhow::bail;
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    use crate::{Color, Colorscheme};
+
+    pub fn reload_zathura(
+        _: impl AsRef<Path>,
+        _: &Colorscheme,
+        _: &BTreeMap<String, Color>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the zathura feature flag")
+    }
+
+    pub fn notify_zathura(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the zathura feature flag")
+    }
+
+    pub(crate) fn zathura_commands(_: &str) -> Vec<&str> {
+        Vec::new()
+    }
+
+    pub fn render_zathura(_: &Colorscheme, _: &BTreeMap<String, Color>) -> anyhow::Result<String> {
+        bail!("alco was compiled without the zathura feature flag")
+    }
+}
+
+#[cfg(feature = "mako")]
+mod mako;
+#[cfg(not(feature = "mako"))]
+mod mako {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_mako(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the mako feature flag")
+    }
+
+    pub fn notify_mako() -> anyhow::Result<()> {
+        bail!("alco was compiled without the mako feature flag")
+    }
+
+    pub fn render_mako(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the mako feature flag")
+    }
+}
+
+#[cfg(feature = "ghostty")]
+mod ghostty;
+#[cfg(not(feature = "ghostty"))]
+mod ghostty {
+    use anyhow::bail;
+    use std::path::Path;
+
+    pub fn reload_ghostty(
         _: impl AsRef<Path>,
         _: impl AsRef<Path>,
         _: impl AsRef<Path>,
         _: impl AsRef<str>,
     ) -> anyhow::Result<()> {
-        bail!("alco was compiled without the kitty feature flag")
+        bail!("alco was compiled without the ghostty feature flag")
     }
-}
 
-#[cfg(feature = "tmux")]
-mod tmux;
-#[cfg(not(feature = "tmux"))]
-mod tmux {
-    use anyhow::bail;
-    use std::path::Path;
+    pub fn notify_ghostty() -> anyhow::Result<()> {
+        bail!("alco was compiled without the ghostty feature flag")
+    }
 
-    pub fn reload_tmux(
+    pub fn render_ghostty(
+        _: impl AsRef<Path>,
         _: impl AsRef<Path>,
         _: impl AsRef<Path>,
         _: impl AsRef<str>,
-    ) -> anyhow::Result<()> {
-        bail!("alco was compiled without the tmux feature flag")
+    ) -> anyhow::Result<String> {
+        bail!("alco was compiled without the ghostty feature flag")
     }
 }
 
-#[cfg(feature = "neovim")]
-mod nvim;
-#[cfg(not(feature = "neovim"))]
-mod nvim {
+#[cfg(feature = "konsole")]
+mod konsole;
+#[cfg(not(feature = "konsole"))]
+mod konsole {
     use anyhow::bail;
     use std::path::Path;
 
-    pub async fn reload_neovim(_: impl AsRef<str>) -> anyhow::Result<()> {
-        bail!("alco was compiled without the neovim feature flag")
+    pub fn reload_konsole(
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+        _: impl AsRef<str>,
+    ) -> anyhow::Result<()> {
+        bail!("alco was compiled without the konsole feature flag")
     }
 }
 
-#[cfg(feature = "starship")]
-mod starship;
-#[cfg(not(feature = "starship"))]
-mod starship {
+#[cfg(feature = "emacs")]
+mod emacs;
+#[cfg(not(feature = "emacs"))]
+mod emacs {
     use anyhow::bail;
     use std::path::Path;
 
-    pub fn reload_starship(
-        _: impl AsRef<Path>,
+    pub fn reload_emacs(
         _: impl AsRef<Path>,
         _: impl AsRef<Path>,
         _: impl AsRef<str>,
     ) -> anyhow::Result<()> {
-        bail!("alco was compiled without the starship feature flag")
+        bail!("alco was compiled without the emacs feature flag")
+    }
+
+    pub(crate) fn load_theme(_: &str) -> String {
+        String::new()
     }
 }
 
-#[cfg(feature = "bat")]
-mod bat;
-#[cfg(not(feature = "bat"))]
-mod bat {
+#[cfg(feature = "vim")]
+mod vim;
+#[cfg(not(feature = "vim"))]
+mod vim {
     use anyhow::bail;
     use std::path::Path;
 
-    pub fn reload_bat(
-        _: impl AsRef<Path>,
+    pub fn reload_vim(
         _: impl AsRef<Path>,
         _: impl AsRef<Path>,
         _: impl AsRef<str>,
     ) -> anyhow::Result<()> {
-        bail!("alco was compiled without the bat feature flag")
+        bail!("alco was compiled without the vim feature flag")
+    }
+
+    pub fn ping_vim() -> anyhow::Result<()> {
+        bail!("alco was compiled without the vim feature flag")
+    }
+
+    pub(crate) fn remote_keys(_: &str) -> String {
+        String::new()
     }
 }
 
-#[cfg(feature = "delta")]
-mod delta;
-#[cfg(not(feature = "delta"))]
-mod delta {
+#[cfg(feature = "xresources")]
+mod xresources;
+#[cfg(not(feature = "xresources"))]
+mod xresources {
     use anyhow::bail;
     use std::path::Path;
 
-    pub fn reload_delta(
-        _: impl AsRef<Path>,
-        _: impl AsRef<Path>,
-        _: impl AsRef<str>,
+    use crate::Colorscheme;
+
+    pub fn reload_xresources(_: impl AsRef<Path>, _: &Colorscheme) -> anyhow::Result<()> {
+        bail!("alco was compiled without the xresources feature flag")
+    }
+
+    pub fn notify_xresources(_: impl AsRef<Path>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the xresources feature flag")
+    }
+
+    pub fn render_xresources(_: &Colorscheme) -> anyhow::Result<String> {
+        bail!("alco was compiled without the xresources feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod focus;
+#[cfg(not(feature = "daemon"))]
+mod focus {
+    use anyhow::bail;
+    use std::sync::mpsc::Sender;
+
+    pub fn watch_focus(_: Sender<crate::Event>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod idle;
+#[cfg(not(feature = "daemon"))]
+mod idle {
+    use anyhow::bail;
+    use std::sync::mpsc::Sender;
+
+    pub fn watch_idle(_: u64, _: Sender<crate::Event>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod power;
+#[cfg(not(feature = "daemon"))]
+mod power {
+    use anyhow::bail;
+    use std::sync::mpsc::Sender;
+
+    pub fn watch_power(_: Sender<crate::Event>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod workspace;
+#[cfg(not(feature = "daemon"))]
+mod workspace {
+    use anyhow::bail;
+    use std::path::PathBuf;
+    use std::sync::mpsc::Sender;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct TerminalWindow {
+        pub workspace: String,
+        pub output: String,
+        pub ttys: Vec<PathBuf>,
+    }
+
+    pub fn watch_workspaces(_: Sender<crate::Event>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+
+    pub fn terminal_windows() -> anyhow::Result<Vec<TerminalWindow>> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod calendar;
+#[cfg(not(feature = "daemon"))]
+mod calendar {
+    use anyhow::bail;
+
+    use crate::DaemonConfig;
+
+    pub fn pinned_colorscheme(_: &DaemonConfig) -> anyhow::Result<Option<String>> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod clock;
+#[cfg(not(feature = "daemon"))]
+mod clock {
+    use anyhow::bail;
+    use std::sync::mpsc::Sender;
+
+    pub fn watch_clock(_: Sender<crate::Event>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod fifo;
+#[cfg(not(feature = "daemon"))]
+mod fifo {
+    use anyhow::bail;
+    use std::path::PathBuf;
+    use std::sync::mpsc::Sender;
+
+    pub fn fifo_path() -> anyhow::Result<PathBuf> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+
+    pub fn watch_fifo(_: Sender<crate::Event>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod portal;
+#[cfg(not(feature = "daemon"))]
+mod portal {
+    use anyhow::bail;
+    use std::sync::mpsc::Sender;
+
+    pub fn watch_appearance(_: Sender<crate::Appearance>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod schedule;
+#[cfg(not(feature = "daemon"))]
+mod schedule {
+    use anyhow::bail;
+    use std::sync::mpsc::Sender;
+
+    pub fn watch_schedule(
+        _: &crate::ScheduleConfig,
+        _: Sender<crate::TimeOfDay>,
     ) -> anyhow::Result<()> {
-        bail!("alco was compiled without the delta feature flag")
+        bail!("alco was compiled without the daemon feature flag")
     }
 }
 
-#[cfg(feature = "cmus")]
-mod cmus;
-#[cfg(not(feature = "cmus"))]
-mod cmus {
+#[cfg(feature = "daemon")]
+mod socket;
+#[cfg(not(feature = "daemon"))]
+mod socket {
+    use anyhow::bail;
+    use std::path::PathBuf;
+    use std::sync::mpsc::Sender;
+
+    pub fn socket_path() -> anyhow::Result<PathBuf> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+
+    pub fn watch_socket(_: Sender<crate::Event>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+
+    pub fn send_command(_: &str) -> anyhow::Result<String> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "daemon")]
+mod watch;
+#[cfg(not(feature = "daemon"))]
+mod watch {
+    use anyhow::bail;
+    use std::path::PathBuf;
+    use std::sync::mpsc::Sender;
+
+    pub fn watch_files(_: Vec<PathBuf>, _: Sender<crate::Event>) -> anyhow::Result<()> {
+        bail!("alco was compiled without the daemon feature flag")
+    }
+}
+
+#[cfg(feature = "http")]
+mod metrics;
+#[cfg(not(feature = "http"))]
+mod metrics {
+    use anyhow::bail;
+    use std::time::Duration;
+
+    use crate::Target;
+
+    pub(crate) fn record_apply(_: Duration) {}
+
+    pub(crate) fn record_reload(_: Target, _: bool, _: Duration) {}
+
+    pub fn serve_metrics(_: &str) -> anyhow::Result<()> {
+        bail!("alco was compiled without the http feature flag")
+    }
+}
+
+#[cfg(feature = "lua")]
+mod script;
+#[cfg(not(feature = "lua"))]
+mod script {
     use anyhow::bail;
     use std::path::Path;
 
-    pub fn reload_cmus(_: impl AsRef<Path>, _: impl AsRef<str>) -> anyhow::Result<()> {
-        bail!("alco was compiled without the tmux feature flag")
+    use crate::Options;
+
+    pub struct Script;
+
+    impl Script {
+        pub fn load(_: impl AsRef<Path>) -> anyhow::Result<Self> {
+            bail!("alco was compiled without the lua feature flag")
+        }
+
+        pub fn transform(&self, _: &mut Options, _: &str) -> anyhow::Result<()> {
+            bail!("alco was compiled without the lua feature flag")
+        }
+
+        pub fn run_targets(&self, _: &Options, _: &str) -> Vec<(String, anyhow::Result<()>)> {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(feature = "wallpaper")]
+mod wallpaper;
+#[cfg(not(feature = "wallpaper"))]
+mod wallpaper {
+    use anyhow::bail;
+    use std::path::Path;
+
+    use crate::{Color, Options};
+
+    pub fn extract_palette(_: impl AsRef<Path>) -> anyhow::Result<Vec<(Color, f32)>> {
+        bail!("alco was compiled without the wallpaper feature flag")
+    }
+
+    pub fn suggest_colorschemes(
+        _: &Options,
+        _: impl AsRef<Path>,
+        _: impl AsRef<Path>,
+    ) -> anyhow::Result<Vec<(String, f32)>> {
+        bail!("alco was compiled without the wallpaper feature flag")
+    }
+}
+
+#[cfg(feature = "i18n")]
+mod i18n;
+#[cfg(not(feature = "i18n"))]
+mod i18n {
+    /// Looks up the English message, without fluent only single line messages with plain
+    /// placeables are supported.
+    pub fn tr(id: &str, args: &[(&str, &str)]) -> String {
+        let messages = include_str!("../locales/en.ftl");
+        let message = messages.lines().find_map(|l| l.strip_prefix(id)?.strip_prefix(" = "));
+        let mut message = message.unwrap_or(id).to_owned();
+        for (name, value) in args {
+            message = message.replace(&format!("{{ ${} }}", name), value);
+        }
+        message
     }
 }
 
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+mod audit;
+mod colorscheme;
+mod compare;
+mod config;
+mod convert;
+mod daemon;
+mod diff;
+mod editor;
+mod font;
+mod gamma;
+mod hook;
+mod import;
+mod migrate;
+mod osc;
+mod pack;
+mod post_apply;
+mod refresh;
+mod roles;
+mod setup;
+mod snapshot;
+mod targets;
+mod template;
+mod terminal;
+mod themes;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 struct Colors {
     colors: Vec<String>,
+    /// How the listed colorschemes are sorted, `file` keeps the order of the file.
+    #[serde(default, skip_serializing_if = "Collation::is_natural")]
+    sort: Collation,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Collation {
+    /// Ignores case and compares numbers by their value, so `scheme2` comes before `scheme10`.
+    #[default]
+    Natural,
+    /// Compares the bytes of the names.
+    Lexical,
+    /// Keeps the order of the file.
+    File,
+}
+
+impl Collation {
+    fn is_natural(&self) -> bool {
+        *self == Collation::Natural
+    }
+
+    fn sort(self, colorschemes: &mut [String]) {
+        match self {
+            Collation::Natural => colorschemes.sort_by(|a, b| natural_cmp(a, b)),
+            Collation::Lexical => colorschemes.sort(),
+            Collation::File => (),
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
-struct Config {
+struct State {
     current: String,
     changed: SystemTime,
+    /// Targets that failed to reload when the current colorscheme was last applied.
+    #[serde(default)]
+    failed: Vec<String>,
+    /// The colorscheme that was active before presentation mode was turned on.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    previous: Option<String>,
+    /// The colorscheme that was active before the current one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last: Option<String>,
+    /// The color filters that are turned on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    filters: Vec<String>,
+    /// The window opacity set with `alco set opacity`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    opacity: Option<f32>,
+    /// When each colorscheme was last applied.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    applied: BTreeMap<String, SystemTime>,
 }
 
-impl Config {
+impl State {
     fn new(current: String, changed: SystemTime) -> Self {
-        Self { current, changed }
+        Self {
+            current,
+            changed,
+            failed: Vec::new(),
+            previous: None,
+            last: None,
+            filters: Vec::new(),
+            opacity: None,
+            applied: BTreeMap::new(),
+        }
+    }
+
+    /// When each colorscheme was last applied, including the current one for state files that
+    /// were written before the applied times were recorded.
+    fn applied(&self) -> BTreeMap<String, SystemTime> {
+        let mut applied = self.applied.clone();
+        applied.entry(self.current.clone()).or_insert(self.changed);
+        applied
     }
 
     fn now(current: String) -> Self {
@@ -189,21 +1409,50 @@ impl Config {
     }
 }
 
+/// A colorscheme of the colorscheme list, with information from its scheme file and the state
+/// file.
+#[derive(Clone, Debug)]
+pub struct ListEntry {
+    pub name: String,
+    /// The scheme file, if the colorscheme has one.
+    pub path: Option<PathBuf>,
+    /// Whether the colorscheme is `dark` or `light`.
+    pub variant: Option<String>,
+    pub tags: Vec<String>,
+    /// When the colorscheme was last applied, if it ever was.
+    pub applied: Option<SystemTime>,
+    /// Whether the colorscheme is disabled, see [`is_disabled`].
+    pub disabled: bool,
+}
+
 pub struct Status {
     pub current: String,
+    /// When the current colorscheme was applied.
+    pub changed: SystemTime,
     pub duration: Duration,
+    pub failed: Vec<Target>,
+    /// The colorscheme that was active before the current one.
+    pub previous: Option<String>,
 }
 
-impl From<Config> for Status {
-    fn from(config: Config) -> Self {
+impl From<State> for Status {
+    fn from(config: State) -> Self {
         let duration = SystemTime::now().duration_since(config.changed).unwrap_or_default();
-        Self::new(config.current, duration)
+        let failed = config.failed.iter().filter_map(|t| t.parse().ok()).collect();
+        let mut status = Self::new(config.current, config.changed, duration, failed);
+        status.previous = config.last;
+        status
     }
 }
 
 impl Status {
-    pub const fn new(current: String, duration: Duration) -> Self {
-        Status { current, duration }
+    pub const fn new(
+        current: String,
+        changed: SystemTime,
+        duration: Duration,
+        failed: Vec<Target>,
+    ) -> Self {
+        Status { current, changed, duration, failed, previous: None }
     }
 }
 
@@ -214,16 +1463,19 @@ pub fn apply(
 ) -> anyhow::Result<()> {
     let colors = list(colors_file)?;
     if colors.contains(&colorscheme) {
-        write_config(config_file, &Config::now(colorscheme))?;
+        write_config(&config_file, &next_state(config_file.as_ref(), colorscheme))?;
         Ok(())
     } else {
         bail!("No matching colorscheme")
     }
 }
 
+/// Switches to the next colorscheme, or the previous one if `reverse` is set. Disabled
+/// colorschemes and ones whose scheme file can't be parsed are skipped.
 pub fn toggle(
     colors_file: impl AsRef<Path>,
     config_file: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
     reverse: bool,
 ) -> anyhow::Result<String> {
     let mut available_colors = list(colors_file)?;
@@ -231,19 +1483,23 @@ pub fn toggle(
         bail!("No colorschemes available");
     }
 
-    let mut index = 0;
-    if let Ok(c) = parse_config(config_file.as_ref()) {
-        if let Some(i) = available_colors.iter().position(|f| f == &c.current) {
-            index = if reverse {
-                (available_colors.len() + i - 1) % available_colors.len()
-            } else {
-                (i + 1) % available_colors.len()
-            };
-        }
-    }
+    let len = available_colors.len();
+    let step = |i: usize| if reverse { (len + i - 1) % len } else { (i + 1) % len };
+    let current = parse_config(config_file.as_ref())
+        .ok()
+        .and_then(|c| available_colors.iter().position(|f| f == &c.current));
+    // without a current colorscheme the cycle starts at the first one
+    let start = current.map_or(0, step);
+    let index = std::iter::successors(Some(start), |i| Some(step(*i)))
+        .take(len)
+        .find(|i| is_usable(&scheme_dir, &available_colors[*i]));
+    let index = match index {
+        Some(i) => i,
+        None => bail!("No usable colorschemes available"),
+    };
 
     let new_scheme = available_colors.remove(index);
-    let new_config = Config::now(new_scheme);
+    let new_config = next_state(config_file.as_ref(), new_scheme);
 
     write_config(config_file, &new_config)?;
 
@@ -267,15 +1523,161 @@ pub fn reload(
     }
 
     let new_scheme = available_colors.remove(index);
-    let new_config = Config::now(new_scheme);
+    let new_config = next_state(config_file.as_ref(), new_scheme);
 
     write_config(config_file, &new_config)?;
 
     Ok(new_config.current)
 }
 
+/// Switches to the presentation colorscheme, remembering the current one so it can be restored by
+/// [`present_off`].
+pub fn present_on(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    colorscheme: String,
+) -> anyhow::Result<()> {
+    let colors = list(colors_file)?;
+    if !colors.contains(&colorscheme) {
+        bail!("No matching colorscheme");
+    }
+
+    let previous = match parse_config(config_file.as_ref()) {
+        // keep the original colorscheme if presentation mode is already on
+        Ok(c) => c.previous.or(Some(c.current)),
+        Err(_) => None,
+    };
+    let mut new_config = next_state(config_file.as_ref(), colorscheme);
+    new_config.previous = previous;
+
+    write_config(config_file, &new_config)
+}
+
+/// Restores the colorscheme that was active before presentation mode was turned on.
+pub fn present_off(config_file: impl AsRef<Path>) -> anyhow::Result<String> {
+    let config = parse_config(config_file.as_ref())?;
+    match config.previous {
+        Some(previous) => {
+            let new_config = next_state(config_file.as_ref(), previous);
+            write_config(config_file, &new_config)?;
+            Ok(new_config.current)
+        }
+        None => bail!("Presentation mode is not on"),
+    }
+}
+
+/// The names of the color filters that are turned on.
+pub fn filters(config_file: impl AsRef<Path>) -> Vec<String> {
+    parse_config(config_file).map(|c| c.filters).unwrap_or_default()
+}
+
+/// Turns a color filter on or off and returns the current colorscheme, which has to be reloaded.
+pub fn set_filter(config_file: impl AsRef<Path>, name: &str, on: bool) -> anyhow::Result<String> {
+    let mut config = parse_config(config_file.as_ref())?;
+    config.filters.retain(|f| f != name);
+    if on {
+        config.filters.push(name.to_owned());
+    }
+    write_config(config_file, &config)?;
+    Ok(config.current)
+}
+
+/// The window opacity that is set, `None` if windows are opaque.
+pub fn opacity(config_file: impl AsRef<Path>) -> Option<f32> {
+    parse_config(config_file).ok().and_then(|c| c.opacity)
+}
+
+/// Sets or unsets the window opacity and returns the current colorscheme, which has to be
+/// reloaded.
+pub fn set_opacity(config_file: impl AsRef<Path>, opacity: Option<f32>) -> anyhow::Result<String> {
+    if opacity.is_some_and(|o| !(0.0..=1.0).contains(&o)) {
+        bail!("The opacity has to be between 0 and 1");
+    }
+
+    let mut config = parse_config(config_file.as_ref())?;
+    config.opacity = opacity;
+    write_config(config_file, &config)?;
+    Ok(config.current)
+}
+
+/// The available colorschemes, in the order `toggle` cycles through them.
 pub fn list(colors_file: impl AsRef<Path>) -> anyhow::Result<Vec<String>> {
-    parse_colors(colors_file).map(|c| c.colors)
+    let mut colors = parse_colors(colors_file)?;
+    colors.sort.sort(&mut colors.colors);
+    Ok(colors.colors)
+}
+
+/// The available colorschemes like [`list`], without the disabled ones.
+pub fn list_enabled(
+    colors_file: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+) -> anyhow::Result<Vec<String>> {
+    let mut colors = list(colors_file)?;
+    colors.retain(|c| !is_disabled(&scheme_dir, c));
+    Ok(colors)
+}
+
+/// Whether a colorscheme is disabled, either by `disabled: true` in the `meta` section of its
+/// scheme file, or by its scheme file being moved into the `disabled` subdirectory of the scheme
+/// directory.
+pub fn is_disabled(scheme_dir: impl AsRef<Path>, colorscheme: &str) -> bool {
+    let scheme_dir = scheme_dir.as_ref();
+    if scheme_file(scheme_dir.join("disabled"), colorscheme).exists() {
+        return true;
+    }
+    let path = scheme_file(scheme_dir, colorscheme);
+    path.exists() && SchemeFile::load(path).is_ok_and(|s| s.meta.disabled)
+}
+
+/// Whether a colorscheme is `dark` or `light`, read from the variant or the tags of its scheme
+/// file.
+pub fn variant(scheme_dir: impl AsRef<Path>, colorscheme: &str) -> Option<&'static str> {
+    let meta = SchemeFile::load(scheme_file(scheme_dir, colorscheme)).ok()?.meta;
+    let is = |v: &str| meta.variant.as_deref() == Some(v) || meta.tags.iter().any(|t| t == v);
+    ["dark", "light"].into_iter().find(|v| is(v))
+}
+
+/// Whether `toggle` can switch to a colorscheme: it isn't disabled and its scheme file, if it has
+/// one, can be parsed.
+fn is_usable(scheme_dir: impl AsRef<Path>, colorscheme: &str) -> bool {
+    let path = scheme_file(&scheme_dir, colorscheme);
+    !is_disabled(&scheme_dir, colorscheme) && (!path.exists() || SchemeFile::load(path).is_ok())
+}
+
+/// Lists the colorschemes like [`list`], together with their scheme file and when they were last
+/// applied. Scheme files that can't be read are listed without their metadata.
+pub fn list_entries(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+) -> anyhow::Result<Vec<ListEntry>> {
+    let applied = parse_config(config_file).map(|c| c.applied()).unwrap_or_default();
+    let entries = list(colors_file)?
+        .into_iter()
+        .map(|name| {
+            let path = Some(scheme_file(&scheme_dir, &name)).filter(|p| p.exists());
+            let meta = path.as_ref().and_then(|p| SchemeFile::load(p).ok()).map(|s| s.meta);
+            let meta = meta.unwrap_or_default();
+            ListEntry {
+                disabled: is_disabled(&scheme_dir, &name),
+                applied: applied.get(&name).copied(),
+                name,
+                path,
+                variant: meta.variant,
+                tags: meta.tags,
+            }
+        })
+        .collect();
+    Ok(entries)
+}
+
+pub fn add(colors_file: impl AsRef<Path>, colorscheme: String) -> anyhow::Result<()> {
+    let mut colors = parse_colors(colors_file.as_ref())?;
+    if !colors.colors.contains(&colorscheme) {
+        colors.colors.push(colorscheme);
+        write_colors(colors_file, &colors)?;
+    }
+    Ok(())
 }
 
 pub fn status(config_file: impl AsRef<Path>) -> anyhow::Result<Status> {
@@ -283,12 +1685,25 @@ pub fn status(config_file: impl AsRef<Path>) -> anyhow::Result<Status> {
     Ok(Status::from(config))
 }
 
-fn write_config(config_file: impl AsRef<Path>, config: &Config) -> anyhow::Result<()> {
+/// Records which targets failed to reload, so that `status` can report them as out of sync.
+pub fn set_failed(config_file: impl AsRef<Path>, failed: &[Target]) -> anyhow::Result<()> {
+    let mut config = parse_config(config_file.as_ref())?;
+    config.failed = failed.iter().map(|t| t.name().to_owned()).collect();
+    write_config(config_file, &config)
+}
+
+fn write_config(config_file: impl AsRef<Path>, config: &State) -> anyhow::Result<()> {
     let config_str = serde_yaml::to_string(config)?;
     fs::write(config_file, config_str)?;
     Ok(())
 }
 
+fn write_colors(colors_file: impl AsRef<Path>, colors: &Colors) -> anyhow::Result<()> {
+    let colors_str = serde_yaml::to_string(colors)?;
+    fs::write(colors_file, colors_str)?;
+    Ok(())
+}
+
 fn parse_colors(colors_file: impl AsRef<Path>) -> anyhow::Result<Colors> {
     let colors_str = fs::read_to_string(colors_file.as_ref())
         .map_err(|_| anyhow!("Error reading colorscheme list file"))?;
@@ -297,22 +1712,145 @@ fn parse_colors(colors_file: impl AsRef<Path>) -> anyhow::Result<Colors> {
     Ok(colors)
 }
 
-fn parse_config(config_file: impl AsRef<Path>) -> anyhow::Result<Config> {
+/// Compares case-insensitively and runs of digits by their numeric value. Names that only differ in
+/// case or leading zeros are ordered by their bytes, so the order is always the same.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut i, mut j) = (a.char_indices().peekable(), b.char_indices().peekable());
+    loop {
+        let (x, y) = match (i.peek(), j.peek()) {
+            (Some(&(_, x)), Some(&(_, y))) => (x, y),
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+        };
+
+        let ordering = if x.is_ascii_digit() && y.is_ascii_digit() {
+            let number = |it: &mut std::iter::Peekable<std::str::CharIndices>| {
+                let mut digits = String::new();
+                while let Some((_, c)) = it.next_if(|(_, c)| c.is_ascii_digit()) {
+                    digits.push(c);
+                }
+                digits.trim_start_matches('0').to_owned()
+            };
+            let (x, y) = (number(&mut i), number(&mut j));
+            x.len().cmp(&y.len()).then_with(|| x.cmp(&y))
+        } else {
+            i.next();
+            j.next();
+            x.to_lowercase().cmp(y.to_lowercase())
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// A new state for `current`, which keeps the filters and opacity of the previous state and
+/// remembers its colorscheme, unless it is the same one.
+fn next_state(config_file: &Path, current: String) -> State {
+    let mut state = State::now(current);
+    if let Ok(c) = parse_config(config_file) {
+        state.applied = c.applied();
+        state.filters = c.filters;
+        state.opacity = c.opacity;
+        state.last = if c.current != state.current { Some(c.current) } else { c.last };
+    }
+    state.applied.insert(state.current.clone(), state.changed);
+    state
+}
+
+fn parse_config(config_file: impl AsRef<Path>) -> anyhow::Result<State> {
     let config_str = fs::read_to_string(config_file.as_ref())?;
-    Ok(serde_yaml::from_str::<Config>(&config_str)?)
+    Ok(serde_yaml::from_str::<State>(&config_str)?)
+}
+
+/// The value a selector maps a colorscheme to, with its placeholders expanded. `{scheme}` is
+/// replaced with the name of the colorscheme and `{variant}` with its variant, values containing
+/// `{variant}` don't map colorschemes that are neither `dark` nor `light`.
+fn selector(selector: &Yaml, scheme_dir: impl AsRef<Path>, key: &str) -> Option<String> {
+    let (value, _) = selector_entry(selector, key)?;
+    expand_placeholders(value, scheme_dir, key)
 }
 
-fn selector<'a>(selector: &'a Yaml, key: &'_ str) -> Option<&'a str> {
+/// The value of the entry of `key` in a selector, or of the `else` entry if there is none, and
+/// whether it's the `else` entry.
+fn selector_entry<'a>(selector: &'a Yaml, key: &'_ str) -> Option<(&'a str, bool)> {
     let map = selector.as_hash()?;
     let mut default = None;
 
     for (k, v) in map.iter() {
         if k.as_str()? == key {
-            return v.as_str();
+            return v.as_str().map(|v| (v, false));
         } else if k.as_str()? == "else" {
-            default = v.as_str();
+            default = v.as_str().map(|v| (v, true));
         }
     }
 
     default
 }
+
+fn expand_placeholders(
+    value: &str,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: &str,
+) -> Option<String> {
+    let value = value.replace("{scheme}", colorscheme);
+    if !value.contains("{variant}") {
+        return Some(value);
+    }
+    Some(value.replace("{variant}", variant(scheme_dir, colorscheme)?))
+}
+
+fn scheme_file(scheme_dir: impl AsRef<Path>, colorscheme: &str) -> PathBuf {
+    scheme_dir.as_ref().join(format!("{}.yml", colorscheme))
+}
+
+/// Returns the `targets.<target>` section of a scheme file, if the scheme file exists and contains
+/// one.
+#[cfg(any(feature = "alacritty", feature = "kitty", feature = "tmux", feature = "starship"))]
+fn target_section(
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: &str,
+    target: &str,
+) -> anyhow::Result<Option<Yaml>> {
+    let path = scheme_file(scheme_dir, colorscheme);
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let scheme_str =
+        fs::read_to_string(&path).map_err(|_| anyhow!("Error reading colorscheme file"))?;
+    let scheme = yaml_rust::YamlLoader::load_from_str(&scheme_str)?.into_iter().next();
+
+    Ok(scheme.map(|s| s["targets"][target].clone()).filter(|t| !t.is_badvalue()))
+}
+
+/// Returns the flat key value overrides in the `targets.<target>` section of a scheme file.
+#[cfg(any(feature = "kitty", feature = "tmux", feature = "starship"))]
+fn target_overrides(
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: &str,
+    target: &str,
+) -> anyhow::Result<Vec<(String, String)>> {
+    let section = match target_section(scheme_dir, colorscheme, target)? {
+        Some(Yaml::Hash(h)) => h,
+        Some(_) => bail!("Error parsing {} overrides in colorscheme file", target),
+        None => return Ok(Vec::new()),
+    };
+
+    let overrides = section
+        .into_iter()
+        .filter_map(|(k, v)| {
+            let value = match v {
+                Yaml::String(s) => s,
+                Yaml::Integer(i) => i.to_string(),
+                Yaml::Real(r) => r,
+                Yaml::Boolean(b) => b.to_string(),
+                _ => return None,
+            };
+            Some((k.into_string()?, value))
+        })
+        .collect();
+
+    Ok(overrides)
+}
@@ -1,15 +1,36 @@
-use clap::{crate_authors, crate_version, value_parser, Arg, ColorChoice, Command, ValueHint};
-use clap_complete::generate;
-use clap_complete::shells::{Bash, Elvish, Fish, PowerShell, Zsh};
+use clap::parser::ValueSource;
+use clap::{
+    crate_authors, crate_version, value_parser, Arg, ArgMatches, ColorChoice, Command, ValueHint,
+};
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Powershell, Zsh};
+use clap_complete::{ArgValueCandidates, CompleteEnv, CompletionCandidate};
 use shellexpand::tilde;
+use tokio::runtime::Runtime;
 
-use std::future::Future;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process::exit;
 use std::str::FromStr;
-use std::time::Duration;
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 const BIN_NAME: &str = "alco";
+/// How long the daemon waits for another apply request before applying a colorscheme, so that
+/// requests arriving in quick succession, like from key repeat, only apply the last one.
+const COALESCE_DELAY: Duration = Duration::from_millis(50);
+/// The subcommands that reload targets, which fail if a selector or input file of one is missing.
+const RELOADING_SUBCOMMANDS: &[&str] = &[
+    "apply",
+    "toggle",
+    "reload",
+    "present",
+    "filter",
+    "set",
+    "daemon",
+    "follow-system",
+    "schedule",
+];
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 enum Shell {
@@ -35,69 +56,8 @@ impl FromStr for Shell {
     }
 }
 
-struct Options {
-    alacritty: AlacrittyOptions,
-    kitty: KittyOptions,
-    tmux: TmuxOptions,
-    neovim: NeovimOptions,
-    starship: StarshipOptions,
-    bat: BatOptions,
-    delta: DeltaOptions,
-    cmus: CmusOptions,
-}
-
-struct AlacrittyOptions {
-    reload: bool,
-    file: String,
-    in_file: String,
-    selector: String,
-}
-
-struct KittyOptions {
-    reload: bool,
-    file: String,
-    socket: String,
-    selector: String,
-}
-
-struct TmuxOptions {
-    reload: bool,
-    file: String,
-    selector: String,
-}
-
-struct NeovimOptions {
-    reload: bool,
-    command: String,
-}
-
-struct StarshipOptions {
-    reload: bool,
-    file: String,
-    in_file: String,
-    selector: String,
-}
-
-struct BatOptions {
-    reload: bool,
-    file: String,
-    in_file: String,
-    selector: String,
-}
-
-struct DeltaOptions {
-    reload: bool,
-    file: String,
-    selector: String,
-}
-
-struct CmusOptions {
-    reload: bool,
-    selector: String,
-}
-
-fn main() {
-    let mut app = Command::new(BIN_NAME)
+fn app() -> Command {
+    Command::new(BIN_NAME)
         .color(ColorChoice::Auto)
         .bin_name(BIN_NAME)
         .version(crate_version!())
@@ -112,6 +72,31 @@ fn main() {
                 .value_hint(ValueHint::FilePath)
                 .help("Alco's configuration file"),
         )
+        .arg(
+            Arg::new("settings file")
+                .long("settings-file")
+                .short('S')
+                .default_value(alco::DEFAULT_SETTINGS_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("Alco's settings file"),
+        )
+        .arg(
+            Arg::new("script file")
+                .long("script-file")
+                .default_value(alco::DEFAULT_SCRIPT_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The lua init script, which is run on apply if it exists"),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .short('p')
+                .value_name("name")
+                .add(profile_candidates())
+                .help("A profile from the settings file which selects targets and options"),
+        )
         .arg(
             Arg::new("colorscheme file")
                 .long("colorscheme-file")
@@ -121,6 +106,36 @@ fn main() {
                 .value_hint(ValueHint::FilePath)
                 .help("The file that contains a list of colorschemes"),
         )
+        .arg(
+            Arg::new("scheme directory")
+                .long("scheme-dir")
+                .default_value(alco::DEFAULT_SCHEME_DIR)
+                .value_name("dir")
+                .value_hint(ValueHint::DirPath)
+                .help("The directory that contains alco's colorscheme files"),
+        )
+        .arg(
+            Arg::new("cache directory")
+                .long("cache-dir")
+                .default_value(alco::DEFAULT_CACHE_DIR)
+                .value_name("dir")
+                .value_hint(ValueHint::DirPath)
+                .help("The directory prerendered configuration files are stored in"),
+        )
+        .arg(
+            Arg::new("strategy")
+                .long("strategy")
+                .default_value("copy")
+                .value_name("strategy")
+                .value_parser(["copy", "symlink"])
+                .help("Copy configuration files or symlink them into the cache directory"),
+        )
+        .arg(
+            Arg::new("safe")
+                .long("safe")
+                .num_args(0)
+                .help("Check that rewritten configuration files are valid before reloading"),
+        )
         .arg(
             Arg::new("reload all")
                 .long("reload-all")
@@ -128,6 +143,15 @@ fn main() {
                 .num_args(0)
                 .help("Reload all additional colorschemes"),
         )
+        .arg(
+            Arg::new("only")
+                .long("only")
+                .value_name("targets")
+                .value_parser(alco::Target::ALL.map(|t| t.name()))
+                .value_delimiter(',')
+                .conflicts_with("reload all")
+                .help("Only reload these targets, regardless of flags, profiles and settings"),
+        )
         .arg(
             Arg::new("reload alacritty")
                 .long("reload-alacritty")
@@ -160,6 +184,13 @@ fn main() {
                 .value_hint(ValueHint::FilePath)
                 .help("The alacritty selector file which contains a colorscheme mapping"),
         )
+        .arg(
+            Arg::new("only colors")
+                .long("only-colors")
+                .value_name("keys")
+                .value_delimiter(',')
+                .help("Only rewrite these alacritty color keys, like colors.primary.*"),
+        )
         .arg(
             Arg::new("reload kitty")
                 .long("reload-kitty")
@@ -264,6 +295,12 @@ fn main() {
                 .value_hint(ValueHint::FilePath)
                 .help("The starship selector file which contains a colorscheme mapping"),
         )
+        .arg(
+            Arg::new("starship module")
+                .long("starship-module")
+                .num_args(0)
+                .help("Add a custom starship module showing the colorscheme, `${custom.alco}`"),
+        )
         .arg(
             Arg::new("reload bat")
                 .long("reload-bat")
@@ -337,274 +374,2990 @@ fn main() {
                 .help("The cmus selector file which contains a colorscheme mapping"),
         )
         .arg(
-            Arg::new("generate completion")
-                .long("generate-completion")
-                .short('g')
-                .value_name("shell")
-                .value_parser(value_parser!(Shell))
-                .help("Generates a completion script for the specified shell"),
+            Arg::new("reload wezterm")
+                .long("reload-wezterm")
+                .short('w')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload wezterm by updating the colors file"),
         )
-        .subcommands(vec![
-            Command::new("apply")
-                .bin_name("alco-apply")
-                .about("Apply a colorscheme")
-                .arg(Arg::new("colorscheme").index(1).value_name("colorscheme").required(true)),
-            Command::new("toggle")
-                .bin_name("alco-toggle")
-                .about("Toggle the colorscheme between available options")
-                .arg(
-                    Arg::new("reverse")
-                        .long("reverse")
-                        .short('r')
-                        .num_args(0)
-                        .help("Toggle in reverse order between available colorschemes"),
-                ),
-            Command::new("reload").bin_name("alco-reload").about("Reload the current colorscheme"),
-            Command::new("list").bin_name("alco-list").about("List available colorschemes"),
-            Command::new("status").bin_name("alco-status").about("Print the current status").arg(
-                Arg::new("time")
-                    .long("time")
-                    .short('t')
-                    .num_args(0)
-                    .help("Print the duration since the last change"),
-            ),
-        ]);
-
-    let app_m = app.clone().get_matches();
-
-    let generate_completion = app_m.get_one("generate completion");
-    if let Some(shell) = generate_completion {
-        let mut stdout = std::io::stdout();
-        match shell {
-            Shell::Bash => generate(Bash, &mut app, BIN_NAME, &mut stdout),
-            Shell::Elvish => generate(Elvish, &mut app, BIN_NAME, &mut stdout),
-            Shell::Fish => generate(Fish, &mut app, BIN_NAME, &mut stdout),
-            Shell::Zsh => generate(Zsh, &mut app, BIN_NAME, &mut stdout),
-            Shell::Pwrsh => generate(PowerShell, &mut app, BIN_NAME, &mut stdout),
-        }
-
-        exit(0);
-    }
-
-    let colors_file = tilde(app_m.get_one::<String>("colorscheme file").unwrap()).into_owned();
-    let config_file = tilde(app_m.get_one::<String>("configuration file").unwrap()).into_owned();
-    let reload_all = app_m.get_flag("reload all");
-
-    let alacritty = AlacrittyOptions {
-        reload: app_m.get_flag("reload alacritty") | reload_all,
-        file: tilde(app_m.get_one::<String>("alacritty file").unwrap()).into_owned(),
-        in_file: tilde(app_m.get_one::<String>("alacritty in file").unwrap()).into_owned(),
-        selector: tilde(app_m.get_one::<String>("alacritty selector").unwrap()).into_owned(),
-    };
-    let kitty = KittyOptions {
-        reload: app_m.get_flag("reload kitty") | reload_all,
-        file: tilde(app_m.get_one::<String>("kitty file").unwrap()).into_owned(),
-        socket: tilde(app_m.get_one::<String>("kitty socket").unwrap()).into_owned(),
-        selector: tilde(app_m.get_one::<String>("kitty selector").unwrap()).into_owned(),
-    };
-    let tmux = TmuxOptions {
-        reload: app_m.get_flag("reload tmux") | reload_all,
-        file: tilde(app_m.get_one::<String>("tmux file").unwrap()).into_owned(),
-        selector: tilde(app_m.get_one::<String>("tmux selector").unwrap()).into_owned(),
-    };
-    let neovim = NeovimOptions {
-        reload: app_m.get_flag("reload neovim") | reload_all,
-        command: app_m.get_one::<String>("neovim command").unwrap().to_owned(),
-    };
-    let starship = StarshipOptions {
-        reload: app_m.get_flag("reload starship") | reload_all,
-        file: tilde(app_m.get_one::<String>("starship file").unwrap()).into_owned(),
-        in_file: tilde(app_m.get_one::<String>("starship in file").unwrap()).into_owned(),
-        selector: tilde(app_m.get_one::<String>("starship selector").unwrap()).into_owned(),
-    };
-    let bat = BatOptions {
-        reload: app_m.get_flag("reload bat") | reload_all,
-        file: tilde(app_m.get_one::<String>("bat file").unwrap()).into_owned(),
-        in_file: tilde(app_m.get_one::<String>("bat in file").unwrap()).into_owned(),
-        selector: tilde(app_m.get_one::<String>("bat selector").unwrap()).into_owned(),
-    };
-    let delta = DeltaOptions {
-        reload: app_m.get_flag("reload delta") | reload_all,
-        file: tilde(app_m.get_one::<String>("delta file").unwrap()).into_owned(),
-        selector: tilde(app_m.get_one::<String>("delta selector").unwrap()).into_owned(),
-    };
-    let cmus = CmusOptions {
-        reload: app_m.get_flag("reload cmus") | reload_all,
-        selector: tilde(app_m.get_one::<String>("cmus selector").unwrap()).into_owned(),
-    };
-
-    let opts = Options {
-        alacritty,
-        kitty,
-        tmux,
-        neovim,
-        starship,
-        bat,
-        delta,
-        cmus,
-    };
-
-    match app_m.subcommand() {
-        Some(("apply", sub_m)) => {
-            let colorscheme = sub_m.get_one::<String>("colorscheme").unwrap();
-            apply(colors_file, config_file, colorscheme, opts);
-        }
-        Some(("toggle", sub_m)) => {
-            let reverse = sub_m.get_flag("reverse");
-            toggle(colors_file, config_file, reverse, opts);
-        }
-        Some(("reload", _)) => {
-            reload(colors_file, config_file, opts);
-        }
-        Some(("list", _)) => list(colors_file),
-        Some(("status", sub_m)) => {
-            let time = sub_m.get_flag("time");
-            status(config_file, time);
-        }
-        _ => {
-            app.print_help().ok();
-        }
-    }
-}
-
-fn apply(
-    colors_file: impl AsRef<Path>,
-    config_file: impl AsRef<Path>,
-    colorscheme: &str,
-    opts: Options,
-) {
-    match alco::apply(colors_file, config_file, colorscheme.to_owned()) {
-        Ok(_) => apply_colorscheme(colorscheme, opts),
-        Err(e) => {
-            println!("Error applying colorscheme {}:\n{:?}", colorscheme, e);
-        }
-    }
-}
-
-fn toggle(
-    colors_file: impl AsRef<Path>,
-    config_file: impl AsRef<Path>,
-    reverse: bool,
-    opts: Options,
-) {
-    match alco::toggle(&colors_file, &config_file, reverse) {
-        Ok(colorscheme) => apply_colorscheme(&colorscheme, opts),
-        Err(e) => println!("Error toggling colorscheme:\n{}", e),
-    }
-}
-
-fn reload(colors_file: impl AsRef<Path>, config_file: impl AsRef<Path>, opts: Options) {
-    match alco::reload(&colors_file, &config_file) {
-        Ok(colorscheme) => apply_colorscheme(&colorscheme, opts),
-        Err(e) => println!("Error reloading colorscheme:\n{}", e),
-    }
-}
-
-fn apply_colorscheme(colorscheme: &str, opts: Options) {
-    let runtime = tokio::runtime::Builder::new_multi_thread()
-        .worker_threads(8)
-        .enable_io()
-        .build()
-        .expect("tokio runtime failed to start");
-
-    runtime.block_on(async move {
-        #[rustfmt::skip]
-        tokio::join!(
-            spawn_if(opts.alacritty.reload, reload_alacritty(opts.alacritty, colorscheme.to_owned())),
-            spawn_if(opts.kitty.reload, reload_kitty(opts.kitty, colorscheme.to_owned())),
-            spawn_if(opts.tmux.reload, reload_tmux(opts.tmux, colorscheme.to_owned())),
-            spawn_if(opts.neovim.reload, reload_neovim(opts.neovim.command)),
-            spawn_if(opts.starship.reload, reload_starship(opts.starship, colorscheme.to_owned())),
-            spawn_if(opts.bat.reload, reload_bat(opts.bat, colorscheme.to_owned())),
-            spawn_if(opts.delta.reload, reload_delta(opts.delta, colorscheme.to_owned())),
-            spawn_if(opts.cmus.reload, reload_cmus(opts.cmus, colorscheme.to_owned())),
-        );
-    });
-}
-
-async fn spawn_if<F>(condition: bool, f: F)
-where
-    F: Future<Output = ()> + Send + 'static,
-{
-    if condition {
-        if let Err(e) = tokio::spawn(f).await {
-            println!("Error: {e}");
-        }
-    }
-}
-
-fn list(dir: impl AsRef<Path>) {
-    match alco::list(dir.as_ref()) {
-        Ok(files) => {
-            for f in files {
-                println!("{}", f);
-            }
-        }
-        Err(_) => {
-            println!("Error listing files in dir: {}", dir.as_ref().display());
-            std::process::exit(1);
-        }
-    }
-}
-
-fn status(scheme_dir: impl AsRef<Path>, time: bool) {
-    match alco::status(scheme_dir) {
-        Ok(s) => {
-            if time {
-                let seconds = Duration::from_secs(s.duration.as_secs());
-                println!("{} changed {} ago", s.current, humantime::format_duration(seconds),);
-            } else {
-                println!("{}", s.current);
-            }
-        }
-        Err(e) => println!("Error getting current colorscheme:\n{}", e),
-    }
-}
-
-async fn reload_alacritty(opts: AlacrittyOptions, colorscheme: impl AsRef<str>) {
-    if let Err(e) = alco::reload_alacritty(opts.file, opts.in_file, opts.selector, colorscheme) {
-        println!("Error reloading alacritty colorscheme:\n{}", e);
-    }
-}
-
-async fn reload_kitty(opts: KittyOptions, colorscheme: impl AsRef<str>) {
-    if let Err(e) = alco::reload_kitty(opts.file, opts.socket, opts.selector, colorscheme) {
-        println!("Error reloading kitty colorscheme:\n{}", e);
-    }
-}
-
-async fn reload_tmux(opts: TmuxOptions, colorscheme: impl AsRef<str>) {
-    if let Err(e) = alco::reload_tmux(opts.file, opts.selector, colorscheme) {
-        println!("Error reloading tmux colorscheme:\n{}", e);
-    }
-}
-
-async fn reload_neovim(command: impl AsRef<str>) {
-    if let Err(e) = alco::reload_neovim(command).await {
-        println!("Error reloading neovim colorscheme:\n{}", e);
-    }
-}
-
-async fn reload_starship(opts: StarshipOptions, colorscheme: impl AsRef<str>) {
-    if let Err(e) = alco::reload_starship(opts.file, opts.in_file, opts.selector, colorscheme) {
-        println!("Error reloading starship colorscheme:\n{}", e);
-    }
-}
-
-async fn reload_bat(opts: BatOptions, colorscheme: impl AsRef<str>) {
-    if let Err(e) = alco::reload_bat(opts.file, opts.in_file, opts.selector, colorscheme) {
-        println!("Error reloading bat colorscheme:\n{}", e);
-    }
-}
-
-async fn reload_delta(opts: DeltaOptions, colorscheme: impl AsRef<str>) {
-    if let Err(e) = alco::reload_delta(opts.file, opts.selector, colorscheme) {
-        println!("Error reloading delta colorscheme:\n{}", e);
-    }
-}
-
-async fn reload_cmus(opts: CmusOptions, colorscheme: impl AsRef<str>) {
-    if let Err(e) = alco::reload_cmus(opts.selector, colorscheme) {
-        println!("Error reloading cmus colorscheme:\n{}", e);
-    }
+        .arg(
+            Arg::new("wezterm file")
+                .long("wezterm-file")
+                .default_value(alco::DEFAULT_WEZTERM_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The wezterm colors file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("wezterm selector")
+                .long("wezterm-selector")
+                .default_value(alco::DEFAULT_WEZTERM_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The wezterm selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("wezterm config")
+                .long("wezterm-config")
+                .default_value(alco::DEFAULT_WEZTERM_CONFIG)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The wezterm configuration file which will be touched to reload wezterm"),
+        )
+        .arg(
+            Arg::new("reload foot")
+                .long("reload-foot")
+                .short('f')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload foot by updating the colors file and recoloring its terminals"),
+        )
+        .arg(
+            Arg::new("foot file")
+                .long("foot-file")
+                .default_value(alco::DEFAULT_FOOT_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The foot colors file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("foot selector")
+                .long("foot-selector")
+                .default_value(alco::DEFAULT_FOOT_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The foot selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("reload zellij")
+                .long("reload-zellij")
+                .short('z')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload zellij by updating the theme file and setting the theme"),
+        )
+        .arg(
+            Arg::new("zellij file")
+                .long("zellij-file")
+                .default_value(alco::DEFAULT_ZELLIJ_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The zellij theme file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("zellij selector")
+                .long("zellij-selector")
+                .default_value(alco::DEFAULT_ZELLIJ_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The zellij selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("zellij config")
+                .long("zellij-config")
+                .default_value(alco::DEFAULT_ZELLIJ_CONFIG)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The zellij configuration file in which the theme will be set"),
+        )
+        .arg(
+            Arg::new("reload fish")
+                .long("reload-fish")
+                .short('F')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload fish by updating the theme file and setting its color variables"),
+        )
+        .arg(
+            Arg::new("fish file")
+                .long("fish-file")
+                .default_value(alco::DEFAULT_FISH_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The fish theme file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("fish selector")
+                .long("fish-selector")
+                .default_value(alco::DEFAULT_FISH_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The fish selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("reload fzf")
+                .long("reload-fzf")
+                .short('Z')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload fzf by updating the shell snippet with its colors"),
+        )
+        .arg(
+            Arg::new("fzf file")
+                .long("fzf-file")
+                .default_value(alco::DEFAULT_FZF_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The shell snippet setting the fzf colors which will be overwritten"),
+        )
+        .arg(
+            Arg::new("reload rofi")
+                .long("reload-rofi")
+                .short('R')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload rofi by updating the colors file"),
+        )
+        .arg(
+            Arg::new("rofi file")
+                .long("rofi-file")
+                .default_value(alco::DEFAULT_ROFI_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The rofi colors file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("rofi selector")
+                .long("rofi-selector")
+                .default_value(alco::DEFAULT_ROFI_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The rofi selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("reload dunst")
+                .long("reload-dunst")
+                .short('D')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload dunst by updating the urgency colors of its drop-in config"),
+        )
+        .arg(
+            Arg::new("dunst file")
+                .long("dunst-file")
+                .default_value(alco::DEFAULT_DUNST_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The dunst drop-in config which will be overwritten"),
+        )
+        .arg(
+            Arg::new("reload waybar")
+                .long("reload-waybar")
+                .short('W')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload waybar by updating the color definitions of its style"),
+        )
+        .arg(
+            Arg::new("waybar file")
+                .long("waybar-file")
+                .default_value(alco::DEFAULT_WAYBAR_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The stylesheet with the waybar color definitions which will be overwritten"),
+        )
+        .arg(
+            Arg::new("reload polybar")
+                .long("reload-polybar")
+                .short('P')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload polybar by updating the included colors file and restarting it"),
+        )
+        .arg(
+            Arg::new("polybar file")
+                .long("polybar-file")
+                .default_value(alco::DEFAULT_POLYBAR_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The polybar colors file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("polybar selector")
+                .long("polybar-selector")
+                .default_value(alco::DEFAULT_POLYBAR_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The polybar selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("reload picom")
+                .long("reload-picom")
+                .short('O')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload picom by updating the included opacity options"),
+        )
+        .arg(
+            Arg::new("picom file")
+                .long("picom-file")
+                .default_value(alco::DEFAULT_PICOM_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The picom config with the opacity options which will be overwritten"),
+        )
+        .arg(
+            Arg::new("reload i3")
+                .long("reload-i3")
+                .short('I')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload i3 or sway by updating the included window colors"),
+        )
+        .arg(
+            Arg::new("i3 file")
+                .long("i3-file")
+                .default_value(alco::DEFAULT_I3_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The i3 or sway colors file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("i3 selector")
+                .long("i3-selector")
+                .default_value(alco::DEFAULT_I3_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The i3 selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("reload hyprland")
+                .long("reload-hyprland")
+                .short('H')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload hyprland by updating the sourced colors and setting its borders"),
+        )
+        .arg(
+            Arg::new("hyprland file")
+                .long("hyprland-file")
+                .default_value(alco::DEFAULT_HYPRLAND_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The hyprland colors file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("reload gtk")
+                .long("reload-gtk")
+                .short('G')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also switch the dark or light preference and theme of GTK using gsettings"),
+        )
+        .arg(
+            Arg::new("gtk selector")
+                .long("gtk-selector")
+                .default_value(alco::DEFAULT_GTK_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The gtk selector file which maps colorschemes to GTK theme names"),
+        )
+        .arg(
+            Arg::new("reload qutebrowser")
+                .long("reload-qutebrowser")
+                .short('q')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload qutebrowser by updating the sourced colors file and sourcing it again"),
+        )
+        .arg(
+            Arg::new("qutebrowser file")
+                .long("qutebrowser-file")
+                .default_value(alco::DEFAULT_QUTEBROWSER_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The qutebrowser colors file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("qutebrowser selector")
+                .long("qutebrowser-selector")
+                .default_value(alco::DEFAULT_QUTEBROWSER_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The qutebrowser selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("reload vscode")
+                .long("reload-vscode")
+                .short('e')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload VS Code by setting the color theme in its settings file"),
+        )
+        .arg(
+            Arg::new("vscode file")
+                .long("vscode-file")
+                .default_value(alco::DEFAULT_VSCODE_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The VS Code settings file whose color theme will be set"),
+        )
+        .arg(
+            Arg::new("vscode selector")
+                .long("vscode-selector")
+                .default_value(alco::DEFAULT_VSCODE_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The VS Code selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("reload zathura")
+                .long("reload-zathura")
+                .short('u')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload zathura by updating the included colors and setting them over D-Bus"),
+        )
+        .arg(
+            Arg::new("zathura file")
+                .long("zathura-file")
+                .default_value(alco::DEFAULT_ZATHURA_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The zathura colors file which will be overwritten"),
+        )
+        .arg(
+            Arg::new("reload mako")
+                .long("reload-mako")
+                .short('M')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload mako by setting the colors in its configuration file"),
+        )
+        .arg(
+            Arg::new("mako file")
+                .long("mako-file")
+                .default_value(alco::DEFAULT_MAKO_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The mako configuration file whose colors will be set"),
+        )
+        .arg(
+            Arg::new("mako selector")
+                .long("mako-selector")
+                .default_value(alco::DEFAULT_MAKO_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The mako selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("reload ghostty")
+                .long("reload-ghostty")
+                .short('x')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload ghostty by setting the theme in its configuration file"),
+        )
+        .arg(
+            Arg::new("ghostty file")
+                .long("ghostty-file")
+                .default_value(alco::DEFAULT_GHOSTTY_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The ghostty configuration file whose theme will be set"),
+        )
+        .arg(
+            Arg::new("ghostty selector")
+                .long("ghostty-selector")
+                .default_value(alco::DEFAULT_GHOSTTY_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The ghostty selector file which contains a colorscheme mapping"),
+        )
+        .arg(
+            Arg::new("reload konsole")
+                .long("reload-konsole")
+                .short('K')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload Konsole by switching the profile of its sessions over D-Bus"),
+        )
+        .arg(
+            Arg::new("konsole selector")
+                .long("konsole-selector")
+                .default_value(alco::DEFAULT_KONSOLE_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The konsole selector file which maps colorschemes to Konsole profile names"),
+        )
+        .arg(
+            Arg::new("reload emacs")
+                .long("reload-emacs")
+                .short('E')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload emacs by loading the theme in the running server using emacsclient"),
+        )
+        .arg(
+            Arg::new("emacs selector")
+                .long("emacs-selector")
+                .default_value(alco::DEFAULT_EMACS_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The emacs selector file which maps colorschemes to Emacs theme names"),
+        )
+        .arg(
+            Arg::new("reload vim")
+                .long("reload-vim")
+                .short('v')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload vim by setting the colorscheme of its servers using --remote-send"),
+        )
+        .arg(
+            Arg::new("vim selector")
+                .long("vim-selector")
+                .default_value(alco::DEFAULT_VIM_SELECTOR)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The vim selector file which maps colorschemes to Vim colorscheme names"),
+        )
+        .arg(
+            Arg::new("reload xresources")
+                .long("reload-xresources")
+                .short('X')
+                .num_args(0)
+                .conflicts_with("reload all")
+                .help("Also reload xresources by merging the colors into the X resource database"),
+        )
+        .arg(
+            Arg::new("xresources file")
+                .long("xresources-file")
+                .default_value(alco::DEFAULT_XRESOURCES_FILE)
+                .value_name("file")
+                .value_hint(ValueHint::FilePath)
+                .help("The resource file which will be overwritten and merged using xrdb"),
+        )
+        .arg(
+            Arg::new("remote")
+                .long("remote")
+                .num_args(0)
+                .help("Send apply, toggle, list or status to a running daemon"),
+        )
+        .arg(
+            Arg::new("generate completion")
+                .long("generate-completion")
+                .short('g')
+                .value_name("shell")
+                .value_parser(value_parser!(Shell))
+                .help("Prints the script registering the completions of the specified shell"),
+        )
+        .subcommands(vec![
+            Command::new("apply")
+                .bin_name("alco-apply")
+                .about("Apply a colorscheme")
+                .arg(
+                    Arg::new("colorscheme")
+                        .index(1)
+                        .value_name("colorscheme")
+                        .required_unless_present_any(["stdin", "scheme file"])
+                        .conflicts_with_all(["stdin", "scheme file"])
+                        .add(colorscheme_candidates())
+                        .help("The colorscheme to apply, `-` applies the previous one"),
+                )
+                .arg(
+                    Arg::new("stdin")
+                        .long("stdin")
+                        .num_args(0)
+                        .conflicts_with_all(["scheme file", "here", "dry run"])
+                        .help("Apply the scheme file read from stdin without installing it"),
+                )
+                .arg(
+                    Arg::new("scheme file")
+                        .long("file")
+                        .value_name("file")
+                        .value_hint(ValueHint::FilePath)
+                        .conflicts_with_all(["here", "dry run"])
+                        .help("Apply a scheme file outside the scheme directory without installing it"),
+                )
+                .arg(
+                    Arg::new("here")
+                        .long("here")
+                        .num_args(0)
+                        .help("Only recolor the current terminal, without changing any files"),
+                )
+                .arg(
+                    Arg::new("dry run")
+                        .long("dry-run")
+                        .num_args(0)
+                        .conflicts_with("here")
+                        .help("Print the changes to the enabled targets without making them"),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .num_args(0)
+                        .requires("dry run")
+                        .help("Print the changes as json"),
+                )
+                .arg(
+                    Arg::new("accent")
+                        .long("accent")
+                        .value_name("color")
+                        .value_parser(value_parser!(alco::Color))
+                        .conflicts_with("here")
+                        .help("Replace the accent color of the colorscheme, in #rrggbb notation"),
+                ),
+            Command::new("toggle")
+                .bin_name("alco-toggle")
+                .about("Toggle the colorscheme between available options")
+                .arg(
+                    Arg::new("reverse")
+                        .long("reverse")
+                        .short('r')
+                        .num_args(0)
+                        .help("Toggle in reverse order between available colorschemes"),
+                ),
+            Command::new("reload")
+                .bin_name("alco-reload")
+                .about("Reload the current colorscheme")
+                .arg(
+                    Arg::new("failed")
+                        .long("failed")
+                        .num_args(0)
+                        .help("Only reload the targets that failed to reload last time"),
+                )
+                .arg(
+                    Arg::new("here")
+                        .long("here")
+                        .num_args(0)
+                        .conflicts_with("failed")
+                        .help("Only recolor the current terminal, without changing any files"),
+                ),
+            Command::new("present")
+                .bin_name("alco-present")
+                .about("Switch to the presentation colorscheme and back")
+                .arg(
+                    Arg::new("state")
+                        .index(1)
+                        .value_name("state")
+                        .value_parser(["on", "off"])
+                        .required(true),
+                ),
+            Command::new("filter")
+                .bin_name("alco-filter")
+                .about("Turn a color filter from the settings file on or off")
+                .arg(Arg::new("name").index(1).value_name("name").required(true))
+                .arg(
+                    Arg::new("state")
+                        .index(2)
+                        .value_name("state")
+                        .value_parser(["on", "off"])
+                        .required(true),
+                ),
+            Command::new("set")
+                .bin_name("alco-set")
+                .about("Change a setting that is kept when switching colorschemes")
+                .arg(
+                    Arg::new("setting")
+                        .index(1)
+                        .value_name("setting")
+                        .value_parser(["opacity"])
+                        .required(true),
+                )
+                .arg(
+                    Arg::new("value")
+                        .index(2)
+                        .value_name("value")
+                        .required(true)
+                        .help("The window opacity between 0 and 1, or off"),
+                ),
+            Command::new("daemon")
+                .bin_name("alco-daemon")
+                .about("Apply colorschemes in the background and listen for commands"),
+            Command::new("follow-system")
+                .bin_name("alco-follow-system")
+                .about("Follow the dark or light preference of the desktop"),
+            Command::new("schedule")
+                .bin_name("alco-schedule")
+                .about("Switch between the day and night colorscheme at fixed times or the sun"),
+            Command::new("snapshot")
+                .bin_name("alco-snapshot")
+                .about("Render a preview image of a colorscheme")
+                .arg(Arg::new("colorscheme").index(1).value_name("colorscheme").required(true))
+                .arg(
+                    Arg::new("out file")
+                        .long("out")
+                        .short('o')
+                        .value_name("file")
+                        .value_hint(ValueHint::FilePath)
+                        .required(true)
+                        .help("The png or svg file that will be written"),
+                ),
+            Command::new("compare")
+                .about("Show two colorschemes side by side")
+                .arg(
+                    Arg::new("a")
+                        .index(1)
+                        .value_name("colorscheme")
+                        .required(true)
+                        .add(colorscheme_candidates()),
+                )
+                .arg(
+                    Arg::new("b")
+                        .index(2)
+                        .value_name("colorscheme")
+                        .required(true)
+                        .add(colorscheme_candidates()),
+                ),
+            Command::new("gallery")
+                .bin_name("alco-gallery")
+                .about("Generate a html page with previews of all colorschemes")
+                .arg(
+                    Arg::new("out dir")
+                        .long("out")
+                        .short('o')
+                        .value_name("dir")
+                        .value_hint(ValueHint::DirPath)
+                        .required(true)
+                        .help("The directory the index.html file will be written to"),
+                ),
+            Command::new("render")
+                .bin_name("alco-render")
+                .about("Render the configuration files of a colorscheme without installing them")
+                .arg(Arg::new("colorscheme").index(1).value_name("colorscheme").required(true))
+                .arg(
+                    Arg::new("out dir")
+                        .long("out-dir")
+                        .short('o')
+                        .value_name("dir")
+                        .value_hint(ValueHint::DirPath)
+                        .required(true)
+                        .help("The directory the configuration files are rendered into"),
+                ),
+            Command::new("prerender").bin_name("alco-prerender").about(
+                "Prerender the configuration files of all colorschemes for faster switching",
+            ),
+            Command::new("ping")
+                .bin_name("alco-ping")
+                .about("Check the connection to the running instances of a target")
+                .arg(
+                    Arg::new("target")
+                        .index(1)
+                        .value_name("target")
+                        .value_parser(alco::Target::ALL.map(|t| t.name()))
+                        .required(true),
+                ),
+            Command::new("list").bin_name("alco-list").about("List available colorschemes").arg(
+                Arg::new("long")
+                    .long("long")
+                    .short('l')
+                    .num_args(0)
+                    .help("Print a table with the variant, tags, last use and scheme file"),
+            ),
+            Command::new("suggest")
+                .bin_name("alco-suggest")
+                .about("Rank colorschemes by how well they match an image")
+                .arg(
+                    Arg::new("image")
+                        .long("from-image")
+                        .value_name("file")
+                        .value_hint(ValueHint::FilePath)
+                        .required(true)
+                        .help("A png, jpeg or webp image, for example a wallpaper"),
+                )
+                .arg(
+                    Arg::new("count")
+                        .long("count")
+                        .short('n')
+                        .value_name("count")
+                        .value_parser(value_parser!(usize))
+                        .default_value("5")
+                        .help("The number of colorschemes to show"),
+                ),
+            Command::new("find")
+                .bin_name("alco-find")
+                .about("Find colorschemes containing a color")
+                .arg(
+                    Arg::new("color")
+                        .long("color")
+                        .value_name("color")
+                        .value_parser(value_parser!(alco::Color))
+                        .required(true)
+                        .help("The color in #rrggbb or 0xrrggbb notation"),
+                )
+                .arg(
+                    Arg::new("tolerance")
+                        .long("tolerance")
+                        .short('t')
+                        .value_name("distance")
+                        .value_parser(value_parser!(f32))
+                        .default_value("10")
+                        .help("The maximum perceptual distance, 100 is the distance between black and white"),
+                )
+                .arg(
+                    Arg::new("background")
+                        .long("background")
+                        .short('b')
+                        .num_args(0)
+                        .help("Only search background colors"),
+                ),
+            Command::new("migrate-schemes")
+                .bin_name("alco-migrate-schemes")
+                .about("Upgrade all scheme files in the scheme directory to the latest format"),
+            Command::new("import")
+                .bin_name("alco-import")
+                .about("Import colorschemes from other formats")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("alacritty-theme")
+                        .bin_name("alco-import-alacritty-theme")
+                        .about("Import a yaml or toml theme from the alacritty-theme repository")
+                        .arg(
+                            Arg::new("file")
+                                .index(1)
+                                .value_name("file")
+                                .value_hint(ValueHint::FilePath)
+                                .required(true)
+                                .help("The theme file or its http(s) url"),
+                        )
+                        .arg(
+                            Arg::new("name")
+                                .long("name")
+                                .short('n')
+                                .value_name("name")
+                                .help("The colorscheme name, defaults to the theme file name"),
+                        ),
+                ),
+            Command::new("convert")
+                .bin_name("alco-convert")
+                .about("Convert a palette read from stdin to another format and print it")
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .short('f')
+                        .value_name("format")
+                        .value_parser(alco::PaletteFormat::ALL.map(|f| f.name()))
+                        .required(true)
+                        .help("The format of the palette read from stdin"),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .short('t')
+                        .value_name("format")
+                        .value_parser(alco::PaletteFormat::ALL.map(|f| f.name()))
+                        .required(true)
+                        .help("The format of the palette written to stdout"),
+                ),
+            Command::new("update")
+                .bin_name("alco-update")
+                .about("Import an imported colorscheme again from its source")
+                .arg(
+                    Arg::new("colorscheme")
+                        .index(1)
+                        .value_name("colorscheme")
+                        .required(true)
+                        .add(colorscheme_candidates()),
+                ),
+            Command::new("fetch")
+                .bin_name("alco-fetch")
+                .about("Fetch imported colorschemes from their sources and show what changed")
+                .arg(
+                    Arg::new("colorschemes")
+                        .index(1)
+                        .value_name("colorscheme")
+                        .num_args(0..)
+                        .add(colorscheme_candidates()),
+                )
+                .arg(
+                    Arg::new("update all")
+                        .long("update-all")
+                        .num_args(0)
+                        .conflicts_with("colorschemes")
+                        .help("Fetch all imported colorschemes"),
+                )
+                .arg(
+                    Arg::new("yes")
+                        .long("yes")
+                        .short('y')
+                        .num_args(0)
+                        .help("Update changed colorschemes without asking"),
+                ),
+            Command::new("show")
+                .bin_name("alco-show")
+                .about("Show the colors of a colorscheme")
+                .arg(
+                    Arg::new("colorscheme")
+                        .index(1)
+                        .value_name("colorscheme")
+                        .required(true)
+                        .add(colorscheme_candidates()),
+                )
+                .arg(
+                    Arg::new("meta")
+                        .long("meta")
+                        .num_args(0)
+                        .help("Show the metadata of the scheme file instead, like its source"),
+                )
+                .arg(
+                    Arg::new("roles")
+                        .long("roles")
+                        .num_args(0)
+                        .conflicts_with("meta")
+                        .help("Show the colors of the color roles instead"),
+                ),
+            Command::new("resolve")
+                .about("Show what the selectors of the targets map a colorscheme to")
+                .arg(
+                    Arg::new("colorscheme")
+                        .index(1)
+                        .value_name("colorscheme")
+                        .required(true)
+                        .add(colorscheme_candidates()),
+                ),
+            Command::new("setup").about(
+                "Choose the targets to manage, import starter colorschemes and generate selectors",
+            ),
+            Command::new("pack")
+                .bin_name("alco-pack")
+                .about("Manage colorscheme packs")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("install")
+                        .bin_name("alco-pack-install")
+                        .about("Install a pack from a directory, a tarball or an url")
+                        .arg(
+                            Arg::new("source")
+                                .index(1)
+                                .value_name("path|url")
+                                .value_hint(ValueHint::AnyPath)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("pack dir")
+                                .long("pack-dir")
+                                .value_name("dir")
+                                .default_value(alco::DEFAULT_PACK_DIR)
+                                .value_hint(ValueHint::DirPath)
+                                .help("The directory files of installed packs are stored in"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("export")
+                        .bin_name("alco-pack-export")
+                        .about("Bundle colorschemes and their selector entries into a pack")
+                        .arg(
+                            Arg::new("colorschemes")
+                                .index(1)
+                                .value_name("colorscheme")
+                                .num_args(1..)
+                                .required(true)
+                                .add(colorscheme_candidates()),
+                        )
+                        .arg(
+                            Arg::new("out file")
+                                .long("out-file")
+                                .short('o')
+                                .value_name("file")
+                                .value_hint(ValueHint::FilePath)
+                                .required(true)
+                                .help("The tarball that will be written, e.g. my-pack.tar.gz"),
+                        ),
+                ),
+            Command::new("selector")
+                .about("Manage selector files")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("edit")
+                        .about("Map colorschemes to the installed themes of a target interactively")
+                        .arg(
+                            Arg::new("target")
+                                .index(1)
+                                .value_name("target")
+                                .value_parser(alco::Target::ALL.map(|t| t.name()))
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    Command::new("generate")
+                        .about("Propose the installed themes of a target matching each colorscheme")
+                        .arg(
+                            Arg::new("target")
+                                .index(1)
+                                .value_name("target")
+                                .value_parser(alco::Target::ALL.map(|t| t.name()))
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::new("write")
+                                .long("write")
+                                .short('w')
+                                .num_args(0)
+                                .help("Add the proposals for colorschemes without an entry to the selector file"),
+                        ),
+                ),
+            Command::new("hook")
+                .bin_name("alco-hook")
+                .about("Print a shell hook that applies the colorscheme of .alco files")
+                .arg(
+                    Arg::new("shell")
+                        .index(1)
+                        .value_name("shell")
+                        .value_parser(["zsh", "fish"])
+                        .required(true),
+                ),
+            Command::new("log")
+                .bin_name("alco-log")
+                .about("Inspect the changes previous applies made to the configuration files")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("list")
+                        .bin_name("alco-log-list")
+                        .about("List the applies recorded in the audit log, the most recent first"),
+                )
+                .subcommand(
+                    Command::new("show")
+                        .bin_name("alco-log-show")
+                        .about("Print the diffs of an apply")
+                        .arg(
+                            Arg::new("n")
+                                .index(1)
+                                .value_name("n")
+                                .value_parser(value_parser!(usize))
+                                .default_value("1")
+                                .help("The apply counting back from the most recent one, which is 1"),
+                        ),
+                ),
+            Command::new("status")
+                .bin_name("alco-status")
+                .about("Print the current status")
+                .arg(
+                    Arg::new("time")
+                        .long("time")
+                        .short('t')
+                        .num_args(0)
+                        .help("Print the duration since the last change"),
+                )
+                .arg(
+                    Arg::new("previous")
+                        .long("previous")
+                        .short('p')
+                        .num_args(0)
+                        .conflicts_with("time")
+                        .help("Print the colorscheme that was active before the current one"),
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_name("format")
+                        .value_parser(["relative", "rfc3339", "unix"])
+                        .default_value("relative")
+                        .requires("time")
+                        .help("Print the time of the last change relative, as RFC 3339 or unix time"),
+                )
+                .arg(
+                    Arg::new("precision")
+                        .long("precision")
+                        .value_name("precision")
+                        .value_parser(["seconds", "millis", "micros", "nanos"])
+                        .default_value("seconds")
+                        .requires("time")
+                        .help("The precision of the time of the last change"),
+                ),
+        ])
+}
+
+fn main() {
+    CompleteEnv::with_factory(app).complete();
+
+    let app_m = app().get_matches();
+
+    let generate_completion = app_m.get_one("generate completion");
+    if let Some(shell) = generate_completion {
+        let completer: &dyn EnvCompleter = match shell {
+            Shell::Bash => &Bash,
+            Shell::Elvish => &Elvish,
+            Shell::Fish => &Fish,
+            Shell::Zsh => &Zsh,
+            Shell::Pwrsh => &Powershell,
+        };
+        let mut stdout = std::io::stdout();
+        if let Err(e) =
+            completer.write_registration("COMPLETE", BIN_NAME, BIN_NAME, BIN_NAME, &mut stdout)
+        {
+            println!("{}\n{}", alco::tr("error-writing-completion", &[]), e);
+            exit(1);
+        }
+
+        exit(0);
+    }
+
+    if app_m.get_flag("remote") {
+        remote(&app_m);
+    }
+
+    let settings_file = tilde(app_m.get_one::<String>("settings file").unwrap()).into_owned();
+    let settings = match alco::Config::load(&settings_file) {
+        Ok(s) => s,
+        Err(e) => {
+            println!("{}", e);
+            exit(1);
+        }
+    };
+
+    let colors_file = tilde(app_m.get_one::<String>("colorscheme file").unwrap()).into_owned();
+    let config_file = tilde(app_m.get_one::<String>("configuration file").unwrap()).into_owned();
+    let mut opts = match options(&app_m, &settings, &config_file) {
+        Ok(o) => o,
+        Err(e) => {
+            println!("{}", e);
+            exit(1);
+        }
+    };
+    // missing selectors don't get in the way of subcommands that don't reload targets
+    if app_m.subcommand_name().is_some_and(|s| RELOADING_SUBCOMMANDS.contains(&s)) {
+        if let Err(e) = settings.check_targets(&opts) {
+            println!("{}", e);
+            exit(1);
+        }
+    }
+    let scheme_dir = opts.scheme_dir.clone();
+    if let Some(("apply", sub_m)) = app_m.subcommand() {
+        opts.accent = sub_m.get_one::<alco::Color>("accent").copied();
+    }
+
+    match app_m.subcommand() {
+        Some(("apply", sub_m)) if sub_m.get_flag("stdin") => {
+            let mut scheme = String::new();
+            match io::stdin().read_to_string(&mut scheme) {
+                Ok(_) => apply_adhoc(&scheme, "stdin", opts),
+                Err(e) => println!("{}\n{}", alco::tr("error-reading-stdin", &[]), e),
+            }
+        }
+        Some(("apply", sub_m)) if sub_m.contains_id("scheme file") => {
+            let file = tilde(sub_m.get_one::<String>("scheme file").unwrap()).into_owned();
+            let name = Path::new(&file).file_stem().unwrap_or_default().to_string_lossy();
+            match std::fs::read_to_string(&file) {
+                Ok(scheme) => apply_adhoc(&scheme, &name, opts),
+                Err(e) => {
+                    println!("{}\n{}", alco::tr("error-reading-scheme-file", &[("file", &file)]), e)
+                }
+            }
+        }
+        Some(("apply", sub_m)) => {
+            let colorscheme = sub_m.get_one::<String>("colorscheme").unwrap();
+            if sub_m.get_flag("here") {
+                apply_here(colors_file, colorscheme, opts);
+            } else if sub_m.get_flag("dry run") {
+                dry_run(colors_file, config_file, colorscheme, sub_m.get_flag("json"), opts);
+            } else {
+                apply(colors_file, config_file, colorscheme, opts);
+            }
+        }
+        Some(("toggle", sub_m)) => {
+            let reverse = sub_m.get_flag("reverse");
+            toggle(colors_file, config_file, reverse, opts);
+        }
+        Some(("reload", sub_m)) => {
+            if sub_m.get_flag("here") {
+                reload_here(colors_file, config_file, opts);
+            } else if sub_m.get_flag("failed") {
+                reload_failed(config_file, opts);
+            } else {
+                reload(colors_file, config_file, opts);
+            }
+        }
+        Some(("present", sub_m)) => match sub_m.get_one::<String>("state").unwrap().as_str() {
+            "on" => present_on(colors_file, config_file, settings.present_colorscheme, opts),
+            _ => present_off(config_file, opts),
+        },
+        Some(("filter", sub_m)) => {
+            let name = sub_m.get_one::<String>("name").unwrap();
+            let on = sub_m.get_one::<String>("state").unwrap() == "on";
+            filter(config_file, &settings, name, on, opts);
+        }
+        Some(("set", sub_m)) => {
+            let value = sub_m.get_one::<String>("value").unwrap();
+            set_opacity(config_file, value, opts);
+        }
+        Some(("daemon", _)) => {
+            let load = || {
+                let settings = alco::Config::load(&settings_file)?;
+                let opts = options(&app_m, &settings, &config_file)?;
+                settings.check_targets(&opts)?;
+                Ok((settings.daemon, opts))
+            };
+            daemon(&colors_file, &config_file, &settings_file, settings.daemon, opts, load)
+        }
+        Some(("follow-system", _)) => {
+            follow_system(colors_file, config_file, &settings.follow_system, opts)
+        }
+        Some(("schedule", _)) => schedule(colors_file, config_file, &settings.schedule, opts),
+        Some(("snapshot", sub_m)) => {
+            let colorscheme = sub_m.get_one::<String>("colorscheme").unwrap();
+            let out_file = tilde(sub_m.get_one::<String>("out file").unwrap()).into_owned();
+            snapshot(colorscheme, out_file, opts);
+        }
+        Some(("compare", sub_m)) => {
+            let a = sub_m.get_one::<String>("a").unwrap();
+            let b = sub_m.get_one::<String>("b").unwrap();
+            compare(a, b, opts);
+        }
+        Some(("gallery", sub_m)) => {
+            let out_dir = tilde(sub_m.get_one::<String>("out dir").unwrap()).into_owned();
+            gallery(colors_file, out_dir, opts);
+        }
+        Some(("render", sub_m)) => {
+            let colorscheme = sub_m.get_one::<String>("colorscheme").unwrap();
+            let out_dir = tilde(sub_m.get_one::<String>("out dir").unwrap()).into_owned();
+            render(colorscheme, out_dir, opts);
+        }
+        Some(("prerender", _)) => prerender(colors_file, opts),
+        Some(("ping", sub_m)) => {
+            let target = sub_m.get_one::<String>("target").unwrap().parse().unwrap();
+            ping(target, opts);
+        }
+        Some(("list", sub_m)) => {
+            if sub_m.get_flag("long") {
+                list_long(colors_file, config_file, scheme_dir);
+            } else {
+                list(colors_file, scheme_dir);
+            }
+        }
+        Some(("suggest", sub_m)) => {
+            let image = tilde(sub_m.get_one::<String>("image").unwrap()).into_owned();
+            let count = *sub_m.get_one::<usize>("count").unwrap();
+            suggest(colors_file, image, count, opts);
+        }
+        Some(("find", sub_m)) => {
+            let color = *sub_m.get_one::<alco::Color>("color").unwrap();
+            let tolerance = *sub_m.get_one::<f32>("tolerance").unwrap();
+            let background = sub_m.get_flag("background");
+            find(colors_file, color, tolerance, background, opts);
+        }
+        Some(("migrate-schemes", _)) => migrate_schemes(scheme_dir),
+        Some(("import", sub_m)) => match sub_m.subcommand() {
+            Some(("alacritty-theme", import_m)) => {
+                let file = tilde(import_m.get_one::<String>("file").unwrap()).into_owned();
+                let name = import_m.get_one::<String>("name");
+                import_alacritty_theme(colors_file, scheme_dir, &file, name);
+            }
+            _ => unreachable!(),
+        },
+        Some(("convert", sub_m)) => {
+            let from = sub_m.get_one::<String>("from").unwrap().parse().unwrap();
+            let to = sub_m.get_one::<String>("to").unwrap().parse().unwrap();
+            convert(from, to);
+        }
+        Some(("update", sub_m)) => {
+            let colorscheme = sub_m.get_one::<String>("colorscheme").unwrap();
+            update_scheme(scheme_dir, colorscheme);
+        }
+        Some(("fetch", sub_m)) => {
+            let colorschemes: Vec<_> = match sub_m.get_many::<String>("colorschemes") {
+                Some(c) => c.cloned().collect(),
+                None => Vec::new(),
+            };
+            let update_all = sub_m.get_flag("update all");
+            let yes = sub_m.get_flag("yes");
+            fetch(scheme_dir, colorschemes, update_all, yes);
+        }
+        Some(("show", sub_m)) => {
+            let colorscheme = sub_m.get_one::<String>("colorscheme").unwrap();
+            let meta = sub_m.get_flag("meta");
+            let roles = sub_m.get_flag("roles");
+            show(colorscheme, meta, roles, opts);
+        }
+        Some(("resolve", sub_m)) => {
+            let colorscheme = sub_m.get_one::<String>("colorscheme").unwrap();
+            resolve(colorscheme, opts);
+        }
+        Some(("setup", _)) => setup(colors_file, settings_file, opts),
+        Some(("pack", sub_m)) => match sub_m.subcommand() {
+            Some(("install", install_m)) => {
+                let source = tilde(install_m.get_one::<String>("source").unwrap()).into_owned();
+                let pack_dir = tilde(install_m.get_one::<String>("pack dir").unwrap()).into_owned();
+                install_pack(colors_file, &source, pack_dir, opts);
+            }
+            Some(("export", export_m)) => {
+                let colorschemes: Vec<_> =
+                    export_m.get_many::<String>("colorschemes").unwrap().cloned().collect();
+                let out_file = tilde(export_m.get_one::<String>("out file").unwrap()).into_owned();
+                export_pack(colors_file, &colorschemes, out_file, opts);
+            }
+            _ => unreachable!(),
+        },
+        Some(("selector", sub_m)) => match sub_m.subcommand() {
+            Some(("edit", edit_m)) => {
+                let target = edit_m.get_one::<String>("target").unwrap().parse().unwrap();
+                edit_selector(colors_file, target, opts);
+            }
+            Some(("generate", generate_m)) => {
+                let target = generate_m.get_one::<String>("target").unwrap().parse().unwrap();
+                generate_selector(colors_file, target, generate_m.get_flag("write"), opts);
+            }
+            _ => unreachable!(),
+        },
+        Some(("hook", sub_m)) => {
+            let shell = sub_m.get_one::<String>("shell").unwrap();
+            match alco::shell_hook(shell) {
+                Ok(hook) => print!("{}", hook),
+                Err(e) => println!("{}\n{}", alco::tr("error-generating-hook", &[]), e),
+            }
+        }
+        Some(("log", sub_m)) => match sub_m.subcommand() {
+            Some(("list", _)) => log_list(),
+            Some(("show", show_m)) => log_show(*show_m.get_one::<usize>("n").unwrap()),
+            _ => unreachable!(),
+        },
+        Some(("status", sub_m)) => {
+            let time = sub_m.get_flag("time").then(|| {
+                let format = sub_m.get_one::<String>("format").unwrap();
+                let precision = sub_m.get_one::<String>("precision").unwrap();
+                (format.as_str(), precision.as_str())
+            });
+            if sub_m.get_flag("previous") {
+                previous(config_file);
+            } else {
+                status(config_file, time);
+            }
+        }
+        _ => {
+            app().print_help().ok();
+        }
+    }
+}
+
+/// Sends the subcommand to `alco daemon` instead of running it, then exits.
+fn remote(app_m: &ArgMatches) {
+    let command = match app_m.subcommand() {
+        // the daemon only applies colorschemes by name, with the options it was started with
+        Some(("apply", sub_m))
+            if !sub_m.get_flag("here")
+                && !sub_m.get_flag("stdin")
+                && !sub_m.contains_id("scheme file")
+                && !sub_m.get_flag("dry run")
+                && !sub_m.contains_id("accent") =>
+        {
+            format!("apply {}", sub_m.get_one::<String>("colorscheme").unwrap())
+        }
+        Some(("toggle", sub_m)) if !sub_m.get_flag("reverse") => "toggle".to_owned(),
+        Some(("list", sub_m)) if !sub_m.get_flag("long") => "list".to_owned(),
+        Some(("status", sub_m)) if !sub_m.get_flag("time") && !sub_m.get_flag("previous") => {
+            "status".to_owned()
+        }
+        _ => {
+            println!("{}", alco::tr("only-remote-commands", &[]));
+            exit(1);
+        }
+    };
+
+    match alco::send_command(&command) {
+        Ok(reply) => {
+            if !reply.is_empty() {
+                println!("{}", reply);
+            }
+            exit(0);
+        }
+        Err(e) => {
+            println!("{}", e);
+            exit(1);
+        }
+    }
+}
+
+fn apply(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    colorscheme: &str,
+    opts: alco::Options,
+) {
+    let previous;
+    let colorscheme = match colorscheme {
+        "-" => match alco::status(&config_file).map(|s| s.previous) {
+            Ok(Some(p)) => {
+                previous = p;
+                &previous
+            }
+            Ok(None) => {
+                println!(
+                    "{}\n{}",
+                    alco::tr("error-applying-previous", &[]),
+                    alco::tr("no-previous-colorscheme", &[])
+                );
+                return;
+            }
+            Err(e) => {
+                println!("{}\n{}", alco::tr("error-applying-previous", &[]), e);
+                return;
+            }
+        },
+        c => c,
+    };
+
+    match alco::apply(colors_file, &config_file, colorscheme.to_owned()) {
+        Ok(_) => apply_colorscheme(config_file, colorscheme, opts),
+        Err(e) => {
+            println!("{}\n{:?}", alco::tr("error-applying", &[("colorscheme", colorscheme)]), e);
+        }
+    }
+}
+
+/// Applies a scheme file that isn't installed in the scheme directory. The current colorscheme
+/// isn't changed, so toggling or reloading returns to it.
+fn apply_adhoc(scheme: &str, name: &str, mut opts: alco::Options) {
+    match alco::load_adhoc_scheme(&mut opts, scheme, name) {
+        Ok(name) => apply_colors(None, &name, opts),
+        Err(e) => println!("{}\n{}", alco::tr("error-applying", &[("colorscheme", name)]), e),
+    }
+}
+
+/// Prints the changes applying a colorscheme would make, as unified diffs and shell commands, or as
+/// a json array with one record per target.
+fn dry_run(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    colorscheme: &str,
+    json: bool,
+    mut opts: alco::Options,
+) {
+    let colorscheme = match colorscheme {
+        "-" => match alco::status(&config_file).map(|s| s.previous) {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                println!(
+                    "{}\n{}",
+                    alco::tr("error-applying-previous", &[]),
+                    alco::tr("no-previous-colorscheme", &[])
+                );
+                exit(1);
+            }
+            Err(e) => {
+                println!("{}\n{}", alco::tr("error-applying-previous", &[]), e);
+                exit(1);
+            }
+        },
+        c => c.to_owned(),
+    };
+    match alco::list(colors_file) {
+        Ok(c) if c.contains(&colorscheme) => (),
+        Ok(_) => {
+            println!(
+                "{}\n{}",
+                alco::tr("error-applying", &[("colorscheme", &colorscheme)]),
+                alco::tr("no-matching-colorscheme", &[])
+            );
+            exit(1);
+        }
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-applying", &[("colorscheme", &colorscheme)]), e);
+            exit(1);
+        }
+    }
+
+    if let Err(e) = opts.override_accent(&colorscheme) {
+        println!(
+            "{}\n{}",
+            alco::tr("error-overriding-accent", &[("colorscheme", &colorscheme)]),
+            e
+        );
+        exit(1);
+    }
+
+    let plan = alco::plan(&opts, &colorscheme);
+    if json {
+        let records: Vec<_> = plan
+            .into_iter()
+            .map(|(target, change)| match change {
+                Ok(c) => serde_json::json!({
+                    "target": target,
+                    "path": c.path,
+                    "hunks": c.hunks,
+                    "commands": c.commands,
+                }),
+                Err(e) => serde_json::json!({ "target": target, "error": e.to_string() }),
+            })
+            .collect();
+        println!("{}", serde_json::Value::Array(records));
+        return;
+    }
+
+    for (target, change) in plan {
+        let change = match change {
+            Ok(c) => c,
+            Err(e) => {
+                println!(
+                    "{}\n{}",
+                    alco::tr("error-rendering", &[("target", &target.to_string())]),
+                    e
+                );
+                continue;
+            }
+        };
+        if let Some(path) = change.path.filter(|_| !change.hunks.is_empty()) {
+            println!("--- {}\n+++ {}", path.display(), path.display());
+            for hunk in change.hunks {
+                print!("{}", hunk);
+            }
+        }
+        for command in change.commands {
+            println!("$ {}", command);
+        }
+    }
+}
+
+fn apply_here(colors_file: impl AsRef<Path>, colorscheme: &str, opts: alco::Options) {
+    match alco::list(colors_file) {
+        Ok(c) if c.iter().any(|c| c == colorscheme) => (),
+        Ok(_) => {
+            println!(
+                "{}\n{}",
+                alco::tr("error-applying", &[("colorscheme", colorscheme)]),
+                alco::tr("no-matching-colorscheme", &[])
+            );
+            return;
+        }
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-applying", &[("colorscheme", colorscheme)]), e);
+            return;
+        }
+    }
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .expect("tokio runtime failed to start");
+
+    if let Err(e) = runtime.block_on(alco::apply_here(&opts, colorscheme)) {
+        println!("{}\n{}", alco::tr("error-applying", &[("colorscheme", colorscheme)]), e);
+    }
+}
+
+fn reload_here(colors_file: impl AsRef<Path>, config_file: impl AsRef<Path>, opts: alco::Options) {
+    match alco::status(config_file) {
+        Ok(s) => apply_here(colors_file, &s.current, opts),
+        Err(e) => println!("{}\n{}", alco::tr("error-getting-current", &[]), e),
+    }
+}
+
+fn toggle(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    reverse: bool,
+    opts: alco::Options,
+) {
+    match alco::toggle(&colors_file, &config_file, &opts.scheme_dir, reverse) {
+        Ok(colorscheme) => apply_colorscheme(config_file, &colorscheme, opts),
+        Err(e) => println!("{}\n{}", alco::tr("error-toggling", &[]), e),
+    }
+}
+
+fn reload(colors_file: impl AsRef<Path>, config_file: impl AsRef<Path>, opts: alco::Options) {
+    match alco::reload(&colors_file, &config_file) {
+        Ok(colorscheme) => apply_colorscheme(config_file, &colorscheme, opts),
+        Err(e) => println!("{}\n{}", alco::tr("error-reloading", &[]), e),
+    }
+}
+
+fn present_on(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    colorscheme: Option<String>,
+    opts: alco::Options,
+) {
+    let colorscheme = match colorscheme {
+        Some(c) => c,
+        None => {
+            println!(
+                "{}\n{}",
+                alco::tr("error-present-on", &[]),
+                alco::tr("no-present-colorscheme", &[])
+            );
+            return;
+        }
+    };
+
+    match alco::present_on(colors_file, &config_file, colorscheme.clone()) {
+        Ok(_) => apply_colorscheme(config_file, &colorscheme, opts),
+        Err(e) => println!("{}\n{}", alco::tr("error-present-on", &[]), e),
+    }
+}
+
+fn present_off(config_file: impl AsRef<Path>, opts: alco::Options) {
+    match alco::present_off(&config_file) {
+        Ok(colorscheme) => apply_colorscheme(config_file, &colorscheme, opts),
+        Err(e) => println!("{}\n{}", alco::tr("error-present-off", &[]), e),
+    }
+}
+
+fn filter(
+    config_file: impl AsRef<Path>,
+    settings: &alco::Config,
+    name: &str,
+    on: bool,
+    mut opts: alco::Options,
+) {
+    if !settings.filters.contains_key(name) {
+        println!(
+            "{}\n{}",
+            alco::tr("error-setting-filter", &[("filter", name)]),
+            alco::tr("unknown-filter", &[])
+        );
+        return;
+    }
+
+    match alco::set_filter(&config_file, name, on) {
+        Ok(colorscheme) => {
+            opts.filters = settings.active_filters(&config_file);
+            apply_colorscheme(config_file, &colorscheme, opts);
+        }
+        Err(e) => println!("{}\n{}", alco::tr("error-setting-filter", &[("filter", name)]), e),
+    }
+}
+
+fn set_opacity(config_file: impl AsRef<Path>, value: &str, mut opts: alco::Options) {
+    let opacity = match value {
+        "off" => None,
+        v => match v.parse::<f32>() {
+            Ok(o) => Some(o),
+            Err(e) => {
+                println!("{}\n{}", alco::tr("error-setting-opacity", &[]), e);
+                return;
+            }
+        },
+    };
+
+    match alco::set_opacity(&config_file, opacity) {
+        Ok(colorscheme) => {
+            opts.opacity = opacity;
+            apply_colorscheme(config_file, &colorscheme, opts);
+        }
+        Err(e) => println!("{}\n{}", alco::tr("error-setting-opacity", &[]), e),
+    }
+}
+
+fn reload_failed(config_file: impl AsRef<Path>, mut opts: alco::Options) {
+    match alco::status(&config_file) {
+        Ok(s) if s.failed.is_empty() => println!("{}", alco::tr("no-failed-targets", &[])),
+        Ok(s) => {
+            for t in alco::Target::ALL {
+                opts.set_reload(t, s.failed.contains(&t));
+            }
+            apply_colorscheme(config_file, &s.current, opts);
+        }
+        Err(e) => println!("{}\n{}", alco::tr("error-reloading-failed", &[]), e),
+    }
+}
+
+fn apply_colorscheme(config_file: impl AsRef<Path>, colorscheme: &str, opts: alco::Options) {
+    apply_colors(Some(config_file.as_ref()), colorscheme, opts)
+}
+
+/// Reloads the targets and runs everything else that follows a colorscheme change. The targets
+/// that failed to reload are saved in the state file `config_file`, if there is one.
+fn apply_colors(config_file: Option<&Path>, colorscheme: &str, mut opts: alco::Options) {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(8)
+        .enable_io()
+        .build()
+        .expect("tokio runtime failed to start");
+
+    let script = match opts.script_file.exists() {
+        true => match alco::Script::load(&opts.script_file) {
+            Ok(s) => Some(s),
+            Err(e) => {
+                println!("{}\n{}", alco::tr("error-loading-script", &[]), e);
+                None
+            }
+        },
+        false => None,
+    };
+    if let Some(s) = &script {
+        if let Err(e) = s.transform(&mut opts, colorscheme) {
+            println!(
+                "{}\n{}",
+                alco::tr("error-transforming-palette", &[("colorscheme", colorscheme)]),
+                e
+            );
+        }
+    }
+    if let Err(e) = opts.override_accent(colorscheme) {
+        println!("{}\n{}", alco::tr("error-overriding-accent", &[("colorscheme", colorscheme)]), e);
+    }
+
+    let delivery = match opts.detect_terminal {
+        true => alco::terminal_delivery(&opts),
+        false => None,
+    };
+    if let Some(alco::Delivery::Target(t)) = delivery {
+        opts.set_reload(t, true);
+    }
+
+    let refresh = opts.refresh.clone();
+    let before = opts.audit_log.is_some().then(|| alco::read_files(&opts));
+    let opts = Arc::new(opts);
+    let results = runtime.block_on(alco::reload_targets(Arc::clone(&opts), colorscheme));
+    let mut failed = Vec::new();
+    for (target, result) in results {
+        if let Err(e) = result {
+            println!(
+                "{}\n{}",
+                alco::tr("error-reloading-target", &[("target", &target.to_string())]),
+                e
+            );
+            failed.push(target);
+        }
+    }
+    if let (Some(log_file), Some(before)) = (&opts.audit_log, before) {
+        if let Err(e) = alco::log_changes(log_file, colorscheme, before) {
+            println!("{}\n{}", alco::tr("error-writing-audit-log", &[]), e);
+        }
+    }
+
+    if let Some(s) = &script {
+        for (name, result) in s.run_targets(&opts, colorscheme) {
+            if let Err(e) = result {
+                println!("{}\n{}", alco::tr("error-reloading-target", &[("target", &name)]), e);
+            }
+        }
+    }
+    for (name, result) in alco::render_templates(&opts, colorscheme) {
+        if let Err(e) = result {
+            println!("{}\n{}", alco::tr("error-rendering-template", &[("template", &name)]), e);
+        }
+    }
+
+    if delivery == Some(alco::Delivery::Osc) {
+        if let Err(e) = runtime.block_on(alco::apply_here(&opts, colorscheme)) {
+            println!("{}\n{}", alco::tr("error-updating-terminal", &[]), e);
+        }
+    } else {
+        warn_live_reload(&opts, &failed);
+    }
+
+    if let Some(config_file) = config_file {
+        if let Err(e) = alco::set_failed(config_file, &failed) {
+            println!("{}\n{}", alco::tr("error-saving-results", &[]), e);
+        }
+    }
+
+    for result in alco::refresh_status_bars(&refresh) {
+        if let Err(e) = result {
+            println!("{}\n{}", alco::tr("error-refreshing-status-bar", &[]), e);
+        }
+    }
+
+    if let Err(e) = alco::set_temperature(&opts.gamma, colorscheme) {
+        println!("{}\n{}", alco::tr("error-setting-temperature", &[]), e);
+    }
+
+    match alco::run_post_apply_hooks(&opts, colorscheme) {
+        Ok(results) => {
+            for (name, result) in results {
+                if let Err(e) = result {
+                    println!("{}\n{}", alco::tr("error-running-hook", &[("hook", &name)]), e);
+                }
+            }
+        }
+        Err(e) => println!("{}\n{}", alco::tr("error-running-hooks", &[]), e),
+    }
+}
+
+/// Reloads the targets without changing the current colorscheme.
+fn reload_colorscheme(runtime: &Runtime, opts: &Arc<alco::Options>, colorscheme: &str) {
+    let results = runtime.block_on(alco::reload_targets(Arc::clone(opts), colorscheme));
+    for (target, result) in results {
+        if let Err(e) = result {
+            println!(
+                "{}\n{}",
+                alco::tr("error-reloading-target", &[("target", &target.to_string())]),
+                e
+            );
+        }
+    }
+}
+
+/// Prints the applies of the audit log with the targets whose files they changed.
+fn log_list() {
+    let entries = match alco::audit_entries(alco::audit_log_path()) {
+        Ok(e) => e,
+        Err(e) => {
+            println!("{}", e);
+            exit(1);
+        }
+    };
+    for (i, entry) in entries.iter().enumerate() {
+        let time = humantime::format_rfc3339_seconds(entry.time);
+        let targets: Vec<_> = entry.changes.iter().map(|c| c.target.name()).collect();
+        println!("{:>3}  {}  {}  {}", i + 1, time, entry.colorscheme, targets.join(", "));
+    }
+}
+
+/// Prints the diffs of the `n`th most recent apply of the audit log.
+fn log_show(n: usize) {
+    let entry = match alco::audit_entries(alco::audit_log_path()) {
+        Ok(e) => e.into_iter().nth(n.saturating_sub(1)),
+        Err(e) => {
+            println!("{}", e);
+            exit(1);
+        }
+    };
+    let entry = match entry {
+        Some(e) if n > 0 => e,
+        _ => {
+            println!("{}", alco::tr("no-audit-entry", &[("n", &n.to_string())]));
+            exit(1);
+        }
+    };
+
+    let time = humantime::format_rfc3339_seconds(entry.time);
+    println!("{} applied at {}", entry.colorscheme, time);
+    for change in entry.changes {
+        println!("--- {}\n+++ {}", change.path.display(), change.path.display());
+        for hunk in change.hunks {
+            print!("{}", hunk);
+        }
+    }
+}
+
+/// Running alacritty instances ignore the rewritten configuration file if live reload is disabled.
+fn warn_live_reload(opts: &alco::Options, failed: &[alco::Target]) {
+    let file = &opts.alacritty.file;
+    let reloaded =
+        opts.reload(alco::Target::Alacritty) && !failed.contains(&alco::Target::Alacritty);
+    if reloaded && !alco::alacritty_live_reload(file) {
+        println!("{}", alco::tr("warning-live-reload", &[("file", &file.display().to_string())]));
+    }
+}
+
+/// Runs the daemon. `load` reloads the daemon settings and the options when the settings file or a
+/// selector file changes, the new options are used from the next colorscheme change on.
+fn daemon(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    settings_file: impl AsRef<Path>,
+    mut settings: alco::DaemonConfig,
+    mut opts: alco::Options,
+    load: impl Fn() -> anyhow::Result<(alco::DaemonConfig, alco::Options)>,
+) {
+    let (sender, receiver) = mpsc::channel();
+    if let Err(e) = alco::watch_fifo(sender.clone()) {
+        println!("{}\n{}", alco::tr("error-starting-daemon", &[]), e);
+        exit(1);
+    }
+    if let Err(e) = alco::watch_socket(sender.clone()) {
+        println!("{}\n{}", alco::tr("error-starting-daemon", &[]), e);
+        exit(1);
+    }
+    if let Some(address) = &settings.metrics_address {
+        if let Err(e) = alco::serve_metrics(address) {
+            println!("{}\n{}", alco::tr("error-starting-daemon", &[]), e);
+            exit(1);
+        }
+    }
+    let mut files = vec![settings_file.as_ref().to_path_buf()];
+    files.extend(alco::Target::ALL.iter().filter_map(|t| opts.selector(*t)).map(Path::to_path_buf));
+    if let Err(e) = alco::watch_files(files, sender.clone()) {
+        println!("{}\n{}", alco::tr("error-starting-daemon", &[]), e);
+        exit(1);
+    }
+    let mut watchers = Watchers::default();
+    if let Err(e) = watchers.start(&settings, &sender) {
+        println!("{}\n{}", alco::tr("error-starting-daemon", &[]), e);
+        exit(1);
+    }
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(8)
+        .enable_io()
+        .build()
+        .expect("tokio runtime failed to start");
+
+    // the colorscheme of the focused window, `None` if no application specific colorscheme is
+    // applied
+    let mut focus_colorscheme = None;
+    // the colorscheme pinned to today's date
+    let mut pinned_colorscheme = None;
+    let mut brightness = 1.0;
+    let mut on_battery = false;
+    let mut idle = false;
+    // the colorschemes the terminals on workspaces and outputs with rules were recolored with
+    let mut recolored = BTreeMap::new();
+    let mut pending = None;
+    while let Some((event, replies)) = next_event(&receiver, &mut pending) {
+        'event: {
+            match &event {
+                alco::Event::Focus(class) => {
+                    let colorscheme = settings.focus.get(class).cloned();
+                    if colorscheme == focus_colorscheme {
+                        break 'event;
+                    }
+                    focus_colorscheme = colorscheme;
+                }
+                alco::Event::Workspaces => break 'event,
+                alco::Event::Idle(i) => {
+                    if *i == idle {
+                        break 'event;
+                    }
+                    idle = *i;
+                }
+                alco::Event::Power(battery) => {
+                    if *battery == on_battery {
+                        break 'event;
+                    }
+                    on_battery = *battery;
+                }
+                alco::Event::Hour(hour) => {
+                    let pinned = if settings.is_scheduled() {
+                        match alco::pinned_colorscheme(&settings) {
+                            Ok(p) => p,
+                            Err(e) => {
+                                println!("{}\n{}", alco::tr("error-reading-schedule", &[]), e);
+                                pinned_colorscheme.clone()
+                            }
+                        }
+                    } else {
+                        None
+                    };
+                    let b = settings.dim.brightness(*hour as f32);
+                    if pinned == pinned_colorscheme && b == brightness {
+                        break 'event;
+                    }
+                    pinned_colorscheme = pinned;
+                    brightness = b;
+                }
+                alco::Event::Apply(colorscheme) => {
+                    apply(&colors_file, &config_file, colorscheme, opts.clone());
+                    // keep the application specific, pinned, dimmed, battery or idle colors
+                    if focus_colorscheme.is_none()
+                        && pinned_colorscheme.is_none()
+                        && brightness == 1.0
+                        && !on_battery
+                        && !idle
+                    {
+                        break 'event;
+                    }
+                }
+                alco::Event::Toggle => {
+                    toggle(&colors_file, &config_file, false, opts.clone());
+                    if focus_colorscheme.is_none()
+                        && pinned_colorscheme.is_none()
+                        && brightness == 1.0
+                        && !on_battery
+                        && !idle
+                    {
+                        break 'event;
+                    }
+                }
+                alco::Event::Status => {
+                    if replies.is_empty() {
+                        status(&config_file, Some(("relative", "seconds")));
+                    }
+                    break 'event;
+                }
+                alco::Event::Reload => {
+                    match load() {
+                        Ok((s, o)) => {
+                            settings = s;
+                            opts = o;
+                            println!("{}", alco::tr("reloaded-settings", &[]));
+                        }
+                        Err(e) => println!("{}\n{}", alco::tr("error-reloading-settings", &[]), e),
+                    }
+                    if let Err(e) = watchers.start(&settings, &sender) {
+                        println!("{}\n{}", alco::tr("error-reloading-settings", &[]), e);
+                    }
+                    break 'event;
+                }
+                alco::Event::List | alco::Event::Remote(..) => break 'event,
+            }
+
+            let base = match &pinned_colorscheme {
+                Some(c) => c.clone(),
+                None => match alco::status(&config_file) {
+                    Ok(s) => s.current,
+                    Err(e) => {
+                        println!("{}\n{}", alco::tr("error-getting-current", &[]), e);
+                        break 'event;
+                    }
+                },
+            };
+            let battery = on_battery
+                .then(|| settings.battery.colorscheme(alco::variant(&opts.scheme_dir, &base)))
+                .flatten();
+            let apply = match focus_colorscheme.as_ref().or(battery) {
+                Some(c) => c.clone(),
+                None => base,
+            };
+
+            let mut opts = opts.clone();
+            if let Err(e) = opts.dim_background(&apply, brightness) {
+                println!("{}\n{}", alco::tr("error-dimming", &[("colorscheme", &apply)]), e);
+            }
+            if idle {
+                opts.filters.push(("idle".to_owned(), settings.idle.filter()));
+            }
+            reload_colorscheme(&runtime, &Arc::new(opts), &apply);
+        }
+
+        if settings.has_workspace_rules() || !recolored.is_empty() {
+            let current = focus_colorscheme.as_ref().or(pinned_colorscheme.as_ref()).cloned();
+            let current = current.or_else(|| alco::status(&config_file).ok().map(|s| s.current));
+            match &event {
+                alco::Event::Status | alco::Event::List | alco::Event::Remote(..) => (),
+                // only terminals that changed their workspace or output have to be recolored
+                alco::Event::Workspaces => {
+                    recolor_workspaces(&settings, &opts, current, &mut recolored, false)
+                }
+                // reloaded terminals may have reset the colors of all their windows
+                _ => recolor_workspaces(&settings, &opts, current, &mut recolored, true),
+            }
+        }
+
+        for reply in replies {
+            reply.send(remote_reply(&event, &colors_file, &config_file, &opts.scheme_dir)).ok();
+        }
+    }
+    println!("{}", alco::tr("error-receiving-daemon-events", &[]));
+    exit(1);
+}
+
+/// Recolors the terminals on workspaces and outputs with rules using escape sequences. Terminals
+/// that were recolored before but no longer match a rule get the `current` colors back. Only
+/// terminals whose colorscheme changed are recolored, unless `all` is set.
+fn recolor_workspaces(
+    settings: &alco::DaemonConfig,
+    opts: &alco::Options,
+    current: Option<String>,
+    recolored: &mut BTreeMap<PathBuf, String>,
+    all: bool,
+) {
+    let windows = match alco::terminal_windows() {
+        Ok(w) => w,
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-listing-terminals", &[]), e);
+            return;
+        }
+    };
+
+    let mut next = BTreeMap::new();
+    for window in windows {
+        let colorscheme = settings.workspace_colorscheme(&window.workspace, &window.output);
+        if let Some(c) = colorscheme {
+            next.extend(window.ttys.into_iter().map(|t| (t, c.clone())));
+        }
+    }
+
+    let reverted: Vec<_> = recolored.keys().filter(|t| !next.contains_key(*t)).cloned().collect();
+    let mut changes: Vec<_> = next
+        .iter()
+        .filter(|(tty, c)| all || recolored.get(*tty) != Some(c))
+        .map(|(tty, c)| (tty.clone(), c.clone()))
+        .collect();
+    if let Some(current) = &current {
+        changes.extend(reverted.into_iter().map(|t| (t, current.clone())));
+    }
+
+    for (tty, colorscheme) in changes {
+        let scheme = match alco::load_colorscheme(opts, &colorscheme) {
+            Ok(s) => s.map(|c| opts.map_color(c)),
+            Err(e) => {
+                println!(
+                    "{}\n{}",
+                    alco::tr("error-loading-colors", &[("colorscheme", &colorscheme)]),
+                    e
+                );
+                continue;
+            }
+        };
+        // terminals that were closed since listing them are ignored
+        if !tty.exists() {
+            continue;
+        }
+        if let Err(e) = alco::write_osc_tty(&tty, &scheme) {
+            println!(
+                "{}\n{}",
+                alco::tr("error-recoloring-terminal", &[("tty", &tty.display().to_string())]),
+                e
+            );
+        }
+    }
+    *recolored = next;
+}
+
+/// The optional event sources of the daemon, which are started once they're needed.
+#[derive(Default)]
+struct Watchers {
+    focus: bool,
+    clock: bool,
+    idle: bool,
+    power: bool,
+    workspaces: bool,
+}
+
+impl Watchers {
+    /// Starts the watchers the daemon settings need that aren't running yet. Watchers that are no
+    /// longer needed keep running, their events just don't change the colorscheme anymore.
+    fn start(
+        &mut self,
+        settings: &alco::DaemonConfig,
+        sender: &mpsc::Sender<alco::Event>,
+    ) -> anyhow::Result<()> {
+        if !self.focus && !settings.focus.is_empty() {
+            alco::watch_focus(sender.clone())?;
+            self.focus = true;
+        }
+        if !self.clock && (!settings.dim.curve.is_empty() || settings.is_scheduled()) {
+            alco::watch_clock(sender.clone())?;
+            self.clock = true;
+        }
+        if let (false, Some(minutes)) = (self.idle, settings.idle.minutes) {
+            alco::watch_idle(minutes, sender.clone())?;
+            self.idle = true;
+        }
+        if !self.power && settings.battery.is_set() {
+            alco::watch_power(sender.clone())?;
+            self.power = true;
+        }
+        if !self.workspaces && settings.has_workspace_rules() {
+            alco::watch_workspaces(sender.clone())?;
+            self.workspaces = true;
+        }
+        Ok(())
+    }
+}
+
+/// Receives the next daemon event, unwrapping remote events into the event and the channels the
+/// result is sent back on. An apply request that is followed by another one within
+/// [`COALESCE_DELAY`] is dropped in favor of the later one, and its client is answered with the
+/// result of that. An event that ends the wait is stored in `pending` and returned next.
+fn next_event(
+    receiver: &mpsc::Receiver<alco::Event>,
+    pending: &mut Option<alco::Event>,
+) -> Option<(alco::Event, Vec<alco::Reply>)> {
+    let unwrap = |event| match event {
+        alco::Event::Remote(e, r) => (*e, Some(r)),
+        e => (e, None),
+    };
+    let is_apply = |event: &alco::Event| match event {
+        alco::Event::Remote(e, _) => matches!(**e, alco::Event::Apply(_)),
+        e => matches!(e, alco::Event::Apply(_)),
+    };
+
+    let (mut event, reply) = unwrap(pending.take().or_else(|| receiver.recv().ok())?);
+    let mut replies: Vec<_> = reply.into_iter().collect();
+    while let alco::Event::Apply(_) = event {
+        match receiver.recv_timeout(COALESCE_DELAY) {
+            Ok(next) if is_apply(&next) => {
+                let (next, reply) = unwrap(next);
+                event = next;
+                replies.extend(reply);
+            }
+            Ok(next) => {
+                *pending = Some(next);
+                break;
+            }
+            Err(_) => break,
+        }
+    }
+    Some((event, replies))
+}
+
+fn follow_system(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    settings: &alco::FollowSystemConfig,
+    opts: alco::Options,
+) {
+    let (dark, light) = match (&settings.dark, &settings.light) {
+        (Some(d), Some(l)) => (d, l),
+        _ => {
+            println!(
+                "{}\n{}",
+                alco::tr("error-following-system", &[]),
+                alco::tr("no-system-colorschemes", &[])
+            );
+            exit(1);
+        }
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    if let Err(e) = alco::watch_appearance(sender) {
+        println!("{}\n{}", alco::tr("error-following-system", &[]), e);
+        exit(1);
+    }
+
+    let colorschemes = receiver.into_iter().map(|appearance| match appearance {
+        alco::Appearance::Dark => dark,
+        alco::Appearance::Light => light,
+    });
+    apply_changes(colors_file, config_file, colorschemes, opts);
+    println!("{}", alco::tr("error-receiving-appearance", &[]));
+    exit(1);
+}
+
+fn schedule(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    settings: &alco::ScheduleConfig,
+    opts: alco::Options,
+) {
+    let (day, night) = match (&settings.day, &settings.night) {
+        (Some(d), Some(n)) => (d, n),
+        _ => {
+            println!(
+                "{}\n{}",
+                alco::tr("error-starting-schedule", &[]),
+                alco::tr("no-schedule-colorschemes", &[])
+            );
+            exit(1);
+        }
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    if let Err(e) = alco::watch_schedule(settings, sender) {
+        println!("{}\n{}", alco::tr("error-starting-schedule", &[]), e);
+        exit(1);
+    }
+
+    let colorschemes = receiver.into_iter().map(|time| match time {
+        alco::TimeOfDay::Day => day,
+        alco::TimeOfDay::Night => night,
+    });
+    apply_changes(colors_file, config_file, colorschemes, opts);
+    println!("{}", alco::tr("error-receiving-schedule", &[]));
+    exit(1);
+}
+
+/// Applies each colorscheme as it arrives, unless it's already the current one.
+fn apply_changes<'a>(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    colorschemes: impl Iterator<Item = &'a String>,
+    opts: alco::Options,
+) {
+    for colorscheme in colorschemes {
+        if let Ok(s) = alco::status(&config_file) {
+            if s.current == *colorscheme {
+                continue;
+            }
+        }
+        apply(&colors_file, &config_file, colorscheme, opts.clone());
+    }
+}
+
+/// The reply to a command sent to the daemon through its socket, after it was handled.
+fn remote_reply(
+    event: &alco::Event,
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+) -> Result<String, String> {
+    if let alco::Event::List = event {
+        return match alco::list_enabled(colors_file, scheme_dir) {
+            Ok(c) => Ok(c.join("\n")),
+            Err(e) => Err(format!("{}\n{}", alco::tr("error-listing-colorschemes", &[]), e)),
+        };
+    }
+
+    let status = alco::status(config_file)
+        .map_err(|e| format!("{}\n{}", alco::tr("error-getting-current", &[]), e))?;
+    if let alco::Event::Apply(c) = event {
+        if c != "-" && *c != status.current {
+            return Err(format!(
+                "{}\n{}",
+                alco::tr("error-applying", &[("colorscheme", c)]),
+                alco::tr("no-matching-colorscheme", &[])
+            ));
+        }
+    }
+
+    let mut reply = status.current;
+    if !status.failed.is_empty() {
+        let failed: Vec<_> = status.failed.iter().map(|t| t.name()).collect();
+        reply.push_str(&format!(
+            "\n{}",
+            alco::tr("warning-failed-targets", &[("targets", &failed.join(", "))])
+        ));
+    }
+    Ok(reply)
+}
+
+/// Renders the configuration files of the selected targets, or all targets if none is selected,
+/// into `<out_dir>/<target>/`.
+fn render(colorscheme: &str, out_dir: impl AsRef<Path>, opts: alco::Options) {
+    for (target, result) in alco::render_to_dir(&opts, colorscheme, out_dir) {
+        match result {
+            Ok(p) => println!("Rendered {} to {}", target, p.display()),
+            Err(e) => println!(
+                "{}\n{}",
+                alco::tr("error-rendering", &[("target", &target.to_string())]),
+                e
+            ),
+        }
+    }
+}
+
+fn snapshot(colorscheme: &str, out_file: impl AsRef<Path>, opts: alco::Options) {
+    match alco::snapshot(&opts, colorscheme, &out_file) {
+        Ok(_) => println!("Saved snapshot of {} to {}", colorscheme, out_file.as_ref().display()),
+        Err(e) => println!(
+            "{}\n{}",
+            alco::tr("error-creating-snapshot", &[("colorscheme", colorscheme)]),
+            e
+        ),
+    }
+}
+
+/// Prints both colorschemes side by side. Inside tmux the colors stay until enter is pressed.
+fn compare(a: &str, b: &str, opts: alco::Options) {
+    let comparison = match alco::compare(&opts, a, b) {
+        Ok(c) => c,
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-comparing", &[("a", a), ("b", b)]), e);
+            std::process::exit(1);
+        }
+    };
+
+    match &comparison {
+        alco::Comparison::SideBySide(lines) => print!("{}", lines),
+        alco::Comparison::TmuxPanes { sample, .. } => {
+            print!("{}\nPress enter to end the comparison", sample);
+            io::stdout().flush().ok();
+            io::stdin().read_line(&mut String::new()).ok();
+        }
+    }
+
+    if let Err(e) = alco::end_comparison(&comparison) {
+        println!("{}\n{}", alco::tr("error-ending-comparison", &[]), e);
+    }
+}
+
+fn gallery(colors_file: impl AsRef<Path>, out_dir: impl AsRef<Path>, opts: alco::Options) {
+    match alco::gallery(&opts, colors_file, &out_dir) {
+        Ok(skipped) => {
+            for (name, e) in skipped.iter() {
+                println!("Skipped {}:\n{}", name, e);
+            }
+            println!("Saved gallery to {}", out_dir.as_ref().join("index.html").display());
+        }
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-creating-gallery", &[]), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn prerender(colors_file: impl AsRef<Path>, opts: alco::Options) {
+    match alco::prerender(colors_file, &opts) {
+        Ok(colorschemes) => {
+            for (colorscheme, results) in colorschemes {
+                for (target, result) in results {
+                    if let Err(e) = result {
+                        println!(
+                            "{}\n{}",
+                            alco::tr(
+                                "error-prerendering",
+                                &[("target", &target.to_string()), ("colorscheme", &colorscheme)]
+                            ),
+                            e
+                        );
+                    }
+                }
+            }
+        }
+        Err(e) => println!("{}\n{}", alco::tr("error-prerendering-all", &[]), e),
+    }
+}
+
+fn ping(target: alco::Target, opts: alco::Options) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .expect("tokio runtime failed to start");
+
+    match runtime.block_on(alco::ping(&opts, target)) {
+        Ok(latency) => println!("{} responded in {:.2?}", target, latency),
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-pinging", &[("target", &target.to_string())]), e);
+            exit(1);
+        }
+    }
+}
+
+fn list(dir: impl AsRef<Path>, scheme_dir: impl AsRef<Path>) {
+    match alco::list_enabled(dir.as_ref(), scheme_dir) {
+        Ok(files) => {
+            for f in files {
+                println!("{}", f);
+            }
+        }
+        Err(_) => {
+            println!(
+                "{}",
+                alco::tr("error-listing-files", &[("dir", &dir.as_ref().display().to_string())])
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+fn list_long(
+    colors_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+) {
+    let entries = match alco::list_entries(colors_file, config_file, scheme_dir) {
+        Ok(e) => e,
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-listing-colorschemes", &[]), e);
+            exit(1);
+        }
+    };
+
+    let now = SystemTime::now();
+    let mut rows = vec![["NAME", "VARIANT", "TAGS", "APPLIED", "PATH"].map(str::to_owned)];
+    for e in entries.into_iter().filter(|e| !e.disabled) {
+        let applied = match e.applied {
+            Some(t) => {
+                let seconds = now.duration_since(t).unwrap_or_default().as_secs();
+                format!("{} ago", humantime::format_duration(Duration::from_secs(seconds)))
+            }
+            None => "never".to_owned(),
+        };
+        rows.push([
+            e.name,
+            e.variant.unwrap_or_else(|| "-".to_owned()),
+            if e.tags.is_empty() { "-".to_owned() } else { e.tags.join(",") },
+            applied,
+            e.path.map_or_else(|| "-".to_owned(), |p| p.display().to_string()),
+        ]);
+    }
+
+    let mut widths = [0; 5];
+    for row in rows.iter() {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+    for row in rows {
+        let line: Vec<_> = row.iter().zip(widths).map(|(c, w)| format!("{:<w$}", c)).collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
+
+fn suggest(
+    colors_file: impl AsRef<Path>,
+    image: impl AsRef<Path>,
+    count: usize,
+    opts: alco::Options,
+) {
+    match alco::suggest_colorschemes(&opts, colors_file, image) {
+        Ok(ranked) => {
+            for (colorscheme, score) in ranked.iter().take(count) {
+                println!("{} ({:.1})", colorscheme, score);
+            }
+        }
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-suggesting", &[]), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn find(
+    colors_file: impl AsRef<Path>,
+    color: alco::Color,
+    tolerance: f32,
+    background: bool,
+    opts: alco::Options,
+) {
+    match alco::find_colorschemes(&opts, colors_file, color, tolerance, background) {
+        Ok(found) => {
+            for f in found {
+                println!("{}: {} {} ({:.1})", f.colorscheme, f.name, f.color, f.distance);
+            }
+        }
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-searching", &[]), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn migrate_schemes(scheme_dir: impl AsRef<Path>) {
+    let results = match alco::migrate_schemes(scheme_dir) {
+        Ok(r) => r,
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-migrating-all", &[]), e);
+            exit(1);
+        }
+    };
+
+    for (colorscheme, result) in results {
+        match result {
+            Ok(unmapped) => {
+                println!("Migrated {}", colorscheme);
+                for key in unmapped {
+                    println!("  Couldn't map {}", key);
+                }
+            }
+            Err(e) => {
+                println!("{}\n{}", alco::tr("error-migrating", &[("colorscheme", &colorscheme)]), e)
+            }
+        }
+    }
+}
+
+/// Prints the converted palette, errors go to stderr so they don't end up in the output.
+fn convert(from: alco::PaletteFormat, to: alco::PaletteFormat) {
+    let mut input = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut input) {
+        eprintln!("{}\n{}", alco::tr("error-reading-palette", &[]), e);
+        exit(1);
+    }
+
+    match alco::convert(&input, from, to) {
+        Ok(out) => print!("{}", out),
+        Err(e) => {
+            eprintln!(
+                "{}\n{}",
+                alco::tr(
+                    "error-converting-palette",
+                    &[("from", &from.to_string()), ("to", &to.to_string())]
+                ),
+                e
+            );
+            exit(1);
+        }
+    }
+}
+
+fn import_alacritty_theme(
+    colors_file: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    source: &str,
+    name: Option<&String>,
+) {
+    match alco::import_alacritty_theme(source, scheme_dir, name.map(String::as_str)) {
+        Ok((name, path)) => match alco::add(colors_file, name.clone()) {
+            Ok(_) => println!("Imported {} to {}", name, path.display()),
+            Err(e) => println!(
+                "{}\n{}",
+                alco::tr("error-adding-colorscheme", &[("colorscheme", &name)]),
+                e
+            ),
+        },
+        Err(e) => println!("{}\n{}", alco::tr("error-importing-theme", &[]), e),
+    }
+}
+
+/// The options of the settings file, with the targets, paths and profile passed as flags applied.
+fn options(
+    app_m: &ArgMatches,
+    settings: &alco::Config,
+    config_file: impl AsRef<Path>,
+) -> anyhow::Result<alco::Options> {
+    let reload_all = app_m.get_flag("reload all");
+
+    // the settings file overrides the defaults, flags override the settings file
+    let mut opts = settings.options();
+    opts.notify = true;
+    opts.filters = settings.active_filters(&config_file);
+    opts.opacity = alco::opacity(&config_file);
+    opts.strategy = app_m.get_one::<String>("strategy").unwrap().parse().unwrap();
+    opts.safe |= app_m.get_flag("safe");
+    for t in alco::Target::ALL {
+        if reload_all || app_m.get_flag(&format!("reload {}", t.name())) {
+            opts.set_reload(t, true);
+        }
+    }
+
+    set_path(app_m, "scheme directory", &mut opts.scheme_dir);
+    set_path(app_m, "cache directory", &mut opts.cache_dir);
+    set_path(app_m, "script file", &mut opts.script_file);
+    set_path(app_m, "alacritty file", &mut opts.alacritty.file);
+    set_path(app_m, "alacritty in file", &mut opts.alacritty.in_file);
+    set_path(app_m, "alacritty selector", &mut opts.alacritty.selector);
+    if let Some(keys) = app_m.get_many::<String>("only colors") {
+        opts.alacritty.only_colors = keys.cloned().collect();
+    }
+    set_path(app_m, "kitty file", &mut opts.kitty.file);
+    set_path(app_m, "kitty socket", &mut opts.kitty.socket);
+    set_path(app_m, "kitty selector", &mut opts.kitty.selector);
+    set_path(app_m, "tmux file", &mut opts.tmux.file);
+    set_path(app_m, "tmux selector", &mut opts.tmux.selector);
+    if app_m.value_source("neovim command") == Some(ValueSource::CommandLine) {
+        opts.neovim.command = app_m.get_one::<String>("neovim command").unwrap().to_owned();
+    }
+    set_path(app_m, "starship file", &mut opts.starship.file);
+    set_path(app_m, "starship in file", &mut opts.starship.in_file);
+    set_path(app_m, "starship selector", &mut opts.starship.selector);
+    opts.starship.module = app_m.get_flag("starship module");
+    set_path(app_m, "bat file", &mut opts.bat.file);
+    set_path(app_m, "bat in file", &mut opts.bat.in_file);
+    set_path(app_m, "bat selector", &mut opts.bat.selector);
+    set_path(app_m, "delta file", &mut opts.delta.file);
+    set_path(app_m, "delta selector", &mut opts.delta.selector);
+    set_path(app_m, "cmus selector", &mut opts.cmus.selector);
+    set_path(app_m, "wezterm file", &mut opts.wezterm.file);
+    set_path(app_m, "wezterm selector", &mut opts.wezterm.selector);
+    set_path(app_m, "wezterm config", &mut opts.wezterm.config);
+    set_path(app_m, "foot file", &mut opts.foot.file);
+    set_path(app_m, "foot selector", &mut opts.foot.selector);
+    set_path(app_m, "zellij file", &mut opts.zellij.file);
+    set_path(app_m, "zellij selector", &mut opts.zellij.selector);
+    set_path(app_m, "zellij config", &mut opts.zellij.config);
+    set_path(app_m, "fish file", &mut opts.fish.file);
+    set_path(app_m, "fish selector", &mut opts.fish.selector);
+    set_path(app_m, "fzf file", &mut opts.fzf.file);
+    set_path(app_m, "rofi file", &mut opts.rofi.file);
+    set_path(app_m, "rofi selector", &mut opts.rofi.selector);
+    set_path(app_m, "dunst file", &mut opts.dunst.file);
+    set_path(app_m, "waybar file", &mut opts.waybar.file);
+    set_path(app_m, "polybar file", &mut opts.polybar.file);
+    set_path(app_m, "polybar selector", &mut opts.polybar.selector);
+    set_path(app_m, "picom file", &mut opts.picom.file);
+    set_path(app_m, "i3 file", &mut opts.i3.file);
+    set_path(app_m, "i3 selector", &mut opts.i3.selector);
+    set_path(app_m, "hyprland file", &mut opts.hyprland.file);
+    set_path(app_m, "gtk selector", &mut opts.gtk.selector);
+    set_path(app_m, "qutebrowser file", &mut opts.qutebrowser.file);
+    set_path(app_m, "qutebrowser selector", &mut opts.qutebrowser.selector);
+    set_path(app_m, "vscode file", &mut opts.vscode.file);
+    set_path(app_m, "vscode selector", &mut opts.vscode.selector);
+    set_path(app_m, "zathura file", &mut opts.zathura.file);
+    set_path(app_m, "mako file", &mut opts.mako.file);
+    set_path(app_m, "mako selector", &mut opts.mako.selector);
+    set_path(app_m, "ghostty file", &mut opts.ghostty.file);
+    set_path(app_m, "ghostty selector", &mut opts.ghostty.selector);
+    set_path(app_m, "konsole selector", &mut opts.konsole.selector);
+    set_path(app_m, "emacs selector", &mut opts.emacs.selector);
+    set_path(app_m, "vim selector", &mut opts.vim.selector);
+    set_path(app_m, "xresources file", &mut opts.xresources.file);
+
+    if let Some(name) = app_m.get_one::<String>("profile") {
+        let profile = settings.profile(name)?;
+
+        for t in profile.targets.iter() {
+            opts.set_reload(*t, true);
+        }
+        if let Some(s) = profile.strategy {
+            if app_m.value_source("strategy") != Some(ValueSource::CommandLine) {
+                opts.strategy = s;
+            }
+        }
+        if profile.max_parallel.is_some() {
+            opts.max_parallel = profile.max_parallel;
+        }
+        opts.font = profile.font.clone();
+    }
+    if let Some(only) = app_m.get_many::<String>("only") {
+        let only: Vec<&String> = only.collect();
+        for t in alco::Target::ALL {
+            opts.set_reload(t, only.iter().any(|o| *o == t.name()));
+        }
+    }
+
+    Ok(opts)
+}
+
+/// Completes the colorschemes of the colorscheme list, which is read when completing.
+fn colorscheme_candidates() -> ArgValueCandidates {
+    ArgValueCandidates::new(|| {
+        let colors_file = completion_path("colorscheme file", alco::DEFAULT_COLORSCHEME_FILE);
+        let colorschemes = alco::list(colors_file).unwrap_or_default();
+        colorschemes.into_iter().map(CompletionCandidate::new).collect()
+    })
+}
+
+/// Completes the profiles of the settings file, which is read when completing.
+fn profile_candidates() -> ArgValueCandidates {
+    ArgValueCandidates::new(|| {
+        match alco::Config::load(completion_path("settings file", alco::DEFAULT_SETTINGS_FILE)) {
+            Ok(s) => s.profiles.into_keys().map(CompletionCandidate::new).collect(),
+            Err(_) => Vec::new(),
+        }
+    })
+}
+
+/// The path passed as the argument `id` in the command line being completed, or `default`. The
+/// shell passes the words of the command line after a `--`.
+fn completion_path(id: &str, default: &str) -> String {
+    let words = std::env::args_os().skip_while(|a| a != "--").skip(1);
+    let app_m = app().ignore_errors(true).try_get_matches_from(words).ok();
+    let path = app_m.as_ref().and_then(|m| m.get_one::<String>(id)).map_or(default, |p| p);
+    tilde(path).into_owned()
+}
+
+/// Replaces `path` with the value of the argument `id`, if it was passed on the command line.
+fn set_path(app_m: &ArgMatches, id: &str, path: &mut PathBuf) {
+    if app_m.value_source(id) == Some(ValueSource::CommandLine) {
+        *path = PathBuf::from(tilde(app_m.get_one::<String>(id).unwrap()).as_ref());
+    }
+}
+
+fn update_scheme(scheme_dir: impl AsRef<Path>, colorscheme: &str) {
+    match alco::update_scheme(scheme_dir, colorscheme) {
+        Ok(source) => println!("Updated {} from {}", colorscheme, source.url),
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-updating", &[("colorscheme", colorscheme)]), e);
+            exit(1);
+        }
+    }
+}
+
+fn fetch(scheme_dir: impl AsRef<Path>, colorschemes: Vec<String>, update_all: bool, yes: bool) {
+    let colorschemes = match update_all {
+        true => match alco::imported_schemes(&scheme_dir) {
+            Ok(c) => c,
+            Err(e) => {
+                println!("{}\n{}", alco::tr("error-listing-imported", &[]), e);
+                exit(1);
+            }
+        },
+        false if colorschemes.is_empty() => {
+            println!("Pass colorschemes to fetch or --update-all");
+            exit(1);
+        }
+        false => colorschemes,
+    };
+
+    for colorscheme in colorschemes {
+        let update = match alco::fetch_update(&scheme_dir, &colorscheme) {
+            Ok(u) if !u.changed => {
+                println!("{} is up to date", colorscheme);
+                continue;
+            }
+            Ok(u) => u,
+            Err(e) => {
+                println!("{}\n{}", alco::tr("error-fetching", &[("colorscheme", &colorscheme)]), e);
+                continue;
+            }
+        };
+
+        println!("{} ({}):\n{}", colorscheme, update.source.url, update.diff());
+        if !yes && !confirm(&format!("Update {}?", colorscheme)) {
+            continue;
+        }
+        match update.write() {
+            Ok(_) => println!("Updated {}", colorscheme),
+            Err(e) => {
+                println!("{}\n{}", alco::tr("error-updating", &[("colorscheme", &colorscheme)]), e)
+            }
+        }
+    }
+}
+
+/// Asks a yes or no question on stdin, anything but `y` or `yes` is a no.
+fn confirm(question: &str) -> bool {
+    print!("{} [y/N] ", question);
+    io::stdout().flush().ok();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+fn resolve(colorscheme: &str, opts: alco::Options) {
+    for (target, resolution) in alco::resolve(&opts, colorscheme) {
+        match resolution {
+            Ok(r) => println!("{}: {}", target, r),
+            Err(e) => println!("{}: {}", target, e),
+        }
+    }
+}
+
+/// Walks through choosing the targets that are reloaded by default, importing the starter
+/// colorschemes and generating the selectors of the chosen targets.
+fn setup(colors_file: impl AsRef<Path>, settings_file: impl AsRef<Path>, opts: alco::Options) {
+    let detected = alco::detect_targets();
+    let detected: Vec<_> = detected.iter().map(|t| t.name()).collect();
+    println!("Detected targets: {}", detected.join(" "));
+
+    let targets = loop {
+        print!("Targets to manage, separated by spaces [{}]: ", detected.join(" "));
+        io::stdout().flush().ok();
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).is_err() {
+            return;
+        }
+        let names = match answer.trim() {
+            "" => detected.clone(),
+            a => a.split_whitespace().collect(),
+        };
+        match names.iter().map(|n| n.parse()).collect::<anyhow::Result<Vec<alco::Target>>>() {
+            Ok(t) => break t,
+            Err(e) => println!("{}", e),
+        }
+    };
+
+    if confirm("Import starter colorschemes from the alacritty-theme repository?") {
+        for (name, result) in alco::import_starter_schemes(&colors_file, &opts.scheme_dir) {
+            match result {
+                Ok(_) => println!("Imported {}", name),
+                Err(e) => {
+                    println!("{}\n{}", alco::tr("error-importing", &[("colorscheme", &name)]), e)
+                }
+            }
+        }
+    }
+
+    match alco::write_reload_targets(&settings_file, &targets) {
+        Ok(_) => println!("Wrote the targets to {}", settings_file.as_ref().display()),
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-writing-settings", &[]), e);
+            return;
+        }
+    }
+
+    let colorschemes = alco::list(&colors_file).unwrap_or_default();
+    for target in targets.into_iter().filter(|t| opts.selector(*t).is_some()) {
+        let entries: Vec<_> = match alco::generate_selector(&opts, target, &colorschemes) {
+            Ok(p) => p.into_iter().filter_map(|p| Some((p.colorscheme, p.theme?.0))).collect(),
+            Err(e) => {
+                println!(
+                    "{}\n{}",
+                    alco::tr("error-generating-selector", &[("target", &target.to_string())]),
+                    e
+                );
+                continue;
+            }
+        };
+        match alco::add_selector_entries(&opts, target, &entries) {
+            Ok(0) => (),
+            Ok(n) => println!("Added {} entries to the {} selector", n, target),
+            Err(e) => println!(
+                "{}\n{}",
+                alco::tr("error-writing-selector", &[("target", &target.to_string())]),
+                e
+            ),
+        }
+    }
+    println!("Apply a colorscheme using `alco apply <colorscheme>`");
+}
+
+fn show(colorscheme: &str, meta: bool, roles: bool, opts: alco::Options) {
+    if roles {
+        let colors =
+            alco::load_colorscheme(&opts, colorscheme).map(|c| c.map(|c| opts.map_color(c)));
+        match colors.and_then(|c| alco::color_roles(&c, &opts.roles)) {
+            Ok(roles) => {
+                for (name, color) in roles.iter() {
+                    println!("{}: {}", name, color);
+                }
+            }
+            Err(e) => {
+                println!(
+                    "{}\n{}",
+                    alco::tr("error-loading-roles", &[("colorscheme", colorscheme)]),
+                    e
+                );
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    if !meta {
+        match alco::load_colorscheme(&opts, colorscheme) {
+            Ok(colors) => {
+                for (name, color) in colors.colors() {
+                    println!("{}: {}", name, color);
+                }
+            }
+            Err(e) => {
+                println!("{}\n{}", alco::tr("error-loading", &[("colorscheme", colorscheme)]), e);
+                exit(1);
+            }
+        }
+        return;
+    }
+
+    let scheme_file = opts.scheme_dir.join(format!("{}.yml", colorscheme));
+    let meta = match alco::SchemeFile::load(&scheme_file) {
+        Ok(s) => s.meta,
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-loading", &[("colorscheme", colorscheme)]), e);
+            exit(1);
+        }
+    };
+    let fields = [("name", &meta.name), ("author", &meta.author), ("variant", &meta.variant)];
+    for (key, value) in fields {
+        if let Some(v) = value {
+            println!("{}: {}", key, v);
+        }
+    }
+    if let Some(source) = &meta.source {
+        match &source.date {
+            Some(d) => println!("source: {} {} (imported {})", source.format, source.url, d),
+            None => println!("source: {} {}", source.format, source.url),
+        }
+    }
+}
+
+fn install_pack(
+    colors_file: impl AsRef<Path>,
+    source: &str,
+    pack_dir: impl AsRef<Path>,
+    opts: alco::Options,
+) {
+    match alco::install_pack(source, colors_file, pack_dir, &opts) {
+        Ok(p) => {
+            for s in p.skipped {
+                println!("Kept existing {}", s);
+            }
+            println!("Installed pack {} with {}", p.name, p.colorschemes.join(", "));
+        }
+        Err(e) => println!("{}\n{}", alco::tr("error-installing-pack", &[]), e),
+    }
+}
+
+fn edit_selector(colors_file: impl AsRef<Path>, target: alco::Target, opts: alco::Options) {
+    let colorschemes = match alco::list(colors_file) {
+        Ok(c) => c,
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-listing-colorschemes", &[]), e);
+            exit(1);
+        }
+    };
+    match alco::edit_selector(&opts, target, colorschemes) {
+        Ok(0) => (),
+        Ok(n) => println!("Wrote {} {} selector entries", n, target),
+        Err(e) => {
+            println!(
+                "{}\n{}",
+                alco::tr("error-editing-selector", &[("target", &target.to_string())]),
+                e
+            );
+            exit(1);
+        }
+    }
+}
+
+fn generate_selector(
+    colors_file: impl AsRef<Path>,
+    target: alco::Target,
+    write: bool,
+    opts: alco::Options,
+) {
+    let colorschemes = match alco::list(colors_file) {
+        Ok(c) => c,
+        Err(e) => {
+            println!("{}\n{}", alco::tr("error-listing-colorschemes", &[]), e);
+            exit(1);
+        }
+    };
+    let proposals = match alco::generate_selector(&opts, target, &colorschemes) {
+        Ok(p) => p,
+        Err(e) => {
+            println!(
+                "{}\n{}",
+                alco::tr("error-looking-for-themes", &[("target", &target.to_string())]),
+                e
+            );
+            exit(1);
+        }
+    };
+
+    let quote = |s: &str| match serde_yaml::to_string(s) {
+        Ok(q) => q.trim_end().to_owned(),
+        Err(_) => s.to_owned(),
+    };
+    for p in proposals.iter() {
+        match &p.theme {
+            Some((theme, score)) => {
+                println!("{}: {} # {:.0}%", quote(&p.colorscheme), quote(theme), score * 100.0)
+            }
+            None => println!("# {}: no match", p.colorscheme),
+        }
+    }
+
+    if write {
+        let entries: Vec<(String, String)> =
+            proposals.into_iter().filter_map(|p| Some((p.colorscheme, p.theme?.0))).collect();
+        match alco::add_selector_entries(&opts, target, &entries) {
+            Ok(n) => println!("Added {} entries to the {} selector", n, target),
+            Err(e) => {
+                println!(
+                    "{}\n{}",
+                    alco::tr("error-writing-selector", &[("target", &target.to_string())]),
+                    e
+                );
+                exit(1);
+            }
+        }
+    }
+}
+
+fn export_pack(
+    colors_file: impl AsRef<Path>,
+    colorschemes: &[String],
+    out_file: impl AsRef<Path>,
+    opts: alco::Options,
+) {
+    match alco::export_pack(colorschemes, colors_file, &out_file, &opts) {
+        Ok(_) => {
+            println!("Exported {} to {}", colorschemes.join(", "), out_file.as_ref().display())
+        }
+        Err(e) => println!("{}\n{}", alco::tr("error-exporting-pack", &[]), e),
+    }
+}
+
+fn previous(config_file: impl AsRef<Path>) {
+    match alco::status(config_file) {
+        Ok(s) => match s.previous {
+            Some(p) => println!("{}", p),
+            None => println!("No previous colorscheme"),
+        },
+        Err(e) => println!("{}\n{}", alco::tr("error-getting-previous", &[]), e),
+    }
+}
+
+fn status(config_file: impl AsRef<Path>, time: Option<(&str, &str)>) {
+    match alco::status(config_file) {
+        Ok(s) => {
+            match time {
+                Some((format, precision)) => {
+                    println!("{} changed {}", s.current, format_time(&s, format, precision))
+                }
+                None => println!("{}", s.current),
+            }
+
+            if !s.failed.is_empty() {
+                let failed: Vec<_> = s.failed.iter().map(|t| t.name()).collect();
+                eprintln!(
+                    "{}",
+                    alco::tr("warning-failed-targets", &[("targets", &failed.join(", "))])
+                );
+            }
+        }
+        Err(e) => println!("{}\n{}", alco::tr("error-getting-current", &[]), e),
+    }
+}
+
+/// Formats the time of the last change as `relative`, `rfc3339` or `unix` time, truncated to
+/// `seconds`, `millis`, `micros` or `nanos`.
+fn format_time(status: &alco::Status, format: &str, precision: &str) -> String {
+    let truncate = |d: Duration| match precision {
+        "millis" => Duration::from_millis(d.as_millis() as u64),
+        "micros" => Duration::from_micros(d.as_micros() as u64),
+        "nanos" => d,
+        _ => Duration::from_secs(d.as_secs()),
+    };
+
+    let time = match format {
+        "rfc3339" => match precision {
+            "millis" => humantime::format_rfc3339_millis(status.changed).to_string(),
+            "micros" => humantime::format_rfc3339_micros(status.changed).to_string(),
+            "nanos" => humantime::format_rfc3339_nanos(status.changed).to_string(),
+            _ => humantime::format_rfc3339_seconds(status.changed).to_string(),
+        },
+        "unix" => {
+            let since_epoch = status.changed.duration_since(UNIX_EPOCH).unwrap_or_default();
+            let secs = since_epoch.as_secs();
+            let nanos = since_epoch.subsec_nanos();
+            match precision {
+                "millis" => format!("{}.{:03}", secs, nanos / 1_000_000),
+                "micros" => format!("{}.{:06}", secs, nanos / 1_000),
+                "nanos" => format!("{}.{:09}", secs, nanos),
+                _ => secs.to_string(),
+            }
+        }
+        _ => return format!("{} ago", humantime::format_duration(truncate(status.duration))),
+    };
+    format!("at {}", time)
 }
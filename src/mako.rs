@@ -0,0 +1,144 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+pub fn reload_mako(
+    config_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let config = render_mako(&config_file, selector, &scheme_dir, colorscheme)?;
+    fs::write(config_file.as_ref(), config)?;
+    notify_mako()
+}
+
+/// Makes a running mako read its configuration again, nothing is done if mako isn't running.
+pub fn notify_mako() -> anyhow::Result<()> {
+    let output = Command::new("pgrep")
+        .args(["-x", "mako"])
+        .output()
+        .map_err(|e| anyhow!("Error running pgrep:\n{}", e))?;
+    // pgrep exits with 1 if no process matched
+    match output.status.code() {
+        Some(0) => (),
+        Some(1) => return Ok(()),
+        _ => bail!("Error looking for mako:\n{}", String::from_utf8_lossy(&output.stderr).trim()),
+    }
+
+    let output = Command::new("makoctl")
+        .arg("reload")
+        .output()
+        .map_err(|e| anyhow!("Error running makoctl:\n{}", e))?;
+    if !output.status.success() {
+        bail!("Error reloading mako:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Sets the options of the theme file mapped by the selector in the configuration file, like
+/// `background-color=#282828`. Options of a criteria section like `[urgency=high]` are set in the
+/// section of the same name. Existing options are replaced and missing ones are added to the end
+/// of their section, comments and all other options are kept.
+pub fn render_mako(
+    config_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading mako selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+    let theme_file = match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(t) => t,
+        None => bail!("Missing mapping in mako selector"),
+    };
+    let theme = fs::read_to_string(tilde(&theme_file).as_ref())
+        .map_err(|e| anyhow!("Error reading mako theme file {}:\n{}", theme_file, e))?;
+
+    let config = match fs::read_to_string(config_file.as_ref()) {
+        Ok(s) => s,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => bail!("Error reading mako config file:\n{}", e),
+    };
+    Ok(set_options(&config, &theme))
+}
+
+/// The options of a mako configuration grouped by section, starting with the global options
+/// under an empty section name.
+fn sections(config: &str) -> Vec<(&str, Vec<(&str, &str)>)> {
+    let mut sections = vec![("", Vec::new())];
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            sections.push((line, Vec::new()));
+        } else if let Some((key, _)) = line.split_once('=').filter(|_| !line.starts_with('#')) {
+            if let Some((_, options)) = sections.last_mut() {
+                options.push((key.trim(), line));
+            }
+        }
+    }
+    sections
+}
+
+/// Sets the options of `theme` in `config`.
+fn set_options(config: &str, theme: &str) -> String {
+    let mut missing = sections(theme);
+
+    let mut lines = Vec::new();
+    let mut section = "";
+    for line in config.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            push_missing(&mut lines, &mut missing, section);
+            section = trimmed;
+            lines.push(line.to_owned());
+            continue;
+        }
+        let key = trimmed.split_once('=').filter(|_| !trimmed.starts_with('#')).map(|(k, _)| k);
+        match key.and_then(|k| take(&mut missing, section, k.trim())) {
+            Some(option) => lines.push(option.to_owned()),
+            None => lines.push(line.to_owned()),
+        }
+    }
+    push_missing(&mut lines, &mut missing, section);
+
+    // sections the configuration doesn't have yet are added at the end
+    for (section, options) in missing.iter().filter(|(_, o)| !o.is_empty()) {
+        if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+            lines.push(String::new());
+        }
+        lines.push(section.to_string());
+        lines.extend(options.iter().map(|(_, o)| o.to_string()));
+    }
+
+    let mut config = lines.join("\n");
+    config.push('\n');
+    config
+}
+
+/// Removes an option of the theme so it's only set once.
+fn take<'a>(
+    missing: &mut [(&str, Vec<(&str, &'a str)>)],
+    section: &str,
+    key: &str,
+) -> Option<&'a str> {
+    let (_, options) = missing.iter_mut().find(|(s, _)| *s == section)?;
+    let i = options.iter().position(|(k, _)| *k == key)?;
+    Some(options.remove(i).1)
+}
+
+/// Adds the options of a section that weren't replaced before its trailing empty lines.
+fn push_missing(lines: &mut Vec<String>, missing: &mut [(&str, Vec<(&str, &str)>)], section: &str) {
+    let options = match missing.iter_mut().find(|(s, _)| *s == section) {
+        Some((_, o)) => std::mem::take(o),
+        None => return,
+    };
+    let end = lines.iter().rposition(|l| !l.trim().is_empty()).map_or(0, |i| i + 1);
+    let added = options.into_iter().map(|(_, o)| o.to_owned());
+    lines.splice(end..end, added);
+}
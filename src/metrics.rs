@@ -0,0 +1,129 @@
+use anyhow::anyhow;
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::Target;
+
+static METRICS: Mutex<Metrics> = Mutex::new(Metrics::new());
+
+/// Counters of the colorschemes applied by this process.
+struct Metrics {
+    applies: u64,
+    apply_seconds: f64,
+    targets: BTreeMap<Target, TargetMetrics>,
+}
+
+#[derive(Default)]
+struct TargetMetrics {
+    reloads: u64,
+    failures: u64,
+    seconds: f64,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            applies: 0,
+            apply_seconds: 0.0,
+            targets: BTreeMap::new(),
+        }
+    }
+
+    /// Renders the metrics in the prometheus text format.
+    fn render(&self) -> String {
+        let mut text = String::new();
+        let t = &mut text;
+        header(t, "alco_applies_total", "counter", "Colorschemes applied to the targets.");
+        writeln!(t, "alco_applies_total {}", self.applies).ok();
+
+        header(t, "alco_apply_duration_seconds", "summary", "Time it took to reload all targets.");
+        writeln!(t, "alco_apply_duration_seconds_sum {}", self.apply_seconds).ok();
+        writeln!(t, "alco_apply_duration_seconds_count {}", self.applies).ok();
+
+        header(t, "alco_target_reloads_total", "counter", "Reloads of a target.");
+        for (target, m) in self.targets.iter() {
+            writeln!(t, "alco_target_reloads_total{{target=\"{}\"}} {}", target, m.reloads).ok();
+        }
+
+        header(t, "alco_target_failures_total", "counter", "Reloads of a target that failed.");
+        for (target, m) in self.targets.iter() {
+            writeln!(t, "alco_target_failures_total{{target=\"{}\"}} {}", target, m.failures).ok();
+        }
+
+        let name = "alco_target_reload_duration_seconds";
+        header(t, name, "summary", "Time it took to reload a target.");
+        for (target, m) in self.targets.iter() {
+            writeln!(t, "{}_sum{{target=\"{}\"}} {}", name, target, m.seconds).ok();
+            writeln!(t, "{}_count{{target=\"{}\"}} {}", name, target, m.reloads).ok();
+        }
+        text
+    }
+}
+
+fn header(text: &mut String, name: &str, kind: &str, help: &str) {
+    writeln!(text, "# HELP {} {}\n# TYPE {} {}", name, help, name, kind).ok();
+}
+
+/// Records that all targets were reloaded, which took `duration`.
+pub(crate) fn record_apply(duration: Duration) {
+    if let Ok(mut m) = METRICS.lock() {
+        m.applies += 1;
+        m.apply_seconds += duration.as_secs_f64();
+    }
+}
+
+/// Records the reload of a single target.
+pub(crate) fn record_reload(target: Target, failed: bool, duration: Duration) {
+    if let Ok(mut m) = METRICS.lock() {
+        let t = m.targets.entry(target).or_default();
+        t.reloads += 1;
+        t.failures += failed as u64;
+        t.seconds += duration.as_secs_f64();
+    }
+}
+
+/// Serves the metrics of this process at `http://<address>/metrics`.
+pub fn serve_metrics(address: &str) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(address)
+        .map_err(|e| anyhow!("Error listening on {}:\n{}", address, e))?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            thread::spawn(move || handle_client(stream));
+        }
+    });
+
+    Ok(())
+}
+
+fn handle_client(mut stream: TcpStream) {
+    stream.set_read_timeout(Some(Duration::from_secs(5))).ok();
+    let mut line = String::new();
+    if BufReader::new(&stream).read_line(&mut line).is_err() {
+        return;
+    }
+
+    let response = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["GET", "/metrics", ..] => {
+            let body = METRICS.lock().map(|m| m.render()).unwrap_or_default();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        }
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned(),
+    };
+    stream.write_all(response.as_bytes()).ok();
+}
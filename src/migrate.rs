@@ -0,0 +1,303 @@
+use anyhow::{anyhow, bail};
+use yaml_rust::{Yaml, YamlLoader};
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::colorscheme::COLOR_NAMES;
+use crate::{Color, Colorscheme, SCHEME_VERSION};
+
+/// Upgrades all version 1 scheme files in the scheme directory to the latest format. Returns the
+/// migrated colorschemes with the keys of their `colors` section that couldn't be mapped to the
+/// new format, and are therefore dropped.
+pub fn migrate_schemes(
+    scheme_dir: impl AsRef<Path>,
+) -> anyhow::Result<Vec<(String, anyhow::Result<Vec<String>>)>> {
+    let entries =
+        fs::read_dir(scheme_dir.as_ref()).map_err(|_| anyhow!("Error reading scheme directory"))?;
+
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| Some(e.ok()?.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "yml"))
+        .collect();
+    paths.sort();
+
+    let mut results = Vec::new();
+    for path in paths {
+        let name = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(n) => n.to_owned(),
+            None => continue,
+        };
+        let result = match migrate_scheme(&path, &name) {
+            Ok(Some(unmapped)) => Ok(unmapped),
+            Ok(None) => continue,
+            Err(e) => Err(e),
+        };
+        results.push((name, result));
+    }
+
+    Ok(results)
+}
+
+/// Rewrites a version 1 scheme file, returns `None` if it already is up to date.
+fn migrate_scheme(path: &Path, name: &str) -> anyhow::Result<Option<Vec<String>>> {
+    let scheme_str =
+        fs::read_to_string(path).map_err(|_| anyhow!("Error reading colorscheme file"))?;
+    let yaml = YamlLoader::load_from_str(&scheme_str)?.into_iter().next().unwrap_or(Yaml::Null);
+    if !yaml["version"].is_badvalue() {
+        return Ok(None);
+    }
+
+    let colors = &yaml["colors"];
+    let mut unmapped = Vec::new();
+    if let Yaml::Hash(h) = colors {
+        for (k, v) in h.iter() {
+            unmapped_keys(v, k.as_str().unwrap_or_default(), &mut unmapped);
+        }
+    }
+
+    let lines: Vec<_> = scheme_str.lines().collect();
+    let top_level = |l: &&str| !l.is_empty() && !l.starts_with([' ', '\t', '#']);
+    let start = lines.iter().position(|l| top_level(l) && key(l) == Some("colors"));
+    let (start, end) = match start {
+        Some(s) => {
+            let len = lines[s + 1..].iter().position(top_level).unwrap_or(lines.len() - s - 1);
+            // keep the empty lines separating the next section
+            let empty = lines[s + 1..s + 1 + len].iter().rev().take_while(|l| l.trim().is_empty());
+            (s, s + 1 + len - empty.count())
+        }
+        None if colors.is_badvalue() => (0, 0),
+        None => bail!("Unsupported layout of the colors section"),
+    };
+    let comments = comments(&lines[start..end]);
+
+    // comments and the document start marker at the start of the file stay there
+    let header = lines[..start]
+        .iter()
+        .take_while(|l| l.trim().is_empty() || l.trim_start().starts_with('#') || l.trim() == "---")
+        .count();
+
+    let mut out = String::new();
+    for l in lines[..header].iter() {
+        out.push_str(l);
+        out.push('\n');
+    }
+    // imported files already contain a meta section
+    match yaml["meta"].is_badvalue() {
+        true => out.push_str(&format!("version: {}\nmeta:\n  name: {}\n", SCHEME_VERSION, name)),
+        false => out.push_str(&format!("version: {}\n", SCHEME_VERSION)),
+    }
+    for l in lines[header..start].iter() {
+        out.push_str(l);
+        out.push('\n');
+    }
+    out.push_str(&v2_colors(&Colorscheme::from_yaml(colors), &comments));
+    for l in lines[end..].iter() {
+        out.push_str(l);
+        out.push('\n');
+    }
+
+    fs::write(path, out)?;
+    Ok(Some(unmapped))
+}
+
+/// The version 2 path of a version 1 color, relative to the `colors` section.
+fn v2_path(v1_path: &str) -> Option<String> {
+    let path = match v1_path {
+        "primary.foreground" => "foreground".to_owned(),
+        "primary.background" => "background".to_owned(),
+        "cursor.cursor" => "cursor".to_owned(),
+        _ => {
+            let (section, name) = v1_path.split_once('.')?;
+            if !matches!(section, "normal" | "bright") || !COLOR_NAMES.contains(&name) {
+                return None;
+            }
+            v1_path.to_owned()
+        }
+    };
+    Some(path)
+}
+
+fn unmapped_keys(yaml: &Yaml, path: &str, unmapped: &mut Vec<String>) {
+    match yaml {
+        Yaml::Hash(h) => {
+            for (k, v) in h.iter() {
+                let key = k.as_str().unwrap_or_default();
+                unmapped_keys(v, &format!("{}.{}", path, key), unmapped);
+            }
+        }
+        v => {
+            let valid = v.as_str().is_some_and(|s| s.parse::<Color>().is_ok());
+            if v2_path(path).is_none() || !valid {
+                unmapped.push(format!("colors.{}", path));
+            }
+        }
+    }
+}
+
+fn key(line: &str) -> Option<&str> {
+    let (key, _) = line.trim_start().split_once(':')?;
+    Some(key.trim().trim_matches(['"', '\'']))
+}
+
+/// Comments on their own lines before a color and the comment after it.
+type Comments = HashMap<String, (Vec<String>, Option<String>)>;
+
+/// Collects the comments of the colors section by the version 2 path of the color they belong to.
+fn comments(lines: &[&str]) -> Comments {
+    let mut comments = Comments::new();
+    let mut pending = Vec::new();
+    let mut stack: Vec<(usize, &str)> = Vec::new();
+
+    for line in lines.iter().skip(1) {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            pending.push(trimmed.to_owned());
+            continue;
+        }
+
+        let indent = line.len() - trimmed.len();
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+        let key = match key(line) {
+            Some(k) => k,
+            None => continue,
+        };
+
+        let path = stack.iter().map(|(_, k)| *k).chain([key]).collect::<Vec<_>>().join(".");
+        stack.push((indent, key));
+        let v2_path = match v2_path(&path) {
+            Some(p) => p,
+            None => continue,
+        };
+
+        let (before, after) = comments.entry(v2_path).or_default();
+        before.append(&mut pending);
+        *after = line.split_once(" #").map(|(_, c)| format!("#{}", c));
+    }
+
+    comments
+}
+
+/// The `colors` section of a version 2 scheme file, without any comments.
+pub(crate) fn render_v2_colors(scheme: &Colorscheme) -> String {
+    v2_colors(scheme, &Comments::new())
+}
+
+fn v2_colors(scheme: &Colorscheme, comments: &Comments) -> String {
+    let mut out = String::new();
+    push_color(&mut out, "foreground", scheme.foreground, comments);
+    push_color(&mut out, "background", scheme.background, comments);
+    push_color(&mut out, "cursor", scheme.cursor, comments);
+    for (section, colors) in [("normal", &scheme.normal), ("bright", &scheme.bright)] {
+        if colors.iter().all(Option::is_none) {
+            continue;
+        }
+        out.push_str(&format!("  {}:\n", section));
+        for (name, color) in COLOR_NAMES.iter().zip(colors.iter()) {
+            push_color(&mut out, &format!("{}.{}", section, name), *color, comments);
+        }
+    }
+
+    match out.is_empty() {
+        true => out,
+        false => format!("colors:\n{}", out),
+    }
+}
+
+fn push_color(out: &mut String, path: &str, color: Option<Color>, comments: &Comments) {
+    let color = match color {
+        Some(c) => c,
+        None => return,
+    };
+    let depth = path.matches('.').count() + 1;
+    let indent = "  ".repeat(depth);
+    let key = path.rsplit('.').next().unwrap_or(path);
+
+    let (before, after) = match comments.get(path) {
+        Some((b, a)) => (b.as_slice(), a.as_deref()),
+        None => (&[][..], None),
+    };
+    for c in before {
+        out.push_str(&format!("{}{}\n", indent, c));
+    }
+    match after {
+        Some(c) => out.push_str(&format!("{}{}: \"{}\" {}\n", indent, key, color, c)),
+        None => out.push_str(&format!("{}{}: \"{}\"\n", indent, key, color)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Migrates a scheme directory containing only the given scheme file and returns the results
+    /// and the contents of the file afterwards.
+    fn migrate(test: &str, scheme: &str) -> (Vec<(String, Vec<String>)>, String) {
+        let dir =
+            std::env::temp_dir().join(format!("alco-migrate-{}-{}", std::process::id(), test));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("nord.yml"), scheme).unwrap();
+
+        let results = migrate_schemes(&dir).unwrap();
+        let migrated = fs::read_to_string(dir.join("nord.yml")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let results = results.into_iter().map(|(n, r)| (n, r.unwrap())).collect();
+        (results, migrated)
+    }
+
+    #[test]
+    fn v1_with_comments() {
+        let v1 = concat!(
+            "# nord colors\n",
+            "colors:\n",
+            "  primary:\n",
+            "    # the default text color\n",
+            "    foreground: '#d8dee9'\n",
+            "    background: '#2e3440' # polar night\n",
+            "  normal:\n",
+            "    red: '#BF616A'\n",
+            "  dim:\n",
+            "    red: '#94545d'\n",
+            "\n",
+            "extra: true\n",
+        );
+        let v2 = concat!(
+            "# nord colors\n",
+            "version: 2\n",
+            "meta:\n",
+            "  name: nord\n",
+            "colors:\n",
+            "  # the default text color\n",
+            "  foreground: \"#d8dee9\"\n",
+            "  background: \"#2e3440\" # polar night\n",
+            "  normal:\n",
+            "    red: \"#bf616a\"\n",
+            "\n",
+            "extra: true\n",
+        );
+
+        let (results, migrated) = migrate("v1", v1);
+        assert_eq!(results, [("nord".to_owned(), vec!["colors.dim.red".to_owned()])]);
+        assert_eq!(migrated, v2);
+
+        // the migrated file is up to date
+        let (results, migrated) = migrate("v1-again", &migrated);
+        assert!(results.is_empty());
+        assert_eq!(migrated, v2);
+    }
+
+    #[test]
+    fn v2_unchanged() {
+        let v2 = "version: 2\n# nord colors\ncolors:\n  foreground: '#d8dee9'\n";
+        let (results, migrated) = migrate("v2", v2);
+        assert!(results.is_empty());
+        assert_eq!(migrated, v2);
+    }
+}
@@ -2,17 +2,10 @@ use nvim_rs::create::tokio::new_path as nvim_connect_unix_socket;
 use nvim_rs::rpc::handler::Dummy;
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub async fn reload_neovim(command: impl AsRef<str>) -> anyhow::Result<()> {
-    let instances: Vec<_> = fs::read_dir("/run/user/1000")?
-        .into_iter()
-        .filter_map(Result::ok)
-        .filter(|d| d.metadata().map(|m| !m.is_dir()).unwrap_or(false))
-        .filter(|d| d.file_name().to_str().map(|s| s.starts_with("nvim")).unwrap_or(false))
-        .map(|d| d.path())
-        .collect();
-
+    let instances = instances()?;
     if instances.is_empty() {
         return Ok(());
     }
@@ -22,6 +15,43 @@ pub async fn reload_neovim(command: impl AsRef<str>) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Runs `command` in the neovim instance listening on `socket` after setting
+/// `g:alco_colorscheme`, which the command can use to load a colorscheme other than the current
+/// one.
+pub async fn reload_neovim_instance(
+    socket: impl AsRef<Path>,
+    command: impl AsRef<str>,
+    colorscheme: &str,
+) -> anyhow::Result<()> {
+    let (nvim, _handle) = nvim_connect_unix_socket(socket.as_ref(), Dummy::new()).await?;
+    nvim.command(&format!("let g:alco_colorscheme = '{}'", colorscheme.replace('\'', "''")))
+        .await?;
+    nvim.exec_lua(command.as_ref(), Vec::new()).await?;
+    Ok(())
+}
+
+/// Requests the api info of all running neovim instances and returns the number of instances.
+pub async fn ping_neovim() -> anyhow::Result<usize> {
+    let instances = instances()?;
+    for p in instances.iter() {
+        let (nvim, _handle) = nvim_connect_unix_socket(p, Dummy::new()).await?;
+        nvim.get_api_info().await?;
+    }
+
+    Ok(instances.len())
+}
+
+fn instances() -> anyhow::Result<Vec<PathBuf>> {
+    let instances = fs::read_dir("/run/user/1000")?
+        .filter_map(Result::ok)
+        .filter(|d| d.metadata().map(|m| !m.is_dir()).unwrap_or(false))
+        .filter(|d| d.file_name().to_str().map(|s| s.starts_with("nvim")).unwrap_or(false))
+        .map(|d| d.path())
+        .collect();
+
+    Ok(instances)
+}
+
 async fn reload_instances(instances: Vec<PathBuf>, command: &str) -> anyhow::Result<()> {
     let tasks = instances
         .into_iter()
@@ -0,0 +1,97 @@
+use anyhow::anyhow;
+
+use std::env;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use crate::Colorscheme;
+
+/// Terminal multiplexers that can forward escape sequences to the terminal they are running in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Passthrough {
+    /// Requires `allow-passthrough` to be turned on.
+    Tmux,
+    Screen,
+}
+
+impl Passthrough {
+    /// Detects the terminal multiplexer alco is running in.
+    pub fn detect() -> Option<Self> {
+        if env::var_os("TMUX").is_some() {
+            Some(Self::Tmux)
+        } else if env::var_os("STY").is_some() {
+            Some(Self::Screen)
+        } else {
+            None
+        }
+    }
+
+    /// Wraps a single escape sequence so that it's forwarded to the outer terminal.
+    pub fn wrap(&self, seq: &str) -> String {
+        match self {
+            Self::Tmux => format!("\x1bPtmux;{}\x1b\\", seq.replace('\x1b', "\x1b\x1b")),
+            // the string terminator would end the passthrough sequence, so use bel instead
+            Self::Screen => format!("\x1bP{}\x1b\\", seq.replace("\x1b\\", "\x07")),
+        }
+    }
+}
+
+/// Whether alco is running on a remote host inside an ssh session.
+pub fn is_ssh_session() -> bool {
+    ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"].iter().any(|v| env::var_os(v).is_some())
+}
+
+/// Returns the escape sequences that set the palette, foreground, background and cursor colors
+/// of a terminal.
+pub fn osc_sequences(colorscheme: &Colorscheme) -> String {
+    sequences(colorscheme).concat()
+}
+
+fn sequences(colorscheme: &Colorscheme) -> Vec<String> {
+    let mut seqs = Vec::new();
+    for (i, c) in colorscheme.ansi().enumerate() {
+        if let Some(c) = c {
+            seqs.push(format!("\x1b]4;{};{}\x1b\\", i, c));
+        }
+    }
+    let special =
+        [(10, colorscheme.foreground), (11, colorscheme.background), (12, colorscheme.cursor)];
+    for (code, color) in special {
+        if let Some(c) = color {
+            seqs.push(format!("\x1b]{};{}\x1b\\", code, c));
+        }
+    }
+    seqs
+}
+
+/// Writes the escape sequences for `colorscheme` to a terminal device like `/dev/pts/3`, which
+/// recolors the terminal the device belongs to.
+pub fn write_osc_tty(tty: impl AsRef<Path>, colorscheme: &Colorscheme) -> anyhow::Result<()> {
+    let tty = tty.as_ref();
+    let mut file = OpenOptions::new()
+        .write(true)
+        .open(tty)
+        .map_err(|e| anyhow!("Error opening {}:\n{}", tty.display(), e))?;
+    file.write_all(osc_sequences(colorscheme).as_bytes())?;
+    Ok(())
+}
+
+/// Writes the escape sequences for `colorscheme` to the controlling terminal, each wrapped in a
+/// passthrough sequence if a terminal multiplexer is given.
+pub fn write_osc(
+    colorscheme: &Colorscheme,
+    passthrough: Option<Passthrough>,
+) -> anyhow::Result<()> {
+    let mut tty = OpenOptions::new()
+        .write(true)
+        .open("/dev/tty")
+        .map_err(|_| anyhow!("Error opening the controlling terminal"))?;
+    let seq = match passthrough {
+        Some(p) => sequences(colorscheme).iter().map(|s| p.wrap(s)).collect(),
+        None => osc_sequences(colorscheme),
+    };
+    tty.write_all(seq.as_bytes())?;
+    tty.flush()?;
+    Ok(())
+}
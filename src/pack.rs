@@ -0,0 +1,379 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::{Yaml, YamlLoader};
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use crate::{Options, Target};
+
+/// Archive extensions that are stripped to get the name of a pack.
+const ARCHIVE_EXTENSIONS: &[&str] = &[".tar.gz", ".tgz", ".tar.xz", ".tar.bz2", ".tar.zst", ".tar"];
+
+pub struct InstalledPack {
+    pub name: String,
+    pub colorschemes: Vec<String>,
+    /// Scheme files and selector entries that already existed and were kept.
+    pub skipped: Vec<String>,
+}
+
+/// Installs a pack from a directory, a tarball or the url of a tarball. A pack has the following
+/// layout, all parts are optional:
+/// - `schemes/<colorscheme>.yml`: colorscheme files that are copied into the scheme directory
+/// - `selectors/<target>.yml`: selector entries that are added to the selector file of a target
+/// - `files/`: files referenced by relative paths in the selector entries, which are copied to
+///   `<pack_dir>/<pack name>/`
+///
+/// Existing scheme files and selector entries are never overwritten.
+pub fn install_pack(
+    source: &str,
+    colors_file: impl AsRef<Path>,
+    pack_dir: impl AsRef<Path>,
+    opts: &Options,
+) -> anyhow::Result<InstalledPack> {
+    let tmp_dir = std::env::temp_dir().join(format!("alco-pack-{}", std::process::id()));
+    let result = fetch(source, &tmp_dir)
+        .and_then(|(name, root)| install(name, &root, colors_file, pack_dir, opts));
+    fs::remove_dir_all(&tmp_dir).ok();
+    result
+}
+
+/// Bundles the scheme files, selector entries and files referenced by those entries of the
+/// `colorschemes` into a tarball at `out_file`, which can be installed using [`install_pack`].
+pub fn export_pack(
+    colorschemes: &[String],
+    colors_file: impl AsRef<Path>,
+    out_file: impl AsRef<Path>,
+    opts: &Options,
+) -> anyhow::Result<()> {
+    let available = crate::list(colors_file)?;
+    if let Some(c) = colorschemes.iter().find(|c| !available.contains(c)) {
+        bail!("No matching colorscheme {}", c);
+    }
+
+    let out_file = out_file.as_ref();
+    let file_name = out_file.file_name().and_then(|f| f.to_str()).unwrap_or_default();
+    let name =
+        ARCHIVE_EXTENSIONS.iter().find_map(|e| file_name.strip_suffix(e)).unwrap_or(file_name);
+    if name.is_empty() {
+        bail!("Unable to derive a pack name from {}", out_file.display());
+    }
+
+    if let Some(parent) = out_file.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp_dir = std::env::temp_dir().join(format!("alco-pack-{}", std::process::id()));
+    let result = export(colorschemes, name, &tmp_dir, opts).and_then(|_| {
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(out_file)
+            .arg("-C")
+            .arg(&tmp_dir)
+            .arg(name)
+            .status()
+            .map_err(|_| anyhow!("Error running tar"))?;
+        if !status.success() {
+            bail!("Error creating pack archive");
+        }
+        Ok(())
+    });
+    fs::remove_dir_all(&tmp_dir).ok();
+    result
+}
+
+fn export(
+    colorschemes: &[String],
+    name: &str,
+    tmp_dir: &Path,
+    opts: &Options,
+) -> anyhow::Result<()> {
+    let root = tmp_dir.join(name);
+    let schemes_dir = root.join("schemes");
+    let selectors_dir = root.join("selectors");
+    let files_dir = root.join("files");
+    fs::create_dir_all(&root)?;
+
+    for c in colorschemes.iter() {
+        let scheme_file = crate::scheme_file(&opts.scheme_dir, c);
+        if scheme_file.exists() {
+            fs::create_dir_all(&schemes_dir)?;
+            fs::copy(scheme_file, schemes_dir.join(format!("{}.yml", c)))?;
+        }
+    }
+
+    for target in Target::ALL {
+        let selector = match opts.selector(target) {
+            Some(s) if s.exists() => s,
+            _ => continue,
+        };
+
+        // the files of the target, by their path relative to the files directory of the pack
+        let mut files: Vec<(String, PathBuf)> = Vec::new();
+        let mut selector_str = String::new();
+        for (k, v) in read_selector(selector)? {
+            if !colorschemes.contains(&k) {
+                continue;
+            }
+
+            let path = PathBuf::from(tilde(&v).as_ref());
+            let file_name = path.file_name().and_then(|f| f.to_str()).filter(|_| path.is_file());
+            let value = match file_name {
+                Some(f) => {
+                    let mut rel = format!("{}/{}", target, f);
+                    if files.iter().any(|(r, p)| r == &rel && p != &path) {
+                        rel = format!("{}/{}-{}", target, k, f);
+                    }
+                    if !files.iter().any(|(r, _)| r == &rel) {
+                        files.push((rel.clone(), path));
+                    }
+                    rel
+                }
+                None => v,
+            };
+            selector_str.push_str(&format!("{}: {}\n", quote(&k)?, quote(&value)?));
+        }
+
+        if selector_str.is_empty() {
+            continue;
+        }
+        fs::create_dir_all(&selectors_dir)?;
+        fs::write(selectors_dir.join(format!("{}.yml", target)), selector_str)?;
+
+        for (rel, path) in files {
+            let dest = files_dir.join(rel);
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(path, dest)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Downloads and extracts the pack if necessary, and returns its name and root directory.
+fn fetch(source: &str, tmp_dir: &Path) -> anyhow::Result<(String, PathBuf)> {
+    let file_name = source.trim_end_matches('/').rsplit('/').next().unwrap_or_default();
+    let name = ARCHIVE_EXTENSIONS
+        .iter()
+        .find_map(|e| file_name.strip_suffix(e))
+        .unwrap_or(file_name)
+        .to_owned();
+    if name.is_empty() {
+        bail!("Unable to derive a pack name from {}", source);
+    }
+    check_name("pack", &name)?;
+
+    let archive = if source.starts_with("http://") || source.starts_with("https://") {
+        fs::create_dir_all(tmp_dir)?;
+        let archive = tmp_dir.join(file_name);
+        let status = Command::new("curl")
+            .arg("-fsSL")
+            .arg("-o")
+            .arg(&archive)
+            .arg(source)
+            .status()
+            .map_err(|_| anyhow!("Error running curl"))?;
+        if !status.success() {
+            bail!("Error downloading pack");
+        }
+        archive
+    } else {
+        let path = PathBuf::from(source);
+        if path.is_dir() {
+            return Ok((name, path));
+        } else if !path.exists() {
+            bail!("Pack not found");
+        }
+        path
+    };
+
+    let root = tmp_dir.join("pack");
+    fs::create_dir_all(&root)?;
+    let status = Command::new("tar")
+        .arg("-xf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&root)
+        .status()
+        .map_err(|_| anyhow!("Error running tar"))?;
+    if !status.success() {
+        bail!("Error extracting pack");
+    }
+
+    // most tarballs contain a single top level directory
+    let entries: Vec<_> = fs::read_dir(&root)?.filter_map(Result::ok).map(|e| e.path()).collect();
+    match entries.as_slice() {
+        [dir] if dir.is_dir() && !is_pack_dir(&root) => Ok((name, dir.clone())),
+        _ => Ok((name, root)),
+    }
+}
+
+fn is_pack_dir(dir: &Path) -> bool {
+    ["schemes", "selectors", "files"].iter().any(|d| dir.join(d).is_dir())
+}
+
+fn install(
+    name: String,
+    root: &Path,
+    colors_file: impl AsRef<Path>,
+    pack_dir: impl AsRef<Path>,
+    opts: &Options,
+) -> anyhow::Result<InstalledPack> {
+    if !is_pack_dir(root) {
+        bail!("Not a pack, expected a schemes, selectors or files directory");
+    }
+
+    let schemes = yaml_files(&root.join("schemes"))?;
+    let mut selectors = Vec::new();
+    for (target, path) in yaml_files(&root.join("selectors"))? {
+        let target: Target = target.parse()?;
+        // an else entry would change the mapping of colorschemes that aren't part of the pack
+        let entries = read_selector(&path)?.into_iter().filter(|(k, _)| k != "else").collect();
+        selectors.push((target, entries));
+    }
+    check_names(&schemes, &selectors)?;
+
+    let mut colorschemes = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (colorscheme, path) in schemes {
+        let dest = crate::scheme_file(&opts.scheme_dir, &colorscheme);
+        if dest.exists() {
+            skipped.push(format!("scheme file {}", colorscheme));
+        } else {
+            fs::create_dir_all(&opts.scheme_dir)?;
+            fs::copy(path, dest)?;
+        }
+        colorschemes.push(colorscheme);
+    }
+
+    let files_dir = root.join("files");
+    let install_dir = pack_dir.as_ref().join(&name);
+    if files_dir.is_dir() {
+        copy_dir(&files_dir, &install_dir)?;
+    }
+
+    for (target, entries) in selectors {
+        let selector = match opts.selector(target) {
+            Some(s) => s,
+            None => bail!("{} doesn't use a selector", target),
+        };
+
+        let existing = if selector.exists() { read_selector(selector)? } else { Vec::new() };
+
+        let mut selector_str = fs::read_to_string(selector).unwrap_or_default();
+        if !selector_str.is_empty() && !selector_str.ends_with('\n') {
+            selector_str.push('\n');
+        }
+        for (k, v) in entries {
+            if !colorschemes.contains(&k) {
+                colorschemes.push(k.clone());
+            }
+            if existing.iter().any(|(e, _)| e == &k) {
+                skipped.push(format!("{} selector entry for {}", target, k));
+                continue;
+            }
+
+            // relative paths point into the files directory of the pack
+            let value = if Path::new(&v).is_relative() && files_dir.join(&v).exists() {
+                install_dir.join(&v).display().to_string()
+            } else {
+                v
+            };
+            selector_str.push_str(&format!("{}: {}\n", quote(&k)?, quote(&value)?));
+        }
+
+        if let Some(parent) = selector.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(selector, selector_str)?;
+    }
+
+    for c in colorschemes.iter() {
+        crate::add(colors_file.as_ref(), c.clone())?;
+    }
+
+    Ok(InstalledPack { name, colorschemes, skipped })
+}
+
+/// Checks that no colorscheme name or relative path of a pack leaves the directory it's joined
+/// to, before anything is installed.
+fn check_names(
+    schemes: &[(String, PathBuf)],
+    selectors: &[(Target, Vec<(String, String)>)],
+) -> anyhow::Result<()> {
+    for (colorscheme, _) in schemes.iter() {
+        check_name("colorscheme", colorscheme)?;
+    }
+    for (target, entries) in selectors.iter() {
+        for (k, v) in entries.iter() {
+            check_name("colorscheme", k)?;
+            let path = Path::new(v);
+            if path.is_relative() && path.components().any(|c| c == Component::ParentDir) {
+                bail!("Invalid path {} in {} selector entry for {}", v, target, k);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Names from a pack are used as file names, so they may not contain path separators or `..`.
+fn check_name(kind: &str, name: &str) -> anyhow::Result<()> {
+    if name.contains(['/', '\\', '\0']) || name.contains("..") {
+        bail!("Invalid {} name {} in pack", kind, name);
+    }
+    Ok(())
+}
+
+/// Returns the file stems and paths of the yaml files in `dir`, sorted by name.
+fn yaml_files(dir: &Path) -> anyhow::Result<Vec<(String, PathBuf)>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<_> = fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "yml" || e == "yaml"))
+        .filter_map(|p| Some((p.file_stem()?.to_str()?.to_owned(), p)))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+pub(crate) fn read_selector(path: &Path) -> anyhow::Result<Vec<(String, String)>> {
+    let selector_str =
+        fs::read_to_string(path).map_err(|_| anyhow!("Error reading {}", path.display()))?;
+    let selector = match YamlLoader::load_from_str(&selector_str)?.into_iter().next() {
+        Some(Yaml::Hash(h)) => h,
+        Some(_) => bail!("Error parsing {}", path.display()),
+        None => return Ok(Vec::new()),
+    };
+
+    let entries = selector
+        .into_iter()
+        .filter_map(|(k, v)| Some((k.into_string()?, v.into_string()?)))
+        .collect();
+    Ok(entries)
+}
+
+pub(crate) fn quote(str: &str) -> anyhow::Result<String> {
+    Ok(serde_yaml::to_string(str)?.trim_end().to_owned())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let dest = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &dest)?;
+        } else {
+            fs::copy(entry.path(), dest)?;
+        }
+    }
+    Ok(())
+}
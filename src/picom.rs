@@ -0,0 +1,31 @@
+use anyhow::{anyhow, bail};
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+pub fn reload_picom(file: impl AsRef<Path>, opacity: Option<f32>) -> anyhow::Result<()> {
+    let config = render_picom(opacity)?;
+    fs::write(file.as_ref(), config)?;
+    notify_picom()
+}
+
+/// Makes picom read its configuration again, it resets itself when it receives `SIGUSR1`.
+pub fn notify_picom() -> anyhow::Result<()> {
+    let output = Command::new("pkill")
+        .args(["-USR1", "-x", "picom"])
+        .output()
+        .map_err(|e| anyhow!("Error running pkill:\n{}", e))?;
+    // pkill exits with 1 if no process matched
+    match output.status.code() {
+        Some(0 | 1) => Ok(()),
+        _ => bail!("Error resetting picom:\n{}", String::from_utf8_lossy(&output.stderr).trim()),
+    }
+}
+
+/// Renders the `active-opacity` and `inactive-opacity` options, which are reset to opaque windows
+/// if no opacity is set.
+pub fn render_picom(opacity: Option<f32>) -> anyhow::Result<String> {
+    let opacity = opacity.unwrap_or(1.0);
+    Ok(format!("active-opacity = {:?};\ninactive-opacity = {:?};\n", opacity, opacity))
+}
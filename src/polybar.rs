@@ -0,0 +1,58 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+pub fn reload_polybar(
+    colors_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let colors = render_polybar(selector, &scheme_dir, colorscheme)?;
+    fs::write(colors_file.as_ref(), colors)?;
+    notify_polybar()
+}
+
+/// Restarts the running bars using their ipc channels, which makes them read the included colors
+/// file again. Nothing is done if polybar isn't running.
+pub fn notify_polybar() -> anyhow::Result<()> {
+    let running = Command::new("pgrep")
+        .args(["-x", "polybar"])
+        .output()
+        .map_err(|e| anyhow!("Error running pgrep:\n{}", e))?;
+    if !running.status.success() {
+        return Ok(());
+    }
+
+    let output = Command::new("polybar-msg")
+        .args(["cmd", "restart"])
+        .output()
+        .map_err(|e| anyhow!("Error running polybar-msg:\n{}", e))?;
+    if !output.status.success() {
+        bail!("Error restarting polybar:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+pub fn render_polybar(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading polybar selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => {
+            let colors = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading polybar colors file"))?;
+            Ok(colors)
+        }
+        None => bail!("Missing mapping in polybar selector"),
+    }
+}
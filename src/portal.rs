@@ -0,0 +1,70 @@
+use anyhow::{anyhow, bail};
+
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use crate::Appearance;
+
+const PORTAL_DEST: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+
+/// Sends the `color-scheme` preference of the desktop portal right away and then every time it
+/// changes. Uses `gdbus` to talk to the session bus.
+pub fn watch_appearance(sender: Sender<Appearance>) -> anyhow::Result<()> {
+    // subscribe before reading the preference, so a change in between isn't missed
+    let child = Command::new("gdbus")
+        .args(["monitor", "--session", "--dest", PORTAL_DEST, "--object-path", PORTAL_PATH])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|_| anyhow!("Error running gdbus"))?;
+    let stdout = child.stdout.ok_or_else(|| anyhow!("Error reading output of gdbus"))?;
+
+    sender.send(read_appearance()?)?;
+
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some(appearance) = setting_changed(&line) {
+                if sender.send(appearance).is_err() {
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn read_appearance() -> anyhow::Result<Appearance> {
+    let output = Command::new("gdbus")
+        .args(["call", "--session", "--dest", PORTAL_DEST, "--object-path", PORTAL_PATH])
+        .args(["--method", "org.freedesktop.portal.Settings.Read"])
+        .args(["org.freedesktop.appearance", "color-scheme"])
+        .output()
+        .map_err(|_| anyhow!("Error running gdbus"))?;
+    if !output.status.success() {
+        bail!("Error reading color-scheme from the desktop portal");
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    appearance(&stdout).ok_or_else(|| anyhow!("Error parsing color-scheme: {}", stdout.trim()))
+}
+
+/// Parses `SettingChanged ('org.freedesktop.appearance', 'color-scheme', <uint32 1>)` signals.
+fn setting_changed(line: &str) -> Option<Appearance> {
+    let (_, args) = line.split_once("org.freedesktop.portal.Settings.SettingChanged ")?;
+    let args = args.strip_prefix("('org.freedesktop.appearance', 'color-scheme', ")?;
+    appearance(args)
+}
+
+/// Parses the `uint32` value, where `1` means dark, and `2` light or `0` no preference.
+fn appearance(value: &str) -> Option<Appearance> {
+    let (_, value) = value.split_once("uint32 ")?;
+    let end = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    match value[..end].parse::<u32>().ok()? {
+        1 => Some(Appearance::Dark),
+        _ => Some(Appearance::Light),
+    }
+}
@@ -0,0 +1,67 @@
+use anyhow::{anyhow, bail};
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::{Options, SchemeFile};
+
+/// Runs the executable files in `<hook dir>/post-apply.d` in the order of their names, with the
+/// colorscheme in `ALCO_COLORSCHEME` and its variant in `ALCO_VARIANT`. A failing hook doesn't stop
+/// the other ones, the result of each hook is returned. The colors of the roles are passed in
+/// variables like `ALCO_ROLE_ACCENT`.
+pub fn run_post_apply_hooks(
+    opts: &Options,
+    colorscheme: &str,
+) -> anyhow::Result<Vec<(String, anyhow::Result<()>)>> {
+    let dir = opts.hook_dir.join("post-apply.d");
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut hooks: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|_| anyhow!("Error reading hook directory {}", dir.display()))?
+        .filter_map(|e| Some(e.ok()?.path()))
+        .filter(|p| is_executable(p))
+        .collect();
+    hooks.sort();
+
+    let scheme_file = crate::scheme_file(&opts.scheme_dir, colorscheme);
+    let variant = SchemeFile::load(scheme_file).ok().and_then(|s| s.meta.variant);
+    // the hooks also run for colorschemes without readable colors, just without the roles
+    let roles = crate::load_colorscheme(opts, colorscheme)
+        .and_then(|c| crate::color_roles(&c.map(|c| opts.map_color(c)), &opts.roles))
+        .unwrap_or_default();
+
+    let results = hooks
+        .into_iter()
+        .map(|hook| {
+            let name = hook.file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let mut cmd = Command::new(&hook);
+            cmd.env("ALCO_COLORSCHEME", colorscheme);
+            cmd.env("ALCO_VARIANT", variant.as_deref().unwrap_or_default());
+            for (role, color) in roles.iter() {
+                let var = format!("ALCO_ROLE_{}", role.to_uppercase().replace('-', "_"));
+                cmd.env(var, color.to_string());
+            }
+            (name, run(cmd))
+        })
+        .collect();
+    Ok(results)
+}
+
+fn run(mut cmd: Command) -> anyhow::Result<()> {
+    let output = cmd.output()?;
+    if !output.status.success() {
+        bail!("{}:\n{}", output.status, String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+fn is_executable(path: &Path) -> bool {
+    match fs::metadata(path) {
+        Ok(m) => m.is_file() && m.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,77 @@
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+use crate::Event;
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+/// How often the power supplies are checked if upower isn't installed.
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Sends an [`Event::Power`] right away and every time the computer is plugged in or unplugged.
+/// Changes are picked up from `upower --monitor`, or by polling sysfs if upower isn't installed.
+pub fn watch_power(sender: Sender<Event>) -> anyhow::Result<()> {
+    let monitor = Command::new("upower")
+        .arg("--monitor")
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()
+        .and_then(|c| c.stdout);
+
+    thread::spawn(move || {
+        let mut last = None;
+        // upower reports every change of the battery level, only changes of the power source are
+        // sent
+        let mut send = || {
+            let battery = on_battery();
+            if last == Some(battery) {
+                return true;
+            }
+            last = Some(battery);
+            sender.send(Event::Power(battery)).is_ok()
+        };
+        if !send() {
+            return;
+        }
+        match monitor {
+            Some(stdout) => {
+                for _ in BufReader::new(stdout).lines().map_while(Result::ok) {
+                    if !send() {
+                        break;
+                    }
+                }
+            }
+            None => loop {
+                thread::sleep(POLL_INTERVAL);
+                if !send() {
+                    break;
+                }
+            },
+        }
+    });
+
+    Ok(())
+}
+
+/// Whether the computer runs on battery, which is the case if no mains supply is online. Without a
+/// mains supply, the computer runs on battery while a battery is discharging.
+fn on_battery() -> bool {
+    let supplies: Vec<_> = match fs::read_dir(POWER_SUPPLY_DIR) {
+        Ok(e) => e.flatten().map(|e| e.path()).collect(),
+        Err(_) => return false,
+    };
+    let read = |p: &Path, f: &str| fs::read_to_string(p.join(f)).unwrap_or_default();
+
+    let mains: Vec<_> = supplies.iter().filter(|p| read(p, "type").trim() == "Mains").collect();
+    if !mains.is_empty() {
+        return mains.iter().all(|p| read(p, "online").trim() != "1");
+    }
+    supplies
+        .iter()
+        .any(|p| read(p, "type").trim() == "Battery" && read(p, "status").trim() == "Discharging")
+}
@@ -0,0 +1,80 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::YamlLoader;
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+
+/// The version of qutebrowser's ipc protocol.
+const PROTOCOL_VERSION: u32 = 1;
+
+pub fn reload_qutebrowser(
+    colors_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let colors = render_qutebrowser(selector, &scheme_dir, colorscheme)?;
+    fs::write(colors_file.as_ref(), colors)?;
+    notify_qutebrowser(colors_file)
+}
+
+/// Makes the running instances source the colors file, by sending `:config-source` to the ipc
+/// sockets in `$XDG_RUNTIME_DIR/qutebrowser`. This is what `qutebrowser :config-source` does,
+/// without starting a new instance if none is running.
+pub fn notify_qutebrowser(colors_file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let dir = match env::var_os("XDG_RUNTIME_DIR") {
+        Some(d) => PathBuf::from(d).join("qutebrowser"),
+        None => return Ok(()),
+    };
+    let sockets = match fs::read_dir(&dir) {
+        Ok(s) => s,
+        Err(_) => return Ok(()),
+    };
+
+    let command = format!(":config-source {}", colors_file.as_ref().display());
+    let message = serde_json::json!({
+        "args": [command],
+        "target_arg": null,
+        "version": "",
+        "protocol_version": PROTOCOL_VERSION,
+        "cwd": dir,
+    });
+    for socket in sockets.flatten() {
+        if !socket.file_name().to_string_lossy().starts_with("ipc-") {
+            continue;
+        }
+        // sockets of instances that didn't exit cleanly are left behind and refuse connections
+        let mut stream = match UnixStream::connect(socket.path()) {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+        // qutebrowser reads one json message per line
+        stream
+            .write_all(format!("{}\n", message).as_bytes())
+            .map_err(|e| anyhow!("Error sending command to qutebrowser:\n{}", e))?;
+    }
+    Ok(())
+}
+
+pub fn render_qutebrowser(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading qutebrowser selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => {
+            let colors = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading qutebrowser colors file"))?;
+            Ok(colors)
+        }
+        None => bail!("Missing mapping in qutebrowser selector"),
+    }
+}
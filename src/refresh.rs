@@ -0,0 +1,35 @@
+use anyhow::bail;
+
+use std::process::Command;
+
+use crate::RefreshConfig;
+
+/// Makes status bars redraw immediately after a colorscheme was applied, instead of on their next
+/// poll interval.
+pub fn refresh_status_bars(config: &RefreshConfig) -> Vec<anyhow::Result<()>> {
+    let mut results = Vec::new();
+    if config.tmux {
+        results.push(crate::refresh_tmux());
+    }
+    results.extend(config.pids.iter().map(|p| signal_pid(*p)));
+    results.extend(config.processes.iter().map(|p| signal_process(p)));
+    results
+}
+
+fn signal_pid(pid: u32) -> anyhow::Result<()> {
+    let output = Command::new("kill").arg("-s").arg("USR1").arg(pid.to_string()).output()?;
+    if !output.status.success() {
+        bail!("Error sending USR1 to process {}", pid);
+    }
+    Ok(())
+}
+
+/// Sends `USR1` to all processes named `name`, it's not an error if none are running.
+fn signal_process(name: &str) -> anyhow::Result<()> {
+    let output = Command::new("pkill").arg("-USR1").arg("-x").arg(name).output()?;
+    // pkill exits with 1 if no process matched
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        bail!("Error sending USR1 to {}", name);
+    }
+    Ok(())
+}
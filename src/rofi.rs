@@ -0,0 +1,36 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::path::Path;
+
+pub fn reload_rofi(
+    colors_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let colors = render_rofi(selector, &scheme_dir, colorscheme)?;
+    fs::write(colors_file.as_ref(), colors)?;
+    Ok(())
+}
+
+pub fn render_rofi(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading rofi selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => {
+            let colors = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading rofi colors file"))?;
+            Ok(colors)
+        }
+        None => bail!("Missing mapping in rofi selector"),
+    }
+}
@@ -0,0 +1,44 @@
+use anyhow::bail;
+
+use std::collections::BTreeMap;
+
+use crate::{Color, Colorscheme};
+
+/// The rules of the roles that aren't set in the settings file.
+const DEFAULT_ROLES: [(&str, &str); 5] = [
+    ("accent", "colors.normal.blue"),
+    ("warning", "colors.normal.yellow"),
+    ("error", "colors.normal.red"),
+    ("surface", "colors.normal.black | colors.primary.background"),
+    ("muted", "colors.bright.black | colors.normal.white"),
+];
+
+/// Derives semantic colors like `accent` or `error` from the colors of a colorscheme, by role
+/// name. A rule names a color like a template placeholder, several alternatives can be separated
+/// by `|` and the first one that is set is used, or it's a fixed `#rrggbb` color. Roles whose
+/// colors aren't set are left out.
+pub fn color_roles(
+    colors: &Colorscheme,
+    rules: &BTreeMap<String, String>,
+) -> anyhow::Result<BTreeMap<String, Color>> {
+    let defaults = DEFAULT_ROLES.into_iter().filter(|(n, _)| !rules.contains_key(*n));
+    let rules = rules.iter().map(|(n, r)| (n.as_str(), r.as_str())).chain(defaults);
+
+    let mut roles = BTreeMap::new();
+    for (name, rule) in rules {
+        for alternative in rule.split('|').map(str::trim) {
+            let color = match alternative.parse::<Color>() {
+                Ok(c) if alternative.starts_with('#') => Some(c),
+                _ => match crate::template::color(colors, alternative) {
+                    Some(c) => c,
+                    None => bail!("Invalid rule {} of the color role {}", rule, name),
+                },
+            };
+            if let Some(c) = color {
+                roles.insert(name.to_owned(), c);
+                break;
+            }
+        }
+    }
+    Ok(roles)
+}
@@ -0,0 +1,181 @@
+use anyhow::{anyhow, bail};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Duration;
+
+use crate::{ScheduleConfig, TimeOfDay};
+
+/// The longest time between two checks, so that a suspended machine or a changed time zone
+/// doesn't delay a transition for long.
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// The julian date of 2000-01-01 12:00 UTC.
+const J2000: f64 = 2451545.0;
+
+/// Sends the current [`TimeOfDay`] right away and then at every transition between day and night.
+pub fn watch_schedule(settings: &ScheduleConfig, sender: Sender<TimeOfDay>) -> anyhow::Result<()> {
+    let schedule = Schedule::new(settings)?;
+    thread::spawn(move || {
+        let mut last = None;
+        loop {
+            let now = Local::now();
+            let (current, next) = schedule.at(now);
+            if last != Some(current) {
+                if sender.send(current).is_err() {
+                    break;
+                }
+                last = Some(current);
+            }
+
+            let until_next = next.and_then(|n| (n - now).to_std().ok()).unwrap_or(MAX_SLEEP);
+            thread::sleep(until_next.min(MAX_SLEEP));
+        }
+    });
+
+    Ok(())
+}
+
+enum Schedule {
+    Fixed { day_start: NaiveTime, night_start: NaiveTime },
+    Sun { latitude: f64, longitude: f64 },
+}
+
+impl Schedule {
+    fn new(settings: &ScheduleConfig) -> anyhow::Result<Self> {
+        let s = settings;
+        match (s.latitude, s.longitude, &s.day_start, &s.night_start) {
+            (Some(latitude), Some(longitude), None, None) => {
+                if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude) {
+                    bail!("Invalid location {}, {}", latitude, longitude);
+                }
+                Ok(Self::Sun { latitude, longitude })
+            }
+            (None, None, Some(day_start), Some(night_start)) => {
+                let day_start = parse_time(day_start)?;
+                let night_start = parse_time(night_start)?;
+                if day_start == night_start {
+                    bail!("day_start and night_start are the same time");
+                }
+                Ok(Self::Fixed { day_start, night_start })
+            }
+            _ => bail!("Either day_start and night_start or latitude and longitude have to be set"),
+        }
+    }
+
+    /// The time of day at `now` and the next transition, if there is one until tomorrow.
+    fn at(&self, now: DateTime<Local>) -> (TimeOfDay, Option<DateTime<Local>>) {
+        let today = now.date_naive();
+        let mut transitions = Vec::new();
+        for date in [today.pred_opt(), Some(today), today.succ_opt()].into_iter().flatten() {
+            self.transitions(date, &mut transitions);
+        }
+        transitions.sort_by_key(|(t, _)| *t);
+
+        let current = transitions.iter().rev().find(|(t, _)| *t <= now).map(|(_, p)| *p);
+        let next = transitions.iter().find(|(t, _)| *t > now).map(|(t, _)| *t);
+        let current = current.unwrap_or_else(|| match self {
+            Self::Sun { latitude, longitude } => match sun_times(today, *latitude, *longitude) {
+                Err(polar) => polar,
+                Ok(_) => TimeOfDay::Night,
+            },
+            Self::Fixed { .. } => TimeOfDay::Night,
+        });
+        (current, next)
+    }
+
+    fn transitions(&self, date: NaiveDate, transitions: &mut Vec<(DateTime<Local>, TimeOfDay)>) {
+        match self {
+            Self::Fixed { day_start, night_start } => {
+                for (time, period) in [(day_start, TimeOfDay::Day), (night_start, TimeOfDay::Night)]
+                {
+                    // skipped by a daylight saving time change
+                    if let Some(t) = Local.from_local_datetime(&date.and_time(*time)).earliest() {
+                        transitions.push((t, period));
+                    }
+                }
+            }
+            Self::Sun { latitude, longitude } => {
+                if let Ok((sunrise, sunset)) = sun_times(date, *latitude, *longitude) {
+                    transitions.push((sunrise.with_timezone(&Local), TimeOfDay::Day));
+                    transitions.push((sunset.with_timezone(&Local), TimeOfDay::Night));
+                }
+            }
+        }
+    }
+}
+
+fn parse_time(time: &str) -> anyhow::Result<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|_| anyhow!("Invalid time {}, expected hour:minute", time))
+}
+
+/// Sunrise and sunset on `date` using the sunrise equation. If the sun doesn't rise or set that
+/// day, the time of day it stays at is returned as the error.
+fn sun_times(
+    date: NaiveDate,
+    latitude: f64,
+    longitude: f64,
+) -> Result<(DateTime<Utc>, DateTime<Utc>), TimeOfDay> {
+    let days = (date - NaiveDate::from_ymd_opt(2000, 1, 1).unwrap_or_default()).num_days();
+    let mean_solar_time = days as f64 - longitude / 360.0;
+    let anomaly = (357.5291 + 0.98560028 * mean_solar_time).rem_euclid(360.0).to_radians();
+    let center =
+        1.9148 * anomaly.sin() + 0.02 * (2.0 * anomaly).sin() + 0.0003 * (3.0 * anomaly).sin();
+    let ecliptic_longitude =
+        (anomaly.to_degrees() + center + 180.0 + 102.9372).rem_euclid(360.0).to_radians();
+    let transit = J2000 + mean_solar_time + 0.0053 * anomaly.sin()
+        - 0.0069 * (2.0 * ecliptic_longitude).sin();
+    let declination = (ecliptic_longitude.sin() * 23.4397_f64.to_radians().sin()).asin();
+
+    // -0.833° accounts for refraction and the size of the sun
+    let latitude = latitude.to_radians();
+    let cos_hour_angle = ((-0.833_f64).to_radians().sin() - latitude.sin() * declination.sin())
+        / (latitude.cos() * declination.cos());
+    if cos_hour_angle > 1.0 {
+        return Err(TimeOfDay::Night);
+    } else if cos_hour_angle < -1.0 {
+        return Err(TimeOfDay::Day);
+    }
+
+    let hour_angle = cos_hour_angle.acos().to_degrees() / 360.0;
+    Ok((julian_to_utc(transit - hour_angle), julian_to_utc(transit + hour_angle)))
+}
+
+fn julian_to_utc(julian: f64) -> DateTime<Utc> {
+    let millis = ((julian - 2440587.5) * 86_400_000.0) as i64;
+    Utc.timestamp_millis_opt(millis).single().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: DateTime<Utc>, expected: &str) {
+        let expected: DateTime<Utc> = expected.parse().unwrap();
+        let diff = (actual - expected).num_seconds().abs();
+        assert!(diff < 180, "expected {} but got {}", expected, actual);
+    }
+
+    #[test]
+    fn sunrise_and_sunset() {
+        // Berlin at the summer solstice
+        let date = NaiveDate::from_ymd_opt(2024, 6, 21).unwrap();
+        let (sunrise, sunset) = sun_times(date, 52.52, 13.405).unwrap();
+        assert_near(sunrise, "2024-06-21T02:43:00Z");
+        assert_near(sunset, "2024-06-21T19:33:00Z");
+
+        // Sydney in winter, the sun rises before midnight UTC
+        let (sunrise, sunset) = sun_times(date, -33.87, 151.21).unwrap();
+        assert_near(sunrise, "2024-06-20T20:59:00Z");
+        assert_near(sunset, "2024-06-21T06:54:00Z");
+
+        // New York and the polar night in Tromsø at the winter solstice
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        let (sunrise, sunset) = sun_times(date, 40.71, -74.006).unwrap();
+        assert_near(sunrise, "2024-12-21T12:16:00Z");
+        assert_near(sunset, "2024-12-21T21:32:00Z");
+        assert_eq!(sun_times(date, 69.65, 18.96), Err(TimeOfDay::Night));
+    }
+}
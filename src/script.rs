@@ -0,0 +1,158 @@
+use anyhow::anyhow;
+use mlua::{Function, Lua, Table, Value};
+
+use std::fs;
+use std::path::Path;
+
+use crate::colorscheme::COLOR_NAMES;
+use crate::{Color, Colorscheme, Options};
+
+/// Defines the `alco` table, which the init script uses to register palette transforms and custom
+/// targets.
+const PRELUDE: &str = r#"
+alco = { transforms = {}, targets = {} }
+
+function alco.transform(f)
+    table.insert(alco.transforms, f)
+end
+
+function alco.target(name, f)
+    table.insert(alco.targets, { name = name, run = f })
+end
+"#;
+
+/// The init script, which can transform the palette of colorschemes and define custom targets.
+pub struct Script {
+    lua: Lua,
+}
+
+impl Script {
+    pub fn load(script_file: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let script_file = script_file.as_ref();
+        let script_str =
+            fs::read_to_string(script_file).map_err(|_| anyhow!("Error reading init script"))?;
+
+        let lua = Lua::new();
+        lua.load(PRELUDE).exec().map_err(lua_error)?;
+        lua.load(&script_str)
+            .set_name(format!("@{}", script_file.display()))
+            .exec()
+            .map_err(lua_error)?;
+
+        Ok(Self { lua })
+    }
+
+    /// Runs the palette transforms, the changed colors are stored in the color map of `opts`.
+    pub fn transform(&self, opts: &mut Options, colorscheme: &str) -> anyhow::Result<()> {
+        let transforms: Vec<Function> = self.alco()?.get("transforms").map_err(lua_error)?;
+        if transforms.is_empty() {
+            return Ok(());
+        }
+
+        let original = crate::load_colorscheme(opts, colorscheme)?;
+        let mut palette = original.clone();
+        for f in transforms {
+            let table = self.to_table(&palette)?;
+            let result: Value = f.call((colorscheme, table.clone())).map_err(lua_error)?;
+            // transforms can either return a new palette or modify the passed one
+            palette = match result {
+                Value::Table(t) => from_table(&t)?,
+                _ => from_table(&table)?,
+            };
+        }
+
+        let colors = |s: &Colorscheme| {
+            let special = [s.foreground, s.background, s.cursor];
+            special.into_iter().chain(s.ansi()).collect::<Vec<_>>()
+        };
+        opts.color_map = colors(&original)
+            .into_iter()
+            .zip(colors(&palette))
+            .filter_map(|(from, to)| Some((from?, to?)))
+            .filter(|(from, to)| from != to)
+            .collect();
+
+        Ok(())
+    }
+
+    /// Runs the custom targets, which are passed the final palette of the colorscheme.
+    pub fn run_targets(
+        &self,
+        opts: &Options,
+        colorscheme: &str,
+    ) -> Vec<(String, anyhow::Result<()>)> {
+        let targets: Vec<Table> =
+            match self.alco().and_then(|a| a.get("targets").map_err(lua_error)) {
+                Ok(t) => t,
+                Err(e) => return vec![("init script".into(), Err(e))],
+            };
+
+        let palette = crate::load_colorscheme(opts, colorscheme)
+            .map(|s| s.map(|c| opts.map_color(c)))
+            .unwrap_or_default();
+
+        targets
+            .into_iter()
+            .map(|t| {
+                let name: String = t.get("name").unwrap_or_else(|_| "unnamed".into());
+                let result = t.get::<_, Function>("run").map_err(lua_error).and_then(|f| {
+                    let table = self.to_table(&palette)?;
+                    f.call::<_, ()>((colorscheme, table)).map_err(lua_error)
+                });
+                (name, result)
+            })
+            .collect()
+    }
+
+    fn alco(&self) -> anyhow::Result<Table<'_>> {
+        self.lua.globals().get("alco").map_err(lua_error)
+    }
+
+    /// Converts a palette to a table of the form
+    /// `{ foreground = "#rrggbb", ..., normal = { black = "#rrggbb", ... }, bright = { ... } }`.
+    fn to_table(&self, palette: &Colorscheme) -> anyhow::Result<Table<'_>> {
+        let hex = |c: Option<Color>| c.map(|c| c.to_string());
+        let table = self.lua.create_table().map_err(lua_error)?;
+        table.set("foreground", hex(palette.foreground)).map_err(lua_error)?;
+        table.set("background", hex(palette.background)).map_err(lua_error)?;
+        table.set("cursor", hex(palette.cursor)).map_err(lua_error)?;
+        for (key, colors) in [("normal", &palette.normal), ("bright", &palette.bright)] {
+            let section = self.lua.create_table().map_err(lua_error)?;
+            for (name, c) in COLOR_NAMES.iter().zip(colors.iter()) {
+                section.set(*name, hex(*c)).map_err(lua_error)?;
+            }
+            table.set(key, section).map_err(lua_error)?;
+        }
+        Ok(table)
+    }
+}
+
+fn from_table(table: &Table) -> anyhow::Result<Colorscheme> {
+    let color = |t: &Table, key: &str| -> anyhow::Result<Option<Color>> {
+        match t.get::<_, Option<String>>(key).map_err(lua_error)? {
+            Some(s) => Ok(Some(s.parse()?)),
+            None => Ok(None),
+        }
+    };
+    let section = |key: &str| -> anyhow::Result<[Option<Color>; 8]> {
+        let mut colors = [None; 8];
+        if let Some(t) = table.get::<_, Option<Table>>(key).map_err(lua_error)? {
+            for (c, name) in colors.iter_mut().zip(COLOR_NAMES) {
+                *c = color(&t, name)?;
+            }
+        }
+        Ok(colors)
+    };
+
+    Ok(Colorscheme {
+        foreground: color(table, "foreground")?,
+        background: color(table, "background")?,
+        cursor: color(table, "cursor")?,
+        normal: section("normal")?,
+        bright: section("bright")?,
+    })
+}
+
+fn lua_error(e: mlua::Error) -> anyhow::Error {
+    anyhow!("{}", e)
+}
@@ -0,0 +1,134 @@
+use anyhow::anyhow;
+use toml_edit::{Array, DocumentMut};
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use crate::Target;
+
+/// The colorschemes `alco setup` imports from the alacritty-theme repository, by name.
+pub const STARTER_SCHEMES: [(&str, &str); 7] = [
+    ("gruvbox-dark", "gruvbox_dark.toml"),
+    ("gruvbox-light", "gruvbox_light.toml"),
+    ("solarized-dark", "solarized_dark.toml"),
+    ("solarized-light", "solarized_light.toml"),
+    ("nord", "nord.toml"),
+    ("dracula", "dracula.toml"),
+    ("tokyo-night", "tokyo_night.toml"),
+];
+
+const ALACRITTY_THEME_URL: &str =
+    "https://raw.githubusercontent.com/alacritty/alacritty-theme/master/themes";
+
+/// The executables that show a target is installed.
+fn programs(target: Target) -> &'static [&'static str] {
+    match target {
+        Target::Alacritty => &["alacritty"],
+        Target::Kitty => &["kitty"],
+        Target::Tmux => &["tmux"],
+        Target::Neovim => &["nvim"],
+        Target::Starship => &["starship"],
+        Target::Bat => &["bat", "batcat"],
+        Target::Delta => &["delta"],
+        Target::Cmus => &["cmus"],
+        Target::Wezterm => &["wezterm"],
+        Target::Foot => &["foot"],
+        Target::Zellij => &["zellij"],
+        Target::Fish => &["fish"],
+        Target::Fzf => &["fzf"],
+        Target::Rofi => &["rofi"],
+        Target::Dunst => &["dunst"],
+        Target::Waybar => &["waybar"],
+        Target::Polybar => &["polybar"],
+        Target::Picom => &["picom"],
+        Target::I3 => &["i3", "sway"],
+        Target::Hyprland => &["Hyprland"],
+        Target::Gtk => &["gsettings"],
+        Target::Qutebrowser => &["qutebrowser"],
+        Target::Vscode => &["code", "codium"],
+        Target::Zathura => &["zathura"],
+        Target::Mako => &["mako"],
+        Target::Ghostty => &["ghostty"],
+        Target::Konsole => &["konsole"],
+        Target::Emacs => &["emacs"],
+        Target::Vim => &["vim"],
+        Target::Xresources => &["xrdb"],
+    }
+}
+
+/// The targets whose executables are found in `PATH`.
+pub fn detect_targets() -> Vec<Target> {
+    let dirs: Vec<_> =
+        env::var_os("PATH").map(|p| env::split_paths(&p).collect()).unwrap_or_default();
+    Target::ALL
+        .into_iter()
+        .filter(|t| programs(*t).iter().any(|p| dirs.iter().any(|d| d.join(p).is_file())))
+        .collect()
+}
+
+/// Imports the starter colorschemes into the scheme directory and adds them to the colorscheme
+/// list, which is created if it doesn't exist. Returns the result of every colorscheme.
+pub fn import_starter_schemes(
+    colors_file: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+) -> Vec<(String, anyhow::Result<()>)> {
+    let colors_file = colors_file.as_ref();
+    if !colors_file.exists() {
+        let created = colors_file.parent().map_or(Ok(()), fs::create_dir_all);
+        if let Err(e) = created.and_then(|_| fs::write(colors_file, "colors: []\n")) {
+            let e = anyhow!("Error writing colorscheme list file:\n{}", e);
+            return vec![(String::new(), Err(e))];
+        }
+    }
+
+    STARTER_SCHEMES
+        .iter()
+        .map(|(name, file)| {
+            let url = format!("{}/{}", ALACRITTY_THEME_URL, file);
+            let result = crate::import_alacritty_theme(&url, &scheme_dir, Some(*name))
+                .and_then(|_| crate::add(colors_file, name.to_string()));
+            (name.to_string(), result)
+        })
+        .collect()
+}
+
+/// Sets the targets reloaded by default in the settings file, keeping comments and all other
+/// settings. The file is created if it doesn't exist.
+pub fn write_reload_targets(
+    settings_file: impl AsRef<Path>,
+    targets: &[Target],
+) -> anyhow::Result<()> {
+    let settings_file = settings_file.as_ref();
+    let settings = fs::read_to_string(settings_file).unwrap_or_default();
+    let settings = set_reload(&settings, targets)?;
+    if let Some(dir) = settings_file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(settings_file, settings)
+        .map_err(|e| anyhow!("Error writing {}:\n{}", settings_file.display(), e))
+}
+
+fn set_reload(settings: &str, targets: &[Target]) -> anyhow::Result<String> {
+    let mut doc: DocumentMut =
+        settings.parse().map_err(|e| anyhow!("Error parsing settings file:\n{}", e))?;
+    let reload: Array = targets.iter().map(|t| t.name()).collect();
+    doc["reload"] = toml_edit::value(reload);
+    Ok(doc.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reload_targets() {
+        let settings = "# my settings\nmax_parallel = 2\nreload = [\"cmus\"]\n";
+        let settings = set_reload(settings, &[Target::Alacritty, Target::Tmux]).unwrap();
+        assert_eq!(
+            settings,
+            "# my settings\nmax_parallel = 2\nreload = [\"alacritty\", \"tmux\"]\n"
+        );
+        assert_eq!(set_reload("", &[Target::Kitty]).unwrap(), "reload = [\"kitty\"]\n");
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::{anyhow, bail};
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::{Color, Colorscheme, Options};
+
+const FONT_SIZE: f32 = 14.0;
+const CHAR_WIDTH: f32 = 8.4;
+const LINE_HEIGHT: f32 = 20.0;
+const PADDING: f32 = 16.0;
+pub(crate) const COLUMNS: usize = 56;
+const SWATCH_SIZE: f32 = 24.0;
+
+/// The foreground color, the ansi colors are referenced by their index.
+pub(crate) const FG: usize = 16;
+
+/// A terminal session with a prompt, `ls` output and a highlighted code sample.
+pub(crate) const MOCK: &[&[(usize, &str)]] = &[
+    &[(2, "user@host"), (FG, " "), (4, "~/src/alco"), (FG, " "), (5, "main"), (FG, " $ ls")],
+    &[(4, "src"), (FG, "  "), (4, "include"), (FG, "  Cargo.toml  README.md  "), (2, "build.sh")],
+    &[
+        (2, "user@host"),
+        (FG, " "),
+        (4, "~/src/alco"),
+        (FG, " "),
+        (5, "main"),
+        (FG, " $ bat main.rs"),
+    ],
+    &[(5, "fn"), (FG, " "), (4, "main"), (FG, "() {")],
+    &[(8, "    // switch the colors of all targets")],
+    &[(5, "    let"), (FG, " targets = "), (3, "8"), (FG, ";")],
+    &[(FG, "    println!("), (2, "\"reloaded {} targets\""), (FG, ", targets);")],
+    &[(FG, "}")],
+    &[(1, "error"), (FG, ": "), (3, "warning"), (FG, ": "), (6, "note")],
+    &[(2, "user@host"), (FG, " "), (4, "~/src/alco"), (FG, " "), (5, "main"), (FG, " $ ")],
+];
+
+/// Renders a small terminal mock using the colors of `colorscheme`, followed by swatches of the
+/// ansi colors.
+pub fn snapshot_svg(colorscheme: &Colorscheme) -> String {
+    let fg = colorscheme.foreground.unwrap_or(Color::new(0xff, 0xff, 0xff));
+    let bg = colorscheme.background.unwrap_or(Color::new(0x00, 0x00, 0x00));
+    let cursor = colorscheme.cursor.unwrap_or(fg);
+    let ansi: Vec<_> = colorscheme.ansi().map(|c| c.unwrap_or(fg)).collect();
+    let color = |i: usize| if i == FG { fg } else { ansi[i] };
+
+    let text_height = MOCK.len() as f32 * LINE_HEIGHT;
+    let width = 2.0 * PADDING + COLUMNS as f32 * CHAR_WIDTH;
+    let height = 3.0 * PADDING + text_height + 2.0 * SWATCH_SIZE;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = width,
+        h = height,
+    );
+    svg.push_str(&format!("<rect width=\"100%\" height=\"100%\" rx=\"6\" fill=\"{}\"/>\n", bg));
+    svg.push_str(&format!(
+        "<g font-family=\"monospace\" font-size=\"{}\" xml:space=\"preserve\">\n",
+        FONT_SIZE
+    ));
+    for (i, line) in MOCK.iter().enumerate() {
+        let y = PADDING + (i as f32 + 0.75) * LINE_HEIGHT;
+        svg.push_str(&format!("<text x=\"{}\" y=\"{}\">", PADDING, y));
+        for (c, text) in line.iter() {
+            svg.push_str(&format!("<tspan fill=\"{}\">{}</tspan>", color(*c), escape(text)));
+        }
+        svg.push_str("</text>\n");
+    }
+    svg.push_str("</g>\n");
+
+    // the cursor after the last prompt
+    let last = MOCK[MOCK.len() - 1];
+    let columns: usize = last.iter().map(|(_, t)| t.chars().count()).sum();
+    svg.push_str(&format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+        PADDING + columns as f32 * CHAR_WIDTH,
+        PADDING + (MOCK.len() as f32 - 1.0) * LINE_HEIGHT + 2.0,
+        CHAR_WIDTH,
+        LINE_HEIGHT - 4.0,
+        cursor,
+    ));
+
+    for (i, c) in ansi.iter().enumerate() {
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"{}\"/>\n",
+            PADDING + (i % 8) as f32 * SWATCH_SIZE,
+            2.0 * PADDING + text_height + (i / 8) as f32 * SWATCH_SIZE,
+            c,
+            s = SWATCH_SIZE,
+        ));
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Writes a preview of a colorscheme to `out_file`, which is a png or svg file depending on its
+/// extension. Png files are converted using `rsvg-convert`.
+pub fn snapshot(
+    opts: &Options,
+    colorscheme: &str,
+    out_file: impl AsRef<Path>,
+) -> anyhow::Result<()> {
+    let scheme = crate::load_colorscheme(opts, colorscheme)?.map(|c| opts.map_color(c));
+    let svg = snapshot_svg(&scheme);
+
+    let out_file = out_file.as_ref();
+    match out_file.extension().and_then(|e| e.to_str()) {
+        Some("svg") => fs::write(out_file, svg)?,
+        Some("png") => {
+            let mut child = Command::new("rsvg-convert")
+                .arg("--format=png")
+                .arg("--output")
+                .arg(out_file)
+                .stdin(Stdio::piped())
+                .spawn()
+                .map_err(|_| anyhow!("Error running rsvg-convert"))?;
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(svg.as_bytes())?;
+            }
+            if !child.wait()?.success() {
+                bail!("Error converting snapshot to png");
+            }
+        }
+        _ => bail!("Unsupported snapshot format, use a png or svg file"),
+    }
+
+    Ok(())
+}
+
+/// Writes an `index.html` file to `out_dir`, containing a preview and the colors of every
+/// colorscheme in the colors file. Returns the colorschemes that couldn't be loaded.
+pub fn gallery(
+    opts: &Options,
+    colors_file: impl AsRef<Path>,
+    out_dir: impl AsRef<Path>,
+) -> anyhow::Result<Vec<(String, anyhow::Error)>> {
+    let mut skipped = Vec::new();
+    let mut html = String::from(GALLERY_HEADER);
+
+    for name in crate::list(colors_file)? {
+        let scheme = match crate::load_colorscheme(opts, &name) {
+            Ok(s) => s.map(|c| opts.map_color(c)),
+            Err(e) => {
+                skipped.push((name, e));
+                continue;
+            }
+        };
+
+        html.push_str(&format!("<section id=\"{0}\">\n<h2>{0}</h2>\n", escape(&name)));
+        html.push_str(&snapshot_svg(&scheme));
+        html.push_str("<ul>\n");
+        for (n, c) in scheme.colors() {
+            html.push_str(&format!(
+                "<li><span style=\"background: {0}\"></span>{1} <code>{0}</code></li>\n",
+                c, n
+            ));
+        }
+        html.push_str("</ul>\n</section>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+
+    let out_dir = out_dir.as_ref();
+    fs::create_dir_all(out_dir)?;
+    fs::write(out_dir.join("index.html"), html)?;
+
+    Ok(skipped)
+}
+
+const GALLERY_HEADER: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>alco colorschemes</title>
+<style>
+body { font-family: sans-serif; margin: 2em; }
+section { display: inline-block; vertical-align: top; margin: 0 2em 2em 0; }
+ul { list-style: none; padding: 0; columns: 2; font-size: 0.8em; }
+li span { display: inline-block; width: 1em; height: 1em; margin-right: 0.5em; vertical-align: middle; border: 1px solid #888; }
+</style>
+</head>
+<body>
+<h1>alco colorschemes</h1>
+";
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,96 @@
+use anyhow::{anyhow, bail};
+
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use crate::Event;
+
+/// The path of the unix socket `alco daemon` accepts commands on.
+pub fn socket_path() -> anyhow::Result<PathBuf> {
+    match env::var_os("XDG_RUNTIME_DIR") {
+        Some(dir) => Ok(PathBuf::from(dir).join("alco.sock")),
+        None => bail!("XDG_RUNTIME_DIR is not set"),
+    }
+}
+
+/// Listens on the socket and sends an [`Event::Remote`] for every `apply <colorscheme>`,
+/// `toggle`, `list` or `status` command a client sends. The reply is written back to the client,
+/// unknown commands are answered with an error.
+pub fn watch_socket(sender: Sender<Event>) -> anyhow::Result<()> {
+    let path = socket_path()?;
+    if path.exists() {
+        if UnixStream::connect(&path).is_ok() {
+            bail!("Another daemon is listening on {}", path.display());
+        }
+        // left behind by a daemon that didn't exit cleanly
+        fs::remove_file(&path)?;
+    }
+    let listener = UnixListener::bind(&path)
+        .map_err(|e| anyhow!("Error listening on {}:\n{}", path.display(), e))?;
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+            let sender = sender.clone();
+            thread::spawn(move || handle_client(stream, sender));
+        }
+    });
+
+    Ok(())
+}
+
+fn handle_client(mut stream: UnixStream, sender: Sender<Event>) {
+    let mut line = String::new();
+    if BufReader::new(&stream).read_line(&mut line).is_err() {
+        return;
+    }
+
+    let event = match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["apply", colorscheme] => Event::Apply((*colorscheme).to_owned()),
+        ["toggle"] => Event::Toggle,
+        ["list"] => Event::List,
+        ["status"] => Event::Status,
+        _ => {
+            stream.write_all(format!("error\nUnknown command {}", line.trim()).as_bytes()).ok();
+            return;
+        }
+    };
+
+    let (reply_sender, reply_receiver) = mpsc::channel();
+    if sender.send(Event::Remote(Box::new(event), reply_sender)).is_err() {
+        return;
+    }
+    let reply = match reply_receiver.recv() {
+        Ok(Ok(text)) => format!("ok\n{}", text),
+        Ok(Err(text)) => format!("error\n{}", text),
+        Err(_) => return,
+    };
+    stream.write_all(reply.as_bytes()).ok();
+}
+
+/// Sends a command to `alco daemon` and returns its reply.
+pub fn send_command(command: &str) -> anyhow::Result<String> {
+    let path = socket_path()?;
+    let mut stream = UnixStream::connect(&path)
+        .map_err(|_| anyhow!("Error connecting to the daemon, is `alco daemon` running?"))?;
+    stream.write_all(format!("{}\n", command).as_bytes())?;
+    stream.shutdown(Shutdown::Write)?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    // the first line of a reply is either `ok` or `error`
+    match reply.split_once('\n').unwrap_or((&reply, "")) {
+        ("ok", text) => Ok(text.to_owned()),
+        ("error", text) => bail!("{}", text),
+        _ => bail!("Invalid reply from the daemon"),
+    }
+}
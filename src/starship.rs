@@ -9,46 +9,81 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+use crate::Colorscheme;
+
 pub fn reload_starship(
     config_file: impl AsRef<Path>,
     in_file: impl AsRef<Path>,
     selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
     scheme_file: impl AsRef<str>,
 ) -> anyhow::Result<()> {
+    let config = render_starship(in_file, selector, scheme_dir, scheme_file)?;
+    fs::write(config_file.as_ref(), config)?;
+
+    Ok(())
+}
+
+pub fn render_starship(
+    in_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    scheme_file: impl AsRef<str>,
+) -> anyhow::Result<String> {
     let selector_str = fs::read_to_string(selector.as_ref())
         .map_err(|_| anyhow!("Error reading starship selector"))?;
     let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
 
-    match super::selector(&selector, scheme_file.as_ref()) {
+    match super::selector(&selector, &scheme_dir, scheme_file.as_ref()) {
         Some(s) => {
-            write_config(config_file, in_file, s)?;
-
-            Ok(())
+            let overrides = super::target_overrides(scheme_dir, scheme_file.as_ref(), "starship")?;
+            render_config(in_file, s, overrides)
         }
         None => bail!("Missing mapping in starship selector"),
     }
 }
 
-fn write_config(
-    config_file: impl AsRef<Path>,
+/// Returns the definition of a custom module named `alco` which shows the name of the colorscheme
+/// in its colors. It's shown by adding `${custom.alco}` to the prompt format.
+pub fn starship_module(colorscheme: &str, colors: Option<&Colorscheme>) -> String {
+    let mut module = String::from("\n[custom.alco]\n");
+    module.push_str("description = \"The current alco colorscheme\"\n");
+    module.push_str("when = true\n");
+
+    let fg = colors.and_then(|c| c.background);
+    let bg = colors.and_then(|c| c.normal[4].or(c.foreground));
+    let style: Vec<_> = [("fg", fg), ("bg", bg)]
+        .into_iter()
+        .filter_map(|(k, c)| Some(format!("{}:{}", k, c?)))
+        .collect();
+    let name = colorscheme.replace('\\', "\\\\").replace('"', "\\\"");
+    match style.is_empty() {
+        true => module.push_str(&format!("format = \"[ {} ]($style) \"\n", name)),
+        false => module.push_str(&format!("format = \"[ {} ]({}) \"\n", name, style.join(" "))),
+    }
+
+    module
+}
+
+fn render_config(
     in_file: impl AsRef<Path>,
     scheme_file: impl AsRef<str>,
-) -> anyhow::Result<()> {
+    overrides: Vec<(String, String)>,
+) -> anyhow::Result<String> {
     let scheme_str = fs::read_to_string(tilde(scheme_file.as_ref()).as_ref())
         .map_err(|_| anyhow!("Starship colorscheme file not found"))?;
     let input_str = fs::read_to_string(in_file.as_ref())
         .map_err(|_| anyhow!("Starship input file not found"))?;
 
-    let colorscheme = parse_colorscheme(&scheme_str)?;
+    let mut colorscheme = parse_colorscheme(&scheme_str)?;
+    colorscheme.extend(overrides);
 
     let mut new_config = input_str;
     for (k, v) in colorscheme.iter() {
         new_config = new_config.replace(&format!("<{}>", k), v);
     }
 
-    fs::write(config_file.as_ref(), new_config)?;
-
-    Ok(())
+    Ok(new_config)
 }
 
 fn parse_colorscheme(str: &str) -> anyhow::Result<HashMap<String, String>> {
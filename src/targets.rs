@@ -0,0 +1,1795 @@
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use shellexpand::tilde;
+use tokio::sync::Semaphore;
+use yaml_rust::YamlLoader;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{Color, Filter, Font, GammaConfig, Hunk, RefreshConfig, TemplateConfig};
+
+/// The rendered file path or error of each target.
+pub type RenderResults = Vec<(Target, anyhow::Result<PathBuf>)>;
+
+/// The line starting the block of a configuration file that alco manages.
+pub const BLOCK_BEGIN: &str = "# BEGIN alco";
+/// The line ending the block of a configuration file that alco manages.
+pub const BLOCK_END: &str = "# END alco";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Target {
+    Alacritty,
+    Kitty,
+    Tmux,
+    Neovim,
+    Starship,
+    Bat,
+    Delta,
+    Cmus,
+    Wezterm,
+    Foot,
+    Zellij,
+    Fish,
+    Fzf,
+    Rofi,
+    Dunst,
+    Waybar,
+    Polybar,
+    Picom,
+    I3,
+    Hyprland,
+    Gtk,
+    Qutebrowser,
+    Vscode,
+    Zathura,
+    Mako,
+    Ghostty,
+    Konsole,
+    Emacs,
+    Vim,
+    Xresources,
+}
+
+impl Target {
+    pub const ALL: [Target; 30] = [
+        Target::Alacritty,
+        Target::Kitty,
+        Target::Tmux,
+        Target::Neovim,
+        Target::Starship,
+        Target::Bat,
+        Target::Delta,
+        Target::Cmus,
+        Target::Wezterm,
+        Target::Foot,
+        Target::Zellij,
+        Target::Fish,
+        Target::Fzf,
+        Target::Rofi,
+        Target::Dunst,
+        Target::Waybar,
+        Target::Polybar,
+        Target::Picom,
+        Target::I3,
+        Target::Hyprland,
+        Target::Gtk,
+        Target::Qutebrowser,
+        Target::Vscode,
+        Target::Zathura,
+        Target::Mako,
+        Target::Ghostty,
+        Target::Konsole,
+        Target::Emacs,
+        Target::Vim,
+        Target::Xresources,
+    ];
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Target::Alacritty => "alacritty",
+            Target::Kitty => "kitty",
+            Target::Tmux => "tmux",
+            Target::Neovim => "neovim",
+            Target::Starship => "starship",
+            Target::Bat => "bat",
+            Target::Delta => "delta",
+            Target::Cmus => "cmus",
+            Target::Wezterm => "wezterm",
+            Target::Foot => "foot",
+            Target::Zellij => "zellij",
+            Target::Fish => "fish",
+            Target::Fzf => "fzf",
+            Target::Rofi => "rofi",
+            Target::Dunst => "dunst",
+            Target::Waybar => "waybar",
+            Target::Polybar => "polybar",
+            Target::Picom => "picom",
+            Target::I3 => "i3",
+            Target::Hyprland => "hyprland",
+            Target::Gtk => "gtk",
+            Target::Qutebrowser => "qutebrowser",
+            Target::Vscode => "vscode",
+            Target::Zathura => "zathura",
+            Target::Mako => "mako",
+            Target::Ghostty => "ghostty",
+            Target::Konsole => "konsole",
+            Target::Emacs => "emacs",
+            Target::Vim => "vim",
+            Target::Xresources => "xresources",
+        }
+    }
+
+    /// Whether alco only manages the lines between [`BLOCK_BEGIN`] and [`BLOCK_END`] of the
+    /// configuration file, so other settings can be kept around them.
+    pub const fn managed_block(&self) -> bool {
+        matches!(
+            self,
+            Target::Tmux
+                | Target::Delta
+                | Target::Dunst
+                | Target::Polybar
+                | Target::Picom
+                | Target::I3
+                | Target::Hyprland
+                | Target::Qutebrowser
+                | Target::Zathura
+        )
+    }
+}
+
+impl FromStr for Target {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match Target::ALL.iter().find(|t| t.name() == s) {
+            Some(t) => Ok(*t),
+            None => bail!("Unknown target {}", s),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Target {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for Target {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How configuration files are installed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Write or copy the configuration file.
+    #[default]
+    Copy,
+    /// Replace the configuration file with a symlink into the cache directory.
+    Symlink,
+}
+
+impl FromStr for Strategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "copy" => Ok(Strategy::Copy),
+            "symlink" => Ok(Strategy::Symlink),
+            _ => bail!("Unknown strategy {}", s),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Strategy {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone)]
+pub struct Options {
+    pub scheme_dir: PathBuf,
+    pub cache_dir: PathBuf,
+    pub strategy: Strategy,
+    /// The maximum number of targets that are reloaded simultaneously, unlimited if `None`.
+    pub max_parallel: Option<usize>,
+    /// Check that rewritten configuration files can be parsed before replacing the current ones.
+    pub safe: bool,
+    /// Update the terminal alco is run in, either by reloading its target or using escape
+    /// sequences.
+    pub detect_terminal: bool,
+    /// Notify running applications after their configuration files were rewritten.
+    pub notify: bool,
+    pub refresh: RefreshConfig,
+    pub gamma: GammaConfig,
+    /// The color filters that are applied to rendered configuration files, by name.
+    pub filters: Vec<(String, Filter)>,
+    /// Colors that are replaced in rendered configuration files, computed by the init script or
+    /// when dimming the background or overriding the accent color.
+    pub color_map: Vec<(Color, Color)>,
+    /// The color that replaces the accent role of the colorscheme.
+    pub accent: Option<Color>,
+    /// The window opacity between 0 and 1 set with `alco set opacity`, which is applied to the
+    /// targets that support transparency.
+    pub opacity: Option<f32>,
+    /// The terminal font of the profile, which overrides the font of the scheme files.
+    pub font: Font,
+    /// The init script, which is run if it exists.
+    pub script_file: PathBuf,
+    /// The directory containing the `<name>.tmpl` files of the templates.
+    pub template_dir: PathBuf,
+    pub templates: BTreeMap<String, TemplateConfig>,
+    /// The rules of the color roles, by role name.
+    pub roles: BTreeMap<String, String>,
+    /// The directory containing the `post-apply.d` directory of hook scripts.
+    pub hook_dir: PathBuf,
+    /// The audit log the changes of every apply are appended to, turned off if `None`.
+    pub audit_log: Option<PathBuf>,
+    pub alacritty: AlacrittyOptions,
+    pub kitty: KittyOptions,
+    pub tmux: TmuxOptions,
+    pub neovim: NeovimOptions,
+    pub starship: StarshipOptions,
+    pub bat: BatOptions,
+    pub delta: DeltaOptions,
+    pub cmus: CmusOptions,
+    pub wezterm: WeztermOptions,
+    pub foot: FootOptions,
+    pub zellij: ZellijOptions,
+    pub fish: FishOptions,
+    pub fzf: FzfOptions,
+    pub rofi: RofiOptions,
+    pub dunst: DunstOptions,
+    pub waybar: WaybarOptions,
+    pub polybar: PolybarOptions,
+    pub picom: PicomOptions,
+    pub i3: I3Options,
+    pub hyprland: HyprlandOptions,
+    pub gtk: GtkOptions,
+    pub qutebrowser: QutebrowserOptions,
+    pub vscode: VscodeOptions,
+    pub zathura: ZathuraOptions,
+    pub mako: MakoOptions,
+    pub ghostty: GhosttyOptions,
+    pub konsole: KonsoleOptions,
+    pub emacs: EmacsOptions,
+    pub vim: VimOptions,
+    pub xresources: XresourcesOptions,
+}
+
+#[derive(Clone, Hash)]
+pub struct AlacrittyOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub in_file: PathBuf,
+    pub selector: PathBuf,
+    /// Patterns of the color keys that are rewritten, like `colors.primary.*`, all if empty.
+    pub only_colors: Vec<String>,
+}
+
+#[derive(Clone, Hash)]
+pub struct KittyOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub socket: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct TmuxOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+    /// Colorschemes of tmux sessions by session name, which only recolor the status line and pane
+    /// borders of that session.
+    pub sessions: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Hash)]
+pub struct NeovimOptions {
+    pub reload: bool,
+    pub command: String,
+}
+
+#[derive(Clone, Hash)]
+pub struct StarshipOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub in_file: PathBuf,
+    pub selector: PathBuf,
+    /// Append a custom module showing the colorscheme name.
+    pub module: bool,
+}
+
+#[derive(Clone, Hash)]
+pub struct BatOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub in_file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct DeltaOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct CmusOptions {
+    pub reload: bool,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct WeztermOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+    /// The configuration file wezterm watches, which is touched to make it reload.
+    pub config: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct FootOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct ZellijOptions {
+    pub reload: bool,
+    /// The theme file in the zellij themes directory.
+    pub file: PathBuf,
+    pub selector: PathBuf,
+    /// The configuration file whose `theme` is set, which running sessions reload.
+    pub config: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct FishOptions {
+    pub reload: bool,
+    /// The theme file whose colors are set as universal variables.
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct FzfOptions {
+    pub reload: bool,
+    /// The shell snippet that sets the colors in `FZF_DEFAULT_OPTS`.
+    pub file: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct RofiOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct DunstOptions {
+    pub reload: bool,
+    /// The drop-in configuration file with the urgency colors.
+    pub file: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct WaybarOptions {
+    pub reload: bool,
+    /// The stylesheet with the color definitions, which the waybar style imports.
+    pub file: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct PolybarOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct PicomOptions {
+    pub reload: bool,
+    /// The configuration file with the opacity options, which the picom config includes.
+    pub file: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct I3Options {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct HyprlandOptions {
+    pub reload: bool,
+    /// The configuration file with the colors, which the Hyprland config sources.
+    pub file: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct GtkOptions {
+    pub reload: bool,
+    /// Maps colorschemes to GTK theme names.
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct QutebrowserOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct VscodeOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct ZathuraOptions {
+    pub reload: bool,
+    /// The configuration file with the colors, which the zathurarc includes.
+    pub file: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct MakoOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct GhosttyOptions {
+    pub reload: bool,
+    pub file: PathBuf,
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct KonsoleOptions {
+    pub reload: bool,
+    /// Maps colorschemes to Konsole profile names.
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct EmacsOptions {
+    pub reload: bool,
+    /// Maps colorschemes to Emacs theme names.
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct VimOptions {
+    pub reload: bool,
+    /// Maps colorschemes to Vim colorscheme names.
+    pub selector: PathBuf,
+}
+
+#[derive(Clone, Hash)]
+pub struct XresourcesOptions {
+    pub reload: bool,
+    /// The resource file with the colors, which is merged into the resource database.
+    pub file: PathBuf,
+}
+
+impl Default for Options {
+    /// The default files and directories, with all targets disabled.
+    fn default() -> Self {
+        Self::in_home(tilde("~").as_ref())
+    }
+}
+
+impl Options {
+    /// The default files and directories inside the home directory `home`, with all targets
+    /// disabled.
+    pub fn in_home(home: impl AsRef<Path>) -> Self {
+        let path = |p: &str| match p.strip_prefix("~/") {
+            Some(p) => home.as_ref().join(p),
+            None => PathBuf::from(p),
+        };
+        Self {
+            scheme_dir: path(crate::DEFAULT_SCHEME_DIR),
+            cache_dir: path(crate::DEFAULT_CACHE_DIR),
+            strategy: Strategy::default(),
+            max_parallel: None,
+            safe: false,
+            detect_terminal: false,
+            notify: true,
+            refresh: RefreshConfig::default(),
+            gamma: GammaConfig::default(),
+            filters: Vec::new(),
+            color_map: Vec::new(),
+            accent: None,
+            opacity: None,
+            font: Font::default(),
+            script_file: path(crate::DEFAULT_SCRIPT_FILE),
+            template_dir: path(crate::DEFAULT_TEMPLATE_DIR),
+            templates: BTreeMap::new(),
+            roles: BTreeMap::new(),
+            hook_dir: path(crate::DEFAULT_HOOK_DIR),
+            audit_log: None,
+            alacritty: AlacrittyOptions {
+                reload: false,
+                file: path(crate::DEFAULT_ALACRITTY_FILE),
+                in_file: path(crate::DEFAULT_ALACRITTY_IN_FILE),
+                selector: path(crate::DEFAULT_ALACRITTY_SELECTOR),
+                only_colors: Vec::new(),
+            },
+            kitty: KittyOptions {
+                reload: false,
+                file: path(crate::DEFAULT_KITTY_FILE),
+                socket: path(crate::DEFAULT_KITTY_SOCKET),
+                selector: path(crate::DEFAULT_KITTY_SELECTOR),
+            },
+            tmux: TmuxOptions {
+                reload: false,
+                file: path(crate::DEFAULT_TMUX_FILE),
+                selector: path(crate::DEFAULT_TMUX_SELECTOR),
+                sessions: BTreeMap::new(),
+            },
+            neovim: NeovimOptions {
+                reload: false,
+                command: crate::DEFAULT_NEOVIM_COMMAND.to_owned(),
+            },
+            starship: StarshipOptions {
+                reload: false,
+                file: path(crate::DEFAULT_STARSHIP_FILE),
+                in_file: path(crate::DEFAULT_STARSHIP_IN_FILE),
+                selector: path(crate::DEFAULT_STARSHIP_SELECTOR),
+                module: false,
+            },
+            bat: BatOptions {
+                reload: false,
+                file: path(crate::DEFAULT_BAT_FILE),
+                in_file: path(crate::DEFAULT_BAT_IN_FILE),
+                selector: path(crate::DEFAULT_BAT_SELECTOR),
+            },
+            delta: DeltaOptions {
+                reload: false,
+                file: path(crate::DEFAULT_DELTA_FILE),
+                selector: path(crate::DEFAULT_DELTA_SELECTOR),
+            },
+            cmus: CmusOptions {
+                reload: false,
+                selector: path(crate::DEFAULT_CMUS_SELECTOR),
+            },
+            wezterm: WeztermOptions {
+                reload: false,
+                file: path(crate::DEFAULT_WEZTERM_FILE),
+                selector: path(crate::DEFAULT_WEZTERM_SELECTOR),
+                config: path(crate::DEFAULT_WEZTERM_CONFIG),
+            },
+            foot: FootOptions {
+                reload: false,
+                file: path(crate::DEFAULT_FOOT_FILE),
+                selector: path(crate::DEFAULT_FOOT_SELECTOR),
+            },
+            zellij: ZellijOptions {
+                reload: false,
+                file: path(crate::DEFAULT_ZELLIJ_FILE),
+                selector: path(crate::DEFAULT_ZELLIJ_SELECTOR),
+                config: path(crate::DEFAULT_ZELLIJ_CONFIG),
+            },
+            fish: FishOptions {
+                reload: false,
+                file: path(crate::DEFAULT_FISH_FILE),
+                selector: path(crate::DEFAULT_FISH_SELECTOR),
+            },
+            fzf: FzfOptions { reload: false, file: path(crate::DEFAULT_FZF_FILE) },
+            rofi: RofiOptions {
+                reload: false,
+                file: path(crate::DEFAULT_ROFI_FILE),
+                selector: path(crate::DEFAULT_ROFI_SELECTOR),
+            },
+            dunst: DunstOptions {
+                reload: false,
+                file: path(crate::DEFAULT_DUNST_FILE),
+            },
+            waybar: WaybarOptions {
+                reload: false,
+                file: path(crate::DEFAULT_WAYBAR_FILE),
+            },
+            polybar: PolybarOptions {
+                reload: false,
+                file: path(crate::DEFAULT_POLYBAR_FILE),
+                selector: path(crate::DEFAULT_POLYBAR_SELECTOR),
+            },
+            picom: PicomOptions {
+                reload: false,
+                file: path(crate::DEFAULT_PICOM_FILE),
+            },
+            i3: I3Options {
+                reload: false,
+                file: path(crate::DEFAULT_I3_FILE),
+                selector: path(crate::DEFAULT_I3_SELECTOR),
+            },
+            hyprland: HyprlandOptions {
+                reload: false,
+                file: path(crate::DEFAULT_HYPRLAND_FILE),
+            },
+            gtk: GtkOptions {
+                reload: false,
+                selector: path(crate::DEFAULT_GTK_SELECTOR),
+            },
+            qutebrowser: QutebrowserOptions {
+                reload: false,
+                file: path(crate::DEFAULT_QUTEBROWSER_FILE),
+                selector: path(crate::DEFAULT_QUTEBROWSER_SELECTOR),
+            },
+            vscode: VscodeOptions {
+                reload: false,
+                file: path(crate::DEFAULT_VSCODE_FILE),
+                selector: path(crate::DEFAULT_VSCODE_SELECTOR),
+            },
+            zathura: ZathuraOptions {
+                reload: false,
+                file: path(crate::DEFAULT_ZATHURA_FILE),
+            },
+            mako: MakoOptions {
+                reload: false,
+                file: path(crate::DEFAULT_MAKO_FILE),
+                selector: path(crate::DEFAULT_MAKO_SELECTOR),
+            },
+            ghostty: GhosttyOptions {
+                reload: false,
+                file: path(crate::DEFAULT_GHOSTTY_FILE),
+                selector: path(crate::DEFAULT_GHOSTTY_SELECTOR),
+            },
+            konsole: KonsoleOptions {
+                reload: false,
+                selector: path(crate::DEFAULT_KONSOLE_SELECTOR),
+            },
+            emacs: EmacsOptions {
+                reload: false,
+                selector: path(crate::DEFAULT_EMACS_SELECTOR),
+            },
+            vim: VimOptions {
+                reload: false,
+                selector: path(crate::DEFAULT_VIM_SELECTOR),
+            },
+            xresources: XresourcesOptions {
+                reload: false,
+                file: path(crate::DEFAULT_XRESOURCES_FILE),
+            },
+        }
+    }
+
+    pub fn reload(&self, target: Target) -> bool {
+        match target {
+            Target::Alacritty => self.alacritty.reload,
+            Target::Kitty => self.kitty.reload,
+            Target::Tmux => self.tmux.reload,
+            Target::Neovim => self.neovim.reload,
+            Target::Starship => self.starship.reload,
+            Target::Bat => self.bat.reload,
+            Target::Delta => self.delta.reload,
+            Target::Cmus => self.cmus.reload,
+            Target::Wezterm => self.wezterm.reload,
+            Target::Foot => self.foot.reload,
+            Target::Zellij => self.zellij.reload,
+            Target::Fish => self.fish.reload,
+            Target::Fzf => self.fzf.reload,
+            Target::Rofi => self.rofi.reload,
+            Target::Dunst => self.dunst.reload,
+            Target::Waybar => self.waybar.reload,
+            Target::Polybar => self.polybar.reload,
+            Target::Picom => self.picom.reload,
+            Target::I3 => self.i3.reload,
+            Target::Hyprland => self.hyprland.reload,
+            Target::Gtk => self.gtk.reload,
+            Target::Qutebrowser => self.qutebrowser.reload,
+            Target::Vscode => self.vscode.reload,
+            Target::Zathura => self.zathura.reload,
+            Target::Mako => self.mako.reload,
+            Target::Ghostty => self.ghostty.reload,
+            Target::Konsole => self.konsole.reload,
+            Target::Emacs => self.emacs.reload,
+            Target::Vim => self.vim.reload,
+            Target::Xresources => self.xresources.reload,
+        }
+    }
+
+    pub fn set_reload(&mut self, target: Target, reload: bool) {
+        match target {
+            Target::Alacritty => self.alacritty.reload = reload,
+            Target::Kitty => self.kitty.reload = reload,
+            Target::Tmux => self.tmux.reload = reload,
+            Target::Neovim => self.neovim.reload = reload,
+            Target::Starship => self.starship.reload = reload,
+            Target::Bat => self.bat.reload = reload,
+            Target::Delta => self.delta.reload = reload,
+            Target::Cmus => self.cmus.reload = reload,
+            Target::Wezterm => self.wezterm.reload = reload,
+            Target::Foot => self.foot.reload = reload,
+            Target::Zellij => self.zellij.reload = reload,
+            Target::Fish => self.fish.reload = reload,
+            Target::Fzf => self.fzf.reload = reload,
+            Target::Rofi => self.rofi.reload = reload,
+            Target::Dunst => self.dunst.reload = reload,
+            Target::Waybar => self.waybar.reload = reload,
+            Target::Polybar => self.polybar.reload = reload,
+            Target::Picom => self.picom.reload = reload,
+            Target::I3 => self.i3.reload = reload,
+            Target::Hyprland => self.hyprland.reload = reload,
+            Target::Gtk => self.gtk.reload = reload,
+            Target::Qutebrowser => self.qutebrowser.reload = reload,
+            Target::Vscode => self.vscode.reload = reload,
+            Target::Zathura => self.zathura.reload = reload,
+            Target::Mako => self.mako.reload = reload,
+            Target::Ghostty => self.ghostty.reload = reload,
+            Target::Konsole => self.konsole.reload = reload,
+            Target::Emacs => self.emacs.reload = reload,
+            Target::Vim => self.vim.reload = reload,
+            Target::Xresources => self.xresources.reload = reload,
+        }
+    }
+
+    pub fn any_reload(&self) -> bool {
+        Target::ALL.iter().any(|t| self.reload(*t))
+    }
+
+    /// The targets that should be rendered, which are all targets if none is enabled.
+    pub fn render_targets(&self) -> Vec<Target> {
+        if self.any_reload() {
+            Target::ALL.into_iter().filter(|t| self.reload(*t)).collect()
+        } else {
+            Target::ALL.to_vec()
+        }
+    }
+
+    /// The configuration file that is written for a target, `None` if the target doesn't have
+    /// one.
+    pub fn file(&self, target: Target) -> Option<PathBuf> {
+        match target {
+            Target::Alacritty => Some(self.alacritty.file.clone()),
+            Target::Kitty => Some(self.kitty.file.clone()),
+            Target::Tmux => Some(self.tmux.file.clone()),
+            Target::Neovim => None,
+            Target::Starship => Some(self.starship.file.clone()),
+            Target::Bat => Some(self.bat.file.clone()),
+            Target::Delta => Some(self.delta.file.clone()),
+            Target::Cmus => Some(PathBuf::from(tilde(crate::CMUS_AUTOSAVE_FILE).as_ref())),
+            Target::Wezterm => Some(self.wezterm.file.clone()),
+            Target::Foot => Some(self.foot.file.clone()),
+            Target::Zellij => Some(self.zellij.file.clone()),
+            Target::Fish => Some(self.fish.file.clone()),
+            Target::Fzf => Some(self.fzf.file.clone()),
+            Target::Rofi => Some(self.rofi.file.clone()),
+            Target::Dunst => Some(self.dunst.file.clone()),
+            Target::Xresources => Some(self.xresources.file.clone()),
+            Target::Waybar => Some(self.waybar.file.clone()),
+            Target::Polybar => Some(self.polybar.file.clone()),
+            Target::Picom => Some(self.picom.file.clone()),
+            Target::I3 => Some(self.i3.file.clone()),
+            Target::Hyprland => Some(self.hyprland.file.clone()),
+            Target::Gtk | Target::Konsole | Target::Emacs | Target::Vim => None,
+            Target::Qutebrowser => Some(self.qutebrowser.file.clone()),
+            Target::Vscode => Some(self.vscode.file.clone()),
+            Target::Zathura => Some(self.zathura.file.clone()),
+            Target::Mako => Some(self.mako.file.clone()),
+            Target::Ghostty => Some(self.ghostty.file.clone()),
+        }
+    }
+
+    /// The selector file of a target, `None` if the target doesn't use one.
+    pub fn selector(&self, target: Target) -> Option<&Path> {
+        match target {
+            Target::Alacritty => Some(&self.alacritty.selector),
+            Target::Kitty => Some(&self.kitty.selector),
+            Target::Tmux => Some(&self.tmux.selector),
+            Target::Neovim => None,
+            Target::Starship => Some(&self.starship.selector),
+            Target::Bat => Some(&self.bat.selector),
+            Target::Delta => Some(&self.delta.selector),
+            Target::Cmus => Some(&self.cmus.selector),
+            Target::Wezterm => Some(&self.wezterm.selector),
+            Target::Foot => Some(&self.foot.selector),
+            Target::Zellij => Some(&self.zellij.selector),
+            Target::Fish => Some(&self.fish.selector),
+            Target::Fzf => None,
+            Target::Rofi => Some(&self.rofi.selector),
+            Target::Dunst | Target::Waybar | Target::Picom | Target::Xresources => None,
+            Target::Polybar => Some(&self.polybar.selector),
+            Target::I3 => Some(&self.i3.selector),
+            Target::Hyprland | Target::Zathura => None,
+            Target::Gtk => Some(&self.gtk.selector),
+            Target::Qutebrowser => Some(&self.qutebrowser.selector),
+            Target::Vscode => Some(&self.vscode.selector),
+            Target::Mako => Some(&self.mako.selector),
+            Target::Ghostty => Some(&self.ghostty.selector),
+            Target::Konsole => Some(&self.konsole.selector),
+            Target::Emacs => Some(&self.emacs.selector),
+            Target::Vim => Some(&self.vim.selector),
+        }
+    }
+
+    /// The files a rendered configuration file depends on. Cached files that are older than one
+    /// of these are rendered again.
+    fn inputs(&self, target: Target, colorscheme: &str) -> Vec<PathBuf> {
+        let mut inputs = vec![crate::scheme_file(&self.scheme_dir, colorscheme)];
+        match target {
+            Target::Alacritty => {
+                inputs.push(self.alacritty.in_file.clone());
+                inputs.extend(self.selector_inputs(&self.alacritty.selector, colorscheme));
+            }
+            Target::Kitty => inputs.extend(self.selector_inputs(&self.kitty.selector, colorscheme)),
+            Target::Tmux => inputs.extend(self.selector_inputs(&self.tmux.selector, colorscheme)),
+            Target::Neovim => (),
+            Target::Starship => {
+                inputs.push(self.starship.in_file.clone());
+                inputs.extend(self.selector_inputs(&self.starship.selector, colorscheme));
+            }
+            Target::Bat => inputs.extend([self.bat.in_file.clone(), self.bat.selector.clone()]),
+            Target::Delta => inputs.extend(self.selector_inputs(&self.delta.selector, colorscheme)),
+            Target::Cmus => {
+                // the autosave file also contains all other settings of cmus
+                let autosave = self.file(target).unwrap_or_default();
+                inputs.extend([self.cmus.selector.clone(), autosave]);
+                // the selector maps to the name of a cmus theme, not to a file
+                if let Some(theme) = self.selected(&self.cmus.selector, colorscheme) {
+                    let theme_file = format!("{}.theme", theme);
+                    inputs.push(PathBuf::from(tilde("~/.config/cmus").as_ref()).join(&theme_file));
+                    inputs.push(Path::new("/usr/share/cmus").join(theme_file));
+                }
+            }
+            Target::Wezterm => {
+                inputs.extend(self.selector_inputs(&self.wezterm.selector, colorscheme))
+            }
+            Target::Foot => inputs.extend(self.selector_inputs(&self.foot.selector, colorscheme)),
+            Target::Zellij => {
+                inputs.extend(self.selector_inputs(&self.zellij.selector, colorscheme))
+            }
+            Target::Fish => inputs.extend(self.selector_inputs(&self.fish.selector, colorscheme)),
+            Target::Fzf => (),
+            Target::Rofi => inputs.extend(self.selector_inputs(&self.rofi.selector, colorscheme)),
+            Target::Dunst | Target::Waybar | Target::Picom | Target::Xresources => (),
+            Target::Polybar => {
+                inputs.extend(self.selector_inputs(&self.polybar.selector, colorscheme))
+            }
+            Target::I3 => inputs.extend(self.selector_inputs(&self.i3.selector, colorscheme)),
+            Target::Hyprland | Target::Zathura => (),
+            Target::Gtk => inputs.push(self.gtk.selector.clone()),
+            Target::Konsole => inputs.push(self.konsole.selector.clone()),
+            Target::Emacs => inputs.push(self.emacs.selector.clone()),
+            Target::Vim => inputs.push(self.vim.selector.clone()),
+            Target::Qutebrowser => {
+                inputs.extend(self.selector_inputs(&self.qutebrowser.selector, colorscheme))
+            }
+            Target::Vscode => {
+                // the settings file also contains all other settings of VS Code
+                let settings = self.vscode.file.clone();
+                inputs.extend([self.vscode.selector.clone(), settings])
+            }
+            Target::Mako => {
+                // the configuration file also contains all other settings of mako
+                inputs.push(self.mako.file.clone());
+                inputs.extend(self.selector_inputs(&self.mako.selector, colorscheme));
+            }
+            Target::Ghostty => {
+                // the configuration file also contains all other settings of ghostty
+                let config = self.ghostty.file.clone();
+                inputs.extend([self.ghostty.selector.clone(), config])
+            }
+        }
+        inputs
+    }
+
+    /// The selector and the file it maps the colorscheme to, which is read when rendering.
+    fn selector_inputs(&self, selector: &Path, colorscheme: &str) -> Vec<PathBuf> {
+        let mut inputs = vec![selector.to_owned()];
+        if let Some(file) = self.selected(selector, colorscheme) {
+            inputs.push(PathBuf::from(tilde(&file).as_ref()));
+        }
+        inputs
+    }
+
+    /// The value a selector maps the colorscheme to, `None` if the selector can't be read or has
+    /// no mapping.
+    fn selected(&self, selector: &Path, colorscheme: &str) -> Option<String> {
+        let selector_str = fs::read_to_string(selector).ok()?;
+        let selector = YamlLoader::load_from_str(&selector_str).ok()?.into_iter().next()?;
+        crate::selector(&selector, &self.scheme_dir, colorscheme)
+    }
+
+    /// The path of a prerendered configuration file inside the cache directory. Files rendered with
+    /// different options are kept apart by the [`Options::render_fingerprint`].
+    pub fn cache_file(&self, target: Target, colorscheme: &str) -> Option<PathBuf> {
+        let file = self.file(target)?;
+        let file_name = file.file_name()?;
+        let dir_name = format!("{}+{:016x}", colorscheme, self.render_fingerprint());
+        Some(self.cache_dir.join(dir_name).join(target.name()).join(file_name))
+    }
+
+    /// A hash of every option that can change a rendered configuration file. The options are
+    /// destructured without `..`, so a new option has to be hashed or explicitly skipped here.
+    /// The hash may change with the Rust version, which only makes prerendered files miss.
+    fn render_fingerprint(&self) -> u64 {
+        let mut opts = self.clone();
+        // which targets are reloaded doesn't change what is rendered
+        for t in Target::ALL {
+            opts.set_reload(t, false);
+        }
+        let Options {
+            scheme_dir,
+            cache_dir: _,
+            strategy: _,
+            max_parallel: _,
+            safe: _,
+            detect_terminal: _,
+            notify: _,
+            refresh: _,
+            gamma: _,
+            filters,
+            color_map,
+            accent,
+            opacity,
+            font: Font { family, size },
+            // the init script is run before rendering and fills the color map
+            script_file: _,
+            template_dir: _,
+            templates: _,
+            roles,
+            hook_dir: _,
+            audit_log: _,
+            alacritty,
+            kitty,
+            tmux,
+            neovim,
+            starship,
+            bat,
+            delta,
+            cmus,
+            wezterm,
+            foot,
+            zellij,
+            fish,
+            fzf,
+            rofi,
+            dunst,
+            waybar,
+            polybar,
+            picom,
+            i3,
+            hyprland,
+            gtk,
+            qutebrowser,
+            vscode,
+            zathura,
+            mako,
+            ghostty,
+            konsole,
+            emacs,
+            vim,
+            xresources,
+        } = &opts;
+
+        let mut hasher = DefaultHasher::new();
+        scheme_dir.hash(&mut hasher);
+        for (name, Filter { warmth, brightness }) in filters.iter() {
+            name.hash(&mut hasher);
+            warmth.to_bits().hash(&mut hasher);
+            brightness.to_bits().hash(&mut hasher);
+        }
+        color_map.hash(&mut hasher);
+        accent.hash(&mut hasher);
+        opacity.map(f32::to_bits).hash(&mut hasher);
+        family.hash(&mut hasher);
+        size.map(f32::to_bits).hash(&mut hasher);
+        roles.hash(&mut hasher);
+        // targets also read the selectors of others, like the alacritty one for the colors
+        alacritty.hash(&mut hasher);
+        kitty.hash(&mut hasher);
+        tmux.hash(&mut hasher);
+        neovim.hash(&mut hasher);
+        starship.hash(&mut hasher);
+        bat.hash(&mut hasher);
+        delta.hash(&mut hasher);
+        cmus.hash(&mut hasher);
+        wezterm.hash(&mut hasher);
+        foot.hash(&mut hasher);
+        zellij.hash(&mut hasher);
+        fish.hash(&mut hasher);
+        fzf.hash(&mut hasher);
+        rofi.hash(&mut hasher);
+        dunst.hash(&mut hasher);
+        waybar.hash(&mut hasher);
+        polybar.hash(&mut hasher);
+        picom.hash(&mut hasher);
+        i3.hash(&mut hasher);
+        hyprland.hash(&mut hasher);
+        gtk.hash(&mut hasher);
+        qutebrowser.hash(&mut hasher);
+        vscode.hash(&mut hasher);
+        zathura.hash(&mut hasher);
+        mako.hash(&mut hasher);
+        ghostty.hash(&mut hasher);
+        konsole.hash(&mut hasher);
+        emacs.hash(&mut hasher);
+        vim.hash(&mut hasher);
+        xresources.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Whether rendered colors are changed by filters or the init script.
+    fn maps_colors(&self) -> bool {
+        !self.filters.is_empty() || !self.color_map.is_empty()
+    }
+
+    /// Darkens the background color of a colorscheme in rendered configuration files, by adding
+    /// it to the color map. `brightness` is between 0 and 1.
+    pub fn dim_background(&mut self, colorscheme: &str, brightness: f32) -> anyhow::Result<()> {
+        if brightness >= 1.0 {
+            return Ok(());
+        }
+
+        let scheme = crate::load_colorscheme(self, colorscheme)?;
+        if let Some(bg) = scheme.background {
+            let dimmed = Filter { warmth: 0.0, brightness: brightness - 1.0 }.apply(bg);
+            self.color_map.push((bg, dimmed));
+        }
+        Ok(())
+    }
+
+    /// Replaces the color of the accent role with `accent` in rendered configuration files, by
+    /// adding it to the color map, and makes it the accent role of templates and hooks. Entries of
+    /// the init script are overruled.
+    pub fn override_accent(&mut self, colorscheme: &str) -> anyhow::Result<()> {
+        let accent = match self.accent {
+            Some(a) => a,
+            None => return Ok(()),
+        };
+
+        let scheme = crate::load_colorscheme(self, colorscheme)?;
+        if let Some(from) = crate::color_roles(&scheme, &self.roles)?.get("accent") {
+            self.color_map.insert(0, (*from, accent));
+        }
+        self.roles.insert("accent".to_owned(), accent.to_string());
+        Ok(())
+    }
+
+    /// Replaces a color using the color map of the init script and applies the filters.
+    pub fn map_color(&self, color: Color) -> Color {
+        let color = match self.color_map.iter().find(|(from, _)| *from == color) {
+            Some((_, to)) => *to,
+            None => color,
+        };
+        self.filters.iter().fold(color, |c, (_, f)| f.apply(c))
+    }
+
+    /// Returns the prerendered configuration file of a target, if it exists and is up to date.
+    fn cached(&self, target: Target, colorscheme: &str) -> Option<PathBuf> {
+        // filters and the init script aren't tracked
+        if self.maps_colors() {
+            return None;
+        }
+
+        let cache_file = self.cache_file(target, colorscheme)?;
+        let cached = fs::metadata(&cache_file).and_then(|m| m.modified()).ok()?;
+
+        let outdated = self.inputs(target, colorscheme).iter().any(|i| {
+            let modified = fs::metadata(i).and_then(|m| m.modified());
+            modified.map(|m| m > cached).unwrap_or(false)
+        });
+
+        (!outdated).then_some(cache_file)
+    }
+}
+
+/// Renders the configuration file of a target, `None` if the target doesn't have one.
+pub fn render_target(
+    opts: &Options,
+    target: Target,
+    colorscheme: &str,
+) -> anyhow::Result<Option<String>> {
+    let scheme_dir = &opts.scheme_dir;
+    let rendered = match target {
+        Target::Alacritty => {
+            let o = &opts.alacritty;
+            crate::render_alacritty(
+                &o.in_file,
+                &o.selector,
+                scheme_dir,
+                colorscheme,
+                &o.only_colors,
+            )?
+        }
+        Target::Kitty => crate::render_kitty(&opts.kitty.selector, scheme_dir, colorscheme)?,
+        Target::Tmux => crate::render_tmux(&opts.tmux.selector, scheme_dir, colorscheme)?,
+        Target::Neovim | Target::Gtk | Target::Konsole | Target::Emacs | Target::Vim => {
+            return Ok(None)
+        }
+        Target::Starship => {
+            let o = &opts.starship;
+            let mut rendered =
+                crate::render_starship(&o.in_file, &o.selector, scheme_dir, colorscheme)?;
+            // a second definition would make the configuration invalid
+            if o.module && !rendered.contains("[custom.alco]") {
+                let colors = crate::load_colorscheme(opts, colorscheme).ok();
+                rendered.push_str(&crate::starship_module(colorscheme, colors.as_ref()));
+            }
+            rendered
+        }
+        Target::Bat => {
+            crate::render_bat(&opts.bat.in_file, &opts.bat.selector, scheme_dir, colorscheme)?
+        }
+        Target::Delta => crate::render_delta(&opts.delta.selector, scheme_dir, colorscheme)?,
+        Target::Cmus => crate::render_cmus(&opts.cmus.selector, scheme_dir, colorscheme)?,
+        Target::Wezterm => crate::render_wezterm(&opts.wezterm.selector, scheme_dir, colorscheme)?,
+        Target::Foot => crate::render_foot(&opts.foot.selector, scheme_dir, colorscheme)?,
+        Target::Zellij => crate::render_zellij(&opts.zellij.selector, scheme_dir, colorscheme)?,
+        Target::Fish => crate::render_fish(&opts.fish.selector, scheme_dir, colorscheme)?,
+        Target::Fzf => crate::render_fzf(&crate::load_colorscheme(opts, colorscheme)?)?,
+        Target::Rofi => crate::render_rofi(&opts.rofi.selector, scheme_dir, colorscheme)?,
+        Target::Dunst => crate::render_dunst(&crate::load_colorscheme(opts, colorscheme)?)?,
+        Target::Xresources => {
+            crate::render_xresources(&crate::load_colorscheme(opts, colorscheme)?)?
+        }
+        Target::Waybar => {
+            let colors = crate::load_colorscheme(opts, colorscheme)?;
+            crate::render_waybar(&colors, &crate::color_roles(&colors, &opts.roles)?)?
+        }
+        Target::Polybar => crate::render_polybar(&opts.polybar.selector, scheme_dir, colorscheme)?,
+        Target::Picom => crate::render_picom(opts.opacity)?,
+        Target::I3 => crate::render_i3(
+            &opts.i3.selector,
+            scheme_dir,
+            colorscheme,
+            &crate::load_colorscheme(opts, colorscheme)?,
+        )?,
+        Target::Hyprland => {
+            // the rgb() colors aren't found when mapping the rendered colors
+            let colors = crate::load_colorscheme(opts, colorscheme)?.map(|c| opts.map_color(c));
+            crate::render_hyprland(&colors, &crate::color_roles(&colors, &opts.roles)?)?
+        }
+        Target::Qutebrowser => {
+            crate::render_qutebrowser(&opts.qutebrowser.selector, scheme_dir, colorscheme)?
+        }
+        Target::Vscode => {
+            let o = &opts.vscode;
+            crate::render_vscode(&o.file, &o.selector, scheme_dir, colorscheme)?
+        }
+        Target::Zathura => {
+            let colors = crate::load_colorscheme(opts, colorscheme)?;
+            crate::render_zathura(&colors, &crate::color_roles(&colors, &opts.roles)?)?
+        }
+        Target::Mako => {
+            crate::render_mako(&opts.mako.file, &opts.mako.selector, scheme_dir, colorscheme)?
+        }
+        Target::Ghostty => {
+            let o = &opts.ghostty;
+            crate::render_ghostty(&o.file, &o.selector, scheme_dir, colorscheme)?
+        }
+    };
+    let rendered = match opts.opacity {
+        Some(opacity) => set_opacity(target, rendered, opacity)?,
+        None => rendered,
+    };
+    let mut rendered = match target {
+        Target::Alacritty | Target::Kitty => {
+            set_font(target, rendered, &crate::font::font(opts, colorscheme)?)?
+        }
+        _ => rendered,
+    };
+    if target.managed_block() {
+        if !rendered.is_empty() && !rendered.ends_with('\n') {
+            rendered.push('\n');
+        }
+        rendered = format!("{}\n{}{}\n", BLOCK_BEGIN, rendered, BLOCK_END);
+    }
+
+    if opts.maps_colors() {
+        Ok(Some(crate::map_colors(&rendered, |c| opts.map_color(c))))
+    } else {
+        Ok(Some(rendered))
+    }
+}
+
+/// Sets the background opacity in a rendered configuration file of a terminal. Wezterm can't read
+/// it from a colorscheme file, so it is added as a top level `window_background_opacity` value,
+/// which the wezterm config has to read itself.
+fn set_opacity(target: Target, mut rendered: String, opacity: f32) -> anyhow::Result<String> {
+    match target {
+        Target::Alacritty => return crate::alacritty_opacity(&rendered, opacity),
+        Target::Kitty => {
+            if !rendered.is_empty() && !rendered.ends_with('\n') {
+                rendered.push('\n');
+            }
+            rendered.push_str(&format!("background_opacity {:?}\n", opacity));
+        }
+        Target::Wezterm => {
+            rendered.insert_str(0, &format!("window_background_opacity = {:?}\n\n", opacity));
+        }
+        _ => (),
+    }
+    Ok(rendered)
+}
+
+/// Sets the font family and size in a rendered configuration file of a terminal.
+fn set_font(target: Target, mut rendered: String, font: &Font) -> anyhow::Result<String> {
+    if font.is_empty() {
+        return Ok(rendered);
+    }
+
+    match target {
+        Target::Alacritty => return crate::alacritty_font(&rendered, font),
+        Target::Kitty => {
+            if !rendered.is_empty() && !rendered.ends_with('\n') {
+                rendered.push('\n');
+            }
+            if let Some(family) = &font.family {
+                rendered.push_str(&format!("font_family {}\n", family));
+            }
+            if let Some(size) = font.size {
+                rendered.push_str(&format!("font_size {:?}\n", size));
+            }
+        }
+        _ => (),
+    }
+    Ok(rendered)
+}
+
+/// Renders the configuration files of the [`Options::render_targets`] into
+/// `<dir>/<target>/<file name>`.
+pub fn render_to_dir(opts: &Options, colorscheme: &str, dir: impl AsRef<Path>) -> RenderResults {
+    opts.render_targets()
+        .into_iter()
+        .filter_map(|target| {
+            let file = opts.file(target)?;
+            let result = render_target(opts, target, colorscheme).and_then(|rendered| {
+                let target_dir = dir.as_ref().join(target.name());
+                let path = target_dir.join(file.file_name().unwrap_or(target.name().as_ref()));
+                fs::create_dir_all(&target_dir)?;
+                fs::write(&path, rendered.unwrap_or_default())?;
+                Ok(path)
+            });
+            Some((target, result))
+        })
+        .collect()
+}
+
+/// The changes reloading a target would make, without making them.
+#[derive(Clone, Debug, Serialize)]
+pub struct Change {
+    /// The configuration file that would be written, `None` if the target doesn't have one.
+    pub path: Option<PathBuf>,
+    /// The diff between the current and the rendered configuration file.
+    pub hunks: Vec<Hunk>,
+    /// The commands that would be run to make running instances reload, as shell commands.
+    pub commands: Vec<String>,
+}
+
+/// What the selector of a target maps a colorscheme to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    /// The value of the entry of the colorscheme.
+    Entry(String),
+    /// The value of the `else` entry, since the colorscheme has none.
+    Else(String),
+    /// Neither the colorscheme nor `else` have an entry.
+    Missing,
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Resolution::Entry(v) => write!(f, "{}", v),
+            Resolution::Else(v) => write!(f, "missing → else → {}", v),
+            Resolution::Missing => write!(f, "missing → else → none"),
+        }
+    }
+}
+
+/// Looks up a colorscheme in the selectors of the targets that would be rendered, without
+/// rendering anything. Targets without a selector are left out.
+pub fn resolve(opts: &Options, colorscheme: &str) -> Vec<(Target, anyhow::Result<Resolution>)> {
+    opts.render_targets()
+        .into_iter()
+        .filter_map(|target| {
+            let selector = opts.selector(target)?;
+            let resolution = fs::read_to_string(selector)
+                .map_err(|_| anyhow!("Error reading {} selector", target))
+                .and_then(|s| Ok(YamlLoader::load_from_str(&s)?))
+                .map(|docs| {
+                    let selector = docs.into_iter().next().unwrap_or(yaml_rust::Yaml::Null);
+                    let entry = crate::selector_entry(&selector, colorscheme);
+                    let expand = |v| crate::expand_placeholders(v, &opts.scheme_dir, colorscheme);
+                    match entry.and_then(|(v, default)| Some((expand(v)?, default))) {
+                        Some((v, false)) => Resolution::Entry(v),
+                        Some((v, true)) => Resolution::Else(v),
+                        None => Resolution::Missing,
+                    }
+                });
+            Some((target, resolution))
+        })
+        .collect()
+}
+
+/// Computes the changes applying a colorscheme would make to the enabled targets, for
+/// `alco apply --dry-run`.
+pub fn plan(opts: &Options, colorscheme: &str) -> Vec<(Target, anyhow::Result<Change>)> {
+    Target::ALL
+        .into_iter()
+        .filter(|t| opts.reload(*t))
+        .map(|target| {
+            let change = render_target(opts, target, colorscheme).map(|rendered| {
+                let path = opts.file(target);
+                let edited =
+                    path.as_deref().filter(|_| target.managed_block()).and_then(edited_file);
+                let rendered = match (rendered, edited) {
+                    (Some(r), Some(e)) => Some(replace_block(&e, &r)),
+                    (rendered, _) => rendered,
+                };
+                let hunks = match (&path, &rendered) {
+                    (Some(p), Some(r)) => {
+                        crate::diff(&fs::read_to_string(p).unwrap_or_default(), r)
+                    }
+                    _ => Vec::new(),
+                };
+                let commands = commands(opts, target, colorscheme, rendered.as_deref());
+                Change { path, hunks, commands }
+            });
+            (target, change)
+        })
+        .collect()
+}
+
+/// The commands [`notify`] runs for a target, as shell commands. Targets that are reloaded through
+/// a socket or by changing files don't run any.
+fn commands(
+    opts: &Options,
+    target: Target,
+    colorscheme: &str,
+    rendered: Option<&str>,
+) -> Vec<String> {
+    if !opts.notify {
+        return Vec::new();
+    }
+    let mapping = || {
+        let selector = fs::read_to_string(opts.selector(target)?).ok()?;
+        let selector = YamlLoader::load_from_str(&selector).ok()?.into_iter().next()?;
+        crate::selector(&selector, &opts.scheme_dir, colorscheme)
+    };
+    let quote = |s: &str| format!("'{}'", s.replace('\'', "'\\''"));
+
+    let command = match target {
+        Target::Kitty if opts.kitty.socket.exists() => {
+            Some(format!("kill -s USR1 <pid of {}>", opts.kitty.socket.display()))
+        }
+        Target::Tmux => {
+            Some(format!("tmux source-file {}", quote(&opts.tmux.file.to_string_lossy())))
+        }
+        Target::Cmus => {
+            mapping().map(|m| format!("cmus-remote -C {}", quote(&format!("colorscheme {}", m))))
+        }
+        Target::Bat => {
+            mapping().filter(|m| m.ends_with(".tmTheme")).map(|_| "bat cache --build".to_owned())
+        }
+        Target::Fish => {
+            let script = crate::fish::fish_script(rendered.unwrap_or_default());
+            (!script.is_empty()).then(|| format!("fish --no-config -c {}", quote(&script)))
+        }
+        Target::Dunst => Some("dunstctl reload".to_owned()),
+        Target::Xresources => {
+            Some(format!("xrdb -merge {}", quote(&opts.xresources.file.to_string_lossy())))
+        }
+        Target::Waybar => Some("pkill -USR2 -x waybar".to_owned()),
+        Target::Polybar => Some("polybar-msg cmd restart".to_owned()),
+        Target::Picom => Some("pkill -USR1 -x picom".to_owned()),
+        Target::I3 => match std::env::var_os("SWAYSOCK") {
+            Some(_) => Some("swaymsg reload".to_owned()),
+            None => Some("i3-msg reload".to_owned()),
+        },
+        Target::Hyprland => {
+            let batch = crate::hyprland::hyprctl_batch(rendered.unwrap_or_default());
+            (!batch.is_empty()).then(|| format!("hyprctl --batch {}", quote(&batch)))
+        }
+        Target::Qutebrowser => {
+            let command = format!(":config-source {}", opts.qutebrowser.file.display());
+            Some(format!("qutebrowser {}", quote(&command)))
+        }
+        Target::Zathura => {
+            let method = "/org/pwmt/zathura org.pwmt.zathura.ExecuteCommand";
+            return crate::zathura::zathura_commands(rendered.unwrap_or_default())
+                .into_iter()
+                .map(|c| {
+                    let arg = quote(&format!("string:{}", c));
+                    format!(
+                        "dbus-send --session --type=method_call --dest=<zathura instance> {} {}",
+                        method, arg
+                    )
+                })
+                .collect();
+        }
+        Target::Mako => Some("makoctl reload".to_owned()),
+        Target::Ghostty => Some("pkill -USR2 -x ghostty".to_owned()),
+        Target::Konsole => {
+            let profile = match mapping() {
+                Some(p) => quote(&p),
+                None => return Vec::new(),
+            };
+            let session = "/Sessions/<session> org.kde.konsole.Session.setProfile";
+            let window = "/Windows/<window> org.kde.konsole.Window.setDefaultProfile";
+            return [session, window]
+                .map(|method| format!("qdbus <konsole instance> {} {}", method, profile))
+                .to_vec();
+        }
+        Target::Emacs => mapping()
+            .map(|t| format!("emacsclient --eval {}", quote(&crate::emacs::load_theme(&t)))),
+        Target::Vim => mapping().map(|c| {
+            format!(
+                "vim --servername <vim server> --remote-send {}",
+                quote(&crate::vim::remote_keys(&c))
+            )
+        }),
+        Target::Gtk => {
+            let settings = crate::gtk_settings(&opts.gtk.selector, &opts.scheme_dir, colorscheme);
+            return settings
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(k, v)| format!("gsettings set {} {} {}", crate::gtk::SCHEMA, k, quote(&v)))
+                .collect();
+        }
+        _ => None,
+    };
+    command.into_iter().collect()
+}
+
+/// Renders the configuration files of all colorschemes into the cache directory, so applying a
+/// colorscheme only has to copy them.
+pub fn prerender(
+    colors_file: impl AsRef<Path>,
+    opts: &Options,
+) -> anyhow::Result<Vec<(String, RenderResults)>> {
+    let colorschemes = crate::list(colors_file)?;
+    let results = colorschemes
+        .into_iter()
+        .map(|c| {
+            let results = render_to_dir(opts, &c, opts.cache_dir.join(&c));
+            (c, results)
+        })
+        .collect();
+    Ok(results)
+}
+
+/// Reloads all enabled targets concurrently.
+pub async fn reload_targets(
+    opts: Arc<Options>,
+    colorscheme: &str,
+) -> Vec<(Target, anyhow::Result<()>)> {
+    let start = Instant::now();
+    let permits = opts.max_parallel.unwrap_or(Semaphore::MAX_PERMITS).max(1);
+    let semaphore = Arc::new(Semaphore::new(permits));
+
+    let tasks = Target::ALL
+        .into_iter()
+        .filter(|t| opts.reload(*t))
+        .map(|t| {
+            let opts = Arc::clone(&opts);
+            let semaphore = Arc::clone(&semaphore);
+            let colorscheme = colorscheme.to_owned();
+            let task = tokio::spawn(async move {
+                let _permit = semaphore.acquire().await?;
+                let start = Instant::now();
+                let result = reload_target(&opts, t, &colorscheme).await;
+                crate::metrics::record_reload(t, result.is_err(), start.elapsed());
+                result
+            });
+            (t, task)
+        })
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::with_capacity(tasks.len());
+    for (t, task) in tasks.into_iter() {
+        let result = match task.await {
+            Ok(r) => r,
+            Err(e) => Err(anyhow!(e)),
+        };
+        results.push((t, result));
+    }
+    crate::metrics::record_apply(start.elapsed());
+    results
+}
+
+async fn reload_target(opts: &Options, target: Target, colorscheme: &str) -> anyhow::Result<()> {
+    let file = match opts.file(target) {
+        Some(f) => f,
+        None if !opts.notify => return Ok(()),
+        None => return notify(opts, target, colorscheme).await,
+    };
+
+    // the cmus autosave and VS Code settings files are rewritten by the applications themselves
+    // and can't be symlinks, neither can the mako and ghostty configurations the user edits
+    let strategy = match target {
+        Target::Cmus | Target::Vscode | Target::Mako | Target::Ghostty => Strategy::Copy,
+        _ => opts.strategy,
+    };
+
+    // the lines around the block of a file the user edited are kept, so it can't be replaced
+    let edited = target.managed_block().then(|| edited_file(&file)).flatten();
+    if let Some(edited) = edited {
+        let rendered = match opts.cached(target, colorscheme) {
+            Some(cache_file) => fs::read_to_string(cache_file)?,
+            None => render_target(opts, target, colorscheme)?.unwrap_or_default(),
+        };
+        let content = replace_block(&edited, &rendered);
+        validate(opts, target, &file, &content)?;
+        fs::write(&file, content)?;
+        return match opts.notify {
+            true => notify(opts, target, colorscheme).await,
+            false => Ok(()),
+        };
+    }
+
+    match (strategy, opts.cached(target, colorscheme)) {
+        (Strategy::Copy, Some(cache_file)) => {
+            validate_file(opts, target, &file, &cache_file)?;
+            remove_symlink(&file)?;
+            fs::copy(cache_file, &file)?;
+        }
+        (Strategy::Copy, None) => {
+            let rendered = render_target(opts, target, colorscheme)?.unwrap_or_default();
+            validate(opts, target, &file, &rendered)?;
+            remove_symlink(&file)?;
+            fs::write(&file, rendered)?;
+        }
+        (Strategy::Symlink, Some(cache_file)) => {
+            validate_file(opts, target, &file, &cache_file)?;
+            link(cache_file, &file)?;
+        }
+        (Strategy::Symlink, None) => {
+            let rendered = render_target(opts, target, colorscheme)?.unwrap_or_default();
+            validate(opts, target, &file, &rendered)?;
+            let cache_file = opts.cache_file(target, colorscheme).unwrap_or_default();
+            if let Some(dir) = cache_file.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(&cache_file, rendered)?;
+            link(cache_file, &file)?;
+        }
+    }
+
+    match opts.notify {
+        true => notify(opts, target, colorscheme).await,
+        false => Ok(()),
+    }
+}
+
+/// The contents of a configuration file that contains a managed block and isn't a symlink to a
+/// cache file, `None` otherwise. Lines outside the block can only have been added by the user.
+fn edited_file(file: &Path) -> Option<String> {
+    if fs::symlink_metadata(file).ok()?.file_type().is_symlink() {
+        return None;
+    }
+    let content = fs::read_to_string(file).ok()?;
+    let lines = content.lines().map(str::trim_end).collect::<Vec<_>>();
+    let begin = lines.iter().position(|l| *l == BLOCK_BEGIN)?;
+    let end = lines[begin..].iter().position(|l| *l == BLOCK_END)? + begin;
+    // a file that only contains the block is replaced as a whole
+    (begin > 0 || end + 1 < lines.len()).then_some(content)
+}
+
+/// Replaces the managed block of `content` with `block`, which includes the begin and end lines.
+fn replace_block(content: &str, block: &str) -> String {
+    let mut out = String::with_capacity(content.len() + block.len());
+    let mut lines = content.split_inclusive('\n');
+    for line in lines.by_ref() {
+        if line.trim_end() == BLOCK_BEGIN {
+            break;
+        }
+        out.push_str(line);
+    }
+    out.push_str(block);
+    for line in lines.by_ref() {
+        if line.trim_end() == BLOCK_END {
+            break;
+        }
+    }
+    out.extend(lines);
+    out
+}
+
+/// In safe mode, checks that the configuration file `file` would be replaced with can be parsed,
+/// so that an invalid file is never reloaded. Only yaml and toml files are checked.
+fn validate(opts: &Options, target: Target, file: &Path, content: &str) -> anyhow::Result<()> {
+    if !opts.safe {
+        return Ok(());
+    }
+
+    let result = match file.extension().and_then(|e| e.to_str()) {
+        Some("yml" | "yaml") => {
+            serde_yaml::from_str::<serde_yaml::Value>(content).map(|_| ()).map_err(|e| anyhow!(e))
+        }
+        Some("toml") => content.parse::<toml::Table>().map(|_| ()).map_err(|e| anyhow!(e)),
+        _ => Ok(()),
+    };
+    result
+        .map_err(|e| anyhow!("Refusing to reload {}, the rendered file is invalid:\n{}", target, e))
+}
+
+fn validate_file(
+    opts: &Options,
+    target: Target,
+    file: &Path,
+    cache_file: &Path,
+) -> anyhow::Result<()> {
+    if !opts.safe {
+        return Ok(());
+    }
+    validate(opts, target, file, &fs::read_to_string(cache_file)?)
+}
+
+/// Atomically replaces `file` with a symlink pointing to `cache_file`.
+fn link(cache_file: impl AsRef<Path>, file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let cache_file = fs::canonicalize(cache_file)?;
+    let file = file.as_ref();
+    let file_name = file.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let tmp_file = file.with_file_name(format!(".{}.alco-tmp", file_name));
+
+    if fs::symlink_metadata(&tmp_file).is_ok() {
+        fs::remove_file(&tmp_file)?;
+    }
+    symlink(cache_file, &tmp_file)?;
+    fs::rename(tmp_file, file)?;
+
+    Ok(())
+}
+
+/// Removes `file` if it is a symlink, so writing to it doesn't modify the cache.
+fn remove_symlink(file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let is_symlink = fs::symlink_metadata(file.as_ref()).map(|m| m.is_symlink()).unwrap_or(false);
+    if is_symlink {
+        fs::remove_file(file)?;
+    }
+    Ok(())
+}
+
+/// Recolors only the terminal alco is running in, without touching any configuration files. Inside
+/// tmux only the current pane is recolored, unless alco is running on a remote host, in which case
+/// the local terminal is recolored through tmux. Inside a neovim terminal that neovim instance is
+/// reloaded as well.
+pub async fn apply_here(opts: &Options, colorscheme: &str) -> anyhow::Result<()> {
+    let scheme = crate::load_colorscheme(opts, colorscheme)?.map(|c| opts.map_color(c));
+    match env::var("TMUX_PANE") {
+        Ok(pane) if !crate::is_ssh_session() => crate::style_tmux_pane(&pane, &scheme)?,
+        _ => crate::write_osc(&scheme, crate::Passthrough::detect())?,
+    }
+
+    if let Ok(socket) = env::var("NVIM") {
+        crate::reload_neovim_instance(socket, &opts.neovim.command, colorscheme).await?;
+    }
+
+    Ok(())
+}
+
+/// Performs a non-destructive connectivity check with the running instances of a target and returns
+/// the time it took.
+pub async fn ping(opts: &Options, target: Target) -> anyhow::Result<Duration> {
+    let start = Instant::now();
+    match target {
+        Target::Kitty => crate::ping_kitty(&opts.kitty.socket)?,
+        Target::Tmux => crate::ping_tmux()?,
+        Target::Neovim => {
+            if crate::ping_neovim().await? == 0 {
+                bail!("No neovim instances running");
+            }
+        }
+        Target::Cmus => crate::ping_cmus()?,
+        Target::Vim => crate::ping_vim()?,
+        Target::Alacritty
+        | Target::Starship
+        | Target::Bat
+        | Target::Delta
+        | Target::Wezterm
+        | Target::Foot
+        | Target::Zellij
+        | Target::Fish
+        | Target::Fzf
+        | Target::Rofi
+        | Target::Dunst
+        | Target::Waybar
+        | Target::Polybar
+        | Target::Picom
+        | Target::I3
+        | Target::Hyprland
+        | Target::Gtk
+        | Target::Konsole
+        | Target::Emacs
+        | Target::Qutebrowser
+        | Target::Vscode
+        | Target::Zathura
+        | Target::Mako
+        | Target::Ghostty
+        | Target::Xresources => {
+            bail!("{} doesn't support remote control", target)
+        }
+    }
+    Ok(start.elapsed())
+}
+
+/// Makes running instances of a target pick up the new configuration.
+async fn notify(opts: &Options, target: Target, colorscheme: &str) -> anyhow::Result<()> {
+    match target {
+        Target::Kitty => crate::notify_kitty(&opts.kitty.socket),
+        Target::Tmux => {
+            crate::notify_tmux(&opts.tmux.file)?;
+            style_tmux_sessions(opts)
+        }
+        Target::Neovim => crate::reload_neovim(&opts.neovim.command).await,
+        Target::Cmus => crate::notify_cmus(&opts.cmus.selector, &opts.scheme_dir, colorscheme),
+        Target::Wezterm => crate::notify_wezterm(&opts.wezterm.config),
+        Target::Foot => crate::notify_foot(&opts.foot.file),
+        Target::Zellij => crate::notify_zellij(&opts.zellij.file, &opts.zellij.config),
+        Target::Fish => crate::notify_fish(&opts.fish.file),
+        Target::Bat => {
+            crate::notify_bat(&opts.bat.file, &opts.bat.selector, &opts.scheme_dir, colorscheme)
+        }
+        Target::Dunst => crate::notify_dunst(),
+        Target::Xresources => crate::notify_xresources(&opts.xresources.file),
+        Target::Waybar => crate::notify_waybar(),
+        Target::Polybar => crate::notify_polybar(),
+        Target::Picom => crate::notify_picom(),
+        Target::I3 => crate::notify_i3(),
+        Target::Hyprland => crate::notify_hyprland(&opts.hyprland.file),
+        Target::Gtk => crate::reload_gtk(&opts.gtk.selector, &opts.scheme_dir, colorscheme),
+        Target::Konsole => {
+            crate::reload_konsole(&opts.konsole.selector, &opts.scheme_dir, colorscheme)
+        }
+        Target::Emacs => crate::reload_emacs(&opts.emacs.selector, &opts.scheme_dir, colorscheme),
+        Target::Vim => crate::reload_vim(&opts.vim.selector, &opts.scheme_dir, colorscheme),
+        Target::Qutebrowser => crate::notify_qutebrowser(&opts.qutebrowser.file),
+        Target::Zathura => crate::notify_zathura(&opts.zathura.file),
+        Target::Mako => crate::notify_mako(),
+        Target::Ghostty => crate::notify_ghostty(),
+        Target::Alacritty
+        | Target::Starship
+        | Target::Delta
+        | Target::Fzf
+        | Target::Rofi
+        | Target::Vscode => Ok(()),
+    }
+}
+
+/// Styles the running tmux sessions that have their own colorscheme, after the global options
+/// were set by sourcing the configuration file.
+fn style_tmux_sessions(opts: &Options) -> anyhow::Result<()> {
+    if opts.tmux.sessions.is_empty() {
+        return Ok(());
+    }
+
+    for session in crate::tmux_sessions()? {
+        if let Some(colorscheme) = opts.tmux.sessions.get(&session) {
+            let scheme = crate::load_colorscheme(opts, colorscheme)?.map(|c| opts.map_color(c));
+            crate::style_tmux_session(&session, &scheme)?;
+        }
+    }
+    Ok(())
+}
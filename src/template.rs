@@ -0,0 +1,119 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::colorscheme::COLOR_NAMES;
+use crate::{Color, Colorscheme, Options, TemplateConfig};
+
+/// Renders the templates of the settings file into their output files and runs their reload
+/// commands. Returns the result of each template.
+pub fn render_templates(opts: &Options, colorscheme: &str) -> Vec<(String, anyhow::Result<()>)> {
+    if opts.templates.is_empty() {
+        return Vec::new();
+    }
+
+    let colors = crate::load_colorscheme(opts, colorscheme)
+        .map(|c| c.map(|c| opts.map_color(c)))
+        .and_then(|c| Ok((crate::color_roles(&c, &opts.roles)?, c)));
+    opts.templates
+        .iter()
+        .map(|(name, template)| {
+            let result = match &colors {
+                Ok((roles, c)) => render_to_output(opts, name, template, colorscheme, c, roles),
+                Err(e) => Err(anyhow!("{}", e)),
+            };
+            (name.clone(), result)
+        })
+        .collect()
+}
+
+fn render_to_output(
+    opts: &Options,
+    name: &str,
+    template: &TemplateConfig,
+    colorscheme: &str,
+    colors: &Colorscheme,
+    roles: &BTreeMap<String, Color>,
+) -> anyhow::Result<()> {
+    let file = opts.template_dir.join(format!("{}.tmpl", name));
+    let template_str = fs::read_to_string(&file)
+        .map_err(|_| anyhow!("Error reading template {}", file.display()))?;
+    let rendered = render_template(&template_str, colorscheme, colors, roles)?;
+
+    let output = PathBuf::from(tilde(&template.output).as_ref());
+    if let Some(dir) = output.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&output, rendered)
+        .map_err(|e| anyhow!("Error writing {}:\n{}", output.display(), e))?;
+
+    if let (Some(command), true) = (&template.command, opts.notify) {
+        let output = Command::new("sh").arg("-c").arg(command).output()?;
+        if !output.status.success() {
+            bail!(
+                "Error running reload command:\n{}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Replaces the `{{colors.<section>.<key>}}` placeholders of a template with the colors of the
+/// alacritty `colors` section they name, `{{roles.<role>}}` with the color of a role and `{{name}}`
+/// with the name of the colorscheme.
+pub fn render_template(
+    template: &str,
+    colorscheme: &str,
+    colors: &Colorscheme,
+    roles: &BTreeMap<String, Color>,
+) -> anyhow::Result<String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(e) => start + e,
+            None => bail!("Unclosed placeholder in template"),
+        };
+        rendered.push_str(&rest[..start]);
+
+        let key = rest[start + 2..end].trim();
+        match key {
+            "name" => rendered.push_str(colorscheme),
+            _ if key.starts_with("roles.") => match roles.get(&key["roles.".len()..]) {
+                Some(c) => rendered.push_str(&c.to_string()),
+                None => bail!("{} has no color for {}", colorscheme, key),
+            },
+            _ => match color(colors, key) {
+                Some(Some(c)) => rendered.push_str(&c.to_string()),
+                Some(None) => bail!("{} has no color {}", colorscheme, key),
+                None => bail!("Unknown placeholder {} in template", key),
+            },
+        }
+        rest = &rest[end + 2..];
+    }
+    rendered.push_str(rest);
+
+    Ok(rendered)
+}
+
+/// The color at an alacritty style path like `colors.normal.red`, `None` if there is no such key.
+pub(crate) fn color(colors: &Colorscheme, key: &str) -> Option<Option<Color>> {
+    match key.split('.').collect::<Vec<_>>().as_slice() {
+        ["colors", "primary", "foreground"] => Some(colors.foreground),
+        ["colors", "primary", "background"] => Some(colors.background),
+        ["colors", "cursor", "cursor"] => Some(colors.cursor),
+        ["colors", section @ ("normal" | "bright"), name] => {
+            let i = COLOR_NAMES.iter().position(|n| n == name)?;
+            match *section {
+                "normal" => Some(colors.normal[i]),
+                _ => Some(colors.bright[i]),
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,107 @@
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal};
+
+use crate::{Options, Target};
+
+/// Terminals alco can detect it's running in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Terminal {
+    Alacritty,
+    Kitty,
+    WezTerm,
+    Foot,
+}
+
+/// How the colors of a running terminal are updated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delivery {
+    /// Rewrite the configuration file of the terminal, which updates all its windows.
+    Target(Target),
+    /// Write escape sequences, which only updates the current session.
+    Osc,
+}
+
+impl Terminal {
+    /// Detects the terminal alco is running in using the environment variables it sets. These are
+    /// inherited by tmux and other programs running inside the terminal.
+    pub fn detect() -> Option<Self> {
+        let var = |name: &str| env::var(name).unwrap_or_default();
+
+        if env::var_os("KITTY_WINDOW_ID").is_some() {
+            return Some(Self::Kitty);
+        }
+        if env::var_os("ALACRITTY_WINDOW_ID").is_some() || env::var_os("ALACRITTY_SOCKET").is_some()
+        {
+            return Some(Self::Alacritty);
+        }
+        if env::var_os("WEZTERM_PANE").is_some() || var("TERM_PROGRAM") == "WezTerm" {
+            return Some(Self::WezTerm);
+        }
+
+        match var("TERM").as_str() {
+            "alacritty" => Some(Self::Alacritty),
+            "xterm-kitty" => Some(Self::Kitty),
+            "wezterm" => Some(Self::WezTerm),
+            t if t.starts_with("foot") => Some(Self::Foot),
+            _ => None,
+        }
+    }
+
+    /// The target rewriting the configuration file of the terminal, if alco supports it.
+    pub fn target(&self) -> Option<Target> {
+        match self {
+            Self::Alacritty => Some(Target::Alacritty),
+            Self::Kitty => Some(Target::Kitty),
+            Self::WezTerm | Self::Foot => None,
+        }
+    }
+
+    /// Chooses the best way to update the colors of the terminal. The configuration file is
+    /// rewritten if the terminal's target is set up, otherwise escape sequences are used.
+    pub fn delivery(&self, opts: &Options) -> Delivery {
+        let target = match self.target() {
+            Some(t) => t,
+            None => return Delivery::Osc,
+        };
+
+        let selector_exists = opts.selector(target).is_some_and(|s| s.exists());
+        // kitty is reloaded using remote control, alacritty has to watch its configuration file
+        let reachable = match target {
+            Target::Kitty => opts.kitty.socket.exists(),
+            Target::Alacritty => crate::alacritty_live_reload(&opts.alacritty.file),
+            _ => true,
+        };
+
+        match selector_exists && reachable {
+            true => Delivery::Target(target),
+            false => Delivery::Osc,
+        }
+    }
+}
+
+/// Chooses how the terminal alco is running in is updated, `None` if alco isn't run interactively,
+/// for example by a key binding, or the terminal isn't detected. Inside an ssh session the
+/// configuration files belong to the remote host, so escape sequences are sent to the local
+/// terminal, whichever it is.
+pub fn terminal_delivery(opts: &Options) -> Option<Delivery> {
+    if !io::stdin().is_terminal() {
+        return None;
+    }
+    if crate::is_ssh_session() {
+        return Some(Delivery::Osc);
+    }
+    Terminal::detect().map(|t| t.delivery(opts))
+}
+
+impl fmt::Display for Terminal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Alacritty => "alacritty",
+            Self::Kitty => "kitty",
+            Self::WezTerm => "wezterm",
+            Self::Foot => "foot",
+        };
+        f.write_str(name)
+    }
+}
@@ -0,0 +1,169 @@
+//! Helpers for integration tests that run alco against a temporary home directory.
+//!
+//! ```no_run
+//! use alco::test_util::FixtureHome;
+//! use alco::Target;
+//!
+//! let home = FixtureHome::new().unwrap();
+//! home.colorschemes(&["dark", "light"]).unwrap();
+//! home.write(".config/tmux/colors/dark.conf", "set -g status-style bg=black").unwrap();
+//! home.selector(Target::Tmux, &[("dark", ".config/tmux/colors/dark.conf")]).unwrap();
+//!
+//! let mut opts = home.options();
+//! opts.tmux.reload = true;
+//! home.apply(&opts, "dark").unwrap();
+//! home.assert_file(".config/tmux/colors/current.conf", "set -g status-style bg=black");
+//! ```
+
+use anyhow::anyhow;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::{Options, Target};
+
+static FIXTURE_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// A temporary directory standing in for `$HOME`, which is removed when dropped.
+///
+/// The cmus target always uses the real home directory, since its autosave file location is fixed.
+pub struct FixtureHome {
+    dir: PathBuf,
+}
+
+impl FixtureHome {
+    pub fn new() -> anyhow::Result<Self> {
+        let count = FIXTURE_COUNT.fetch_add(1, Ordering::Relaxed);
+        let dir = env::temp_dir().join(format!("alco-test-{}-{}", std::process::id(), count));
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)
+            .map_err(|e| anyhow!("Error creating fixture home directory:\n{}", e))?;
+        Ok(Self { dir })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Resolves a path relative to the fixture home, `~/` prefixes are stripped.
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        let path = path.as_ref();
+        self.dir.join(path.strip_prefix("~").unwrap_or(path))
+    }
+
+    /// Writes `contents` to the file at `path`, creating missing parent directories.
+    pub fn write(
+        &self,
+        path: impl AsRef<Path>,
+        contents: impl AsRef<str>,
+    ) -> anyhow::Result<PathBuf> {
+        let path = self.join(path);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, contents.as_ref())
+            .map_err(|e| anyhow!("Error writing fixture file {}:\n{}", path.display(), e))?;
+        Ok(path)
+    }
+
+    pub fn read(&self, path: impl AsRef<Path>) -> anyhow::Result<String> {
+        let path = self.join(path);
+        fs::read_to_string(&path)
+            .map_err(|e| anyhow!("Error reading fixture file {}:\n{}", path.display(), e))
+    }
+
+    /// The colorscheme list file at its default location.
+    pub fn colors_file(&self) -> PathBuf {
+        self.join(crate::DEFAULT_COLORSCHEME_FILE)
+    }
+
+    /// The state file at its default location.
+    pub fn state_file(&self) -> PathBuf {
+        self.join(crate::DEFAULT_CONFIG_FILE)
+    }
+
+    /// Writes the colorscheme list.
+    pub fn colorschemes(&self, colorschemes: &[&str]) -> anyhow::Result<PathBuf> {
+        let mut contents = String::from("colors:\n");
+        for c in colorschemes {
+            contents.push_str(&format!("- {}\n", c));
+        }
+        self.write(self.colors_file(), contents)
+    }
+
+    /// Writes the selector file of `target`, mapping colorschemes to files inside the fixture
+    /// home. Use `else` as the colorscheme for the fallback entry.
+    pub fn selector(&self, target: Target, entries: &[(&str, &str)]) -> anyhow::Result<PathBuf> {
+        let selector = self.options().selector(target).map(Path::to_path_buf);
+        let selector = selector.ok_or_else(|| anyhow!("{} has no selector file", target))?;
+
+        let mut contents = String::new();
+        for (colorscheme, file) in entries {
+            let file = match target {
+                // bat and cmus selectors contain theme names instead of paths
+                Target::Bat | Target::Cmus => PathBuf::from(file),
+                _ => self.join(file),
+            };
+            contents.push_str(&format!("{}: \"{}\"\n", colorscheme, file.display()));
+        }
+        self.write(selector, contents)
+    }
+
+    /// Options with all default files and directories inside the fixture home, all targets
+    /// disabled and notifications of running applications turned off.
+    pub fn options(&self) -> Options {
+        let mut opts = Options::in_home(&self.dir);
+        opts.notify = false;
+        opts
+    }
+
+    /// Runs the same pipeline as `alco apply`: updates the state file and reloads all enabled
+    /// targets. Missing parent directories of the target files are created.
+    pub fn apply(
+        &self,
+        opts: &Options,
+        colorscheme: &str,
+    ) -> anyhow::Result<Vec<(Target, anyhow::Result<()>)>> {
+        for target in Target::ALL.into_iter().filter(|t| opts.reload(*t)) {
+            let file = opts.file(target).filter(|f| f.starts_with(&self.dir));
+            if let Some(dir) = file.as_ref().and_then(|f| f.parent()) {
+                fs::create_dir_all(dir)?;
+            }
+        }
+
+        crate::apply(self.colors_file(), self.state_file(), colorscheme.to_owned())?;
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let results = runtime.block_on(crate::reload_targets(Arc::new(opts.clone()), colorscheme));
+        Ok(results)
+    }
+
+    /// Panics if the file at `path` doesn't exist or its contents differ from `expected`.
+    #[track_caller]
+    pub fn assert_file(&self, path: impl AsRef<Path>, expected: impl AsRef<str>) {
+        let path = path.as_ref();
+        match self.read(path) {
+            Ok(actual) => {
+                assert_eq!(actual, expected.as_ref(), "unexpected contents of {}", path.display())
+            }
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// The colorscheme recorded in the state file.
+    pub fn current(&self) -> anyhow::Result<String> {
+        let state = crate::status(self.state_file())?;
+        Ok(state.current)
+    }
+}
+
+impl Drop for FixtureHome {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
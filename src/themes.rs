@@ -0,0 +1,306 @@
+use anyhow::{anyhow, bail};
+use serde_json::Value;
+use shellexpand::tilde;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::editor::set_entries;
+use crate::pack::read_selector;
+use crate::{Color, Options, Target};
+
+/// Proposals scoring lower than this are left out.
+const MIN_SCORE: f32 = 0.5;
+/// The average perceptual distance at which palettes are considered completely different.
+const MAX_PALETTE_DISTANCE: f32 = 25.0;
+
+/// A theme proposed for a colorscheme by [`generate_selector`].
+pub struct Proposal {
+    pub colorscheme: String,
+    /// The theme that matches best and its score, `None` if no theme scored high enough.
+    pub theme: Option<(String, f32)>,
+}
+
+/// Directories with themes of a target, besides the directory of its configuration file.
+fn theme_dirs(target: Target) -> &'static [&'static str] {
+    match target {
+        Target::Alacritty => &["~/.config/alacritty/themes"],
+        Target::Kitty => &["~/.config/kitty/themes"],
+        Target::Cmus => &["/usr/share/cmus", "~/.config/cmus"],
+        Target::Gtk => &["/usr/share/themes", "~/.themes", "~/.local/share/themes"],
+        Target::Vscode => &["~/.vscode/extensions", "~/.vscode-oss/extensions"],
+        Target::Mako => &["~/.config/mako/themes"],
+        Target::Ghostty => &["/usr/share/ghostty/themes", "~/.config/ghostty/themes"],
+        Target::Konsole => &["~/.local/share/konsole"],
+        Target::Emacs => &["~/.emacs.d/themes", "~/.config/emacs/themes"],
+        _ => &[],
+    }
+}
+
+/// The values the selector of a target can map colorschemes to, found by looking for installed
+/// themes. These are the names of cmus colorschemes, GTK themes, bat themes, VS Code color themes,
+/// ghostty themes, Konsole profiles, Emacs themes and vim colorschemes, and the paths of theme
+/// files for all other targets, like the files next to the configuration file.
+pub fn selector_values(opts: &Options, target: Target) -> anyhow::Result<Vec<String>> {
+    if opts.selector(target).is_none() {
+        bail!("{} doesn't use a selector", target);
+    }
+    installed_themes(opts, target)
+}
+
+/// The installed themes of a target, which includes the neovim and vim colorschemes listed by
+/// `nvim --headless` and `vim -es`.
+fn installed_themes(opts: &Options, target: Target) -> anyhow::Result<Vec<String>> {
+    let dirs = theme_dirs(target).iter().map(|d| PathBuf::from(tilde(d).as_ref()));
+    let mut values: Vec<String> = match target {
+        Target::Neovim => {
+            let list =
+                "lua io.stdout:write(table.concat(vim.fn.getcompletion('', 'color'), '\\n'))";
+            lines(Command::new("nvim").args(["--headless", "-c", list, "-c", "qa"]))?
+        }
+        Target::Vim => {
+            let list = "call writefile(getcompletion('', 'color'), '/dev/stdout')";
+            lines(Command::new("vim").args(["-Nu", "NONE", "-es", "-c", list, "-c", "qa!"]))?
+        }
+        Target::Bat => lines(Command::new("bat").args(["--list-themes", "--color=never"]))?,
+        Target::Cmus => dirs
+            .flat_map(|d| entries(&d))
+            .filter(|p| p.extension().is_some_and(|e| e == "theme"))
+            .filter_map(|p| Some(p.file_stem()?.to_str()?.to_owned()))
+            .collect(),
+        Target::Konsole => dirs
+            .flat_map(|d| entries(&d))
+            .filter(|p| p.extension().is_some_and(|e| e == "profile"))
+            .filter_map(|p| Some(p.file_stem()?.to_str()?.to_owned()))
+            .collect(),
+        Target::Emacs => dirs
+            .flat_map(|d| entries(&d))
+            .filter_map(|p| Some(p.file_name()?.to_str()?.strip_suffix("-theme.el")?.to_owned()))
+            .collect(),
+        Target::Gtk => dirs
+            .flat_map(|d| entries(&d))
+            .filter(|p| p.join("index.theme").exists())
+            .filter_map(|p| Some(p.file_name()?.to_str()?.to_owned()))
+            .collect(),
+        Target::Vscode => dirs.flat_map(|d| entries(&d)).flat_map(|p| vscode_themes(&p)).collect(),
+        Target::Ghostty => dirs
+            .flat_map(|d| entries(&d))
+            .filter(|p| p.is_file())
+            .filter_map(|p| Some(p.file_name()?.to_str()?.to_owned()))
+            .collect(),
+        _ => {
+            let file = opts.file(target).unwrap_or_default();
+            let siblings = file.parent().map(entries).unwrap_or_default();
+            let siblings =
+                siblings.into_iter().filter(|p| *p != file && p.extension() == file.extension());
+            siblings
+                .chain(dirs.flat_map(|d| entries(&d)))
+                .filter(|p| p.is_file())
+                .map(|p| contract_home(&p))
+                .collect()
+        }
+    };
+    values.sort();
+    values.dedup();
+    Ok(values)
+}
+
+/// The non-empty lines a command prints.
+fn lines(command: &mut Command) -> anyhow::Result<Vec<String>> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command.output().map_err(|e| anyhow!("Error running {}:\n{}", program, e))?;
+    if !output.status.success() {
+        bail!(
+            "Error listing {} themes:\n{}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_owned).collect())
+}
+
+fn entries(dir: &Path) -> Vec<PathBuf> {
+    match fs::read_dir(dir) {
+        Ok(e) => e.flatten().map(|e| e.path()).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// The ids or labels of the color themes an extension contributes.
+fn vscode_themes(extension: &Path) -> Vec<String> {
+    let manifest = match fs::read_to_string(extension.join("package.json")) {
+        Ok(m) => m,
+        Err(_) => return Vec::new(),
+    };
+    let manifest: Value = serde_json::from_str(&manifest).unwrap_or_default();
+    let themes = manifest["contributes"]["themes"].as_array().into_iter().flatten();
+    themes.filter_map(|t| t["id"].as_str().or(t["label"].as_str())).map(str::to_owned).collect()
+}
+
+/// Replaces the home directory at the start of `path` with `~`.
+pub(crate) fn contract_home(path: &Path) -> String {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    match home.as_deref().and_then(|h| path.strip_prefix(h).ok()) {
+        Some(p) => format!("~/{}", p.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Proposes the installed theme of a target that matches each colorscheme best, with a score
+/// between 0 and 1. The score is the similarity of the names, averaged with the similarity of the
+/// palettes for theme files containing `#rrggbb` colors. Colorschemes without a theme scoring at
+/// least 0.5 get no proposal.
+pub fn generate_selector(
+    opts: &Options,
+    target: Target,
+    colorschemes: &[String],
+) -> anyhow::Result<Vec<Proposal>> {
+    let themes: Vec<(String, Vec<Color>)> = installed_themes(opts, target)?
+        .into_iter()
+        .map(|t| {
+            let palette = match t.contains('/') {
+                true => fs::read_to_string(tilde(&t).as_ref()).map(|s| hex_colors(&s)),
+                false => Ok(Vec::new()),
+            };
+            (t, palette.unwrap_or_default())
+        })
+        .collect();
+
+    let proposals = colorschemes
+        .iter()
+        .map(|colorscheme| {
+            let scheme = crate::load_colorscheme(opts, colorscheme).ok();
+            let colors: Vec<Color> =
+                scheme.iter().flat_map(|s| s.colors().map(|(_, c)| c)).collect();
+            let best = themes
+                .iter()
+                .map(|(theme, palette)| {
+                    let name = name_similarity(colorscheme, theme_name(theme));
+                    let score = match palette_distance(&colors, palette) {
+                        Some(d) => (name + 1.0 - (d / MAX_PALETTE_DISTANCE).min(1.0)) / 2.0,
+                        None => name,
+                    };
+                    (theme.clone(), score)
+                })
+                .filter(|(_, score)| *score >= MIN_SCORE)
+                .max_by(|(_, a), (_, b)| a.total_cmp(b));
+            Proposal { colorscheme: colorscheme.clone(), theme: best }
+        })
+        .collect();
+    Ok(proposals)
+}
+
+/// Adds entries to the selector file of a target for the colorschemes that don't have one yet.
+/// Returns the number of added entries.
+pub fn add_selector_entries(
+    opts: &Options,
+    target: Target,
+    entries: &[(String, String)],
+) -> anyhow::Result<usize> {
+    let path = match opts.selector(target) {
+        Some(p) => p,
+        None => bail!("{} doesn't use a selector", target),
+    };
+    let existing: BTreeMap<String, String> = match path.exists() {
+        true => read_selector(path)?.into_iter().collect(),
+        false => BTreeMap::new(),
+    };
+    let changes: BTreeMap<String, Option<String>> = entries
+        .iter()
+        .filter(|(k, _)| !existing.contains_key(k))
+        .map(|(k, v)| (k.clone(), Some(v.clone())))
+        .collect();
+    if changes.is_empty() {
+        return Ok(0);
+    }
+
+    let selector = fs::read_to_string(path).unwrap_or_default();
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, set_entries(&selector, &changes)?)
+        .map_err(|e| anyhow!("Error writing {}:\n{}", path.display(), e))?;
+    Ok(changes.len())
+}
+
+/// The file name without extension of theme files, the name itself otherwise.
+fn theme_name(theme: &str) -> &str {
+    let name = theme.rsplit('/').next().unwrap_or(theme);
+    match name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => name,
+    }
+}
+
+/// The Sørensen-Dice coefficient of the character bigrams of both names, ignoring case and
+/// everything but letters and digits, so `gruvbox-dark` and `GruvboxDark` are the same.
+fn name_similarity(a: &str, b: &str) -> f32 {
+    let bigrams = |s: &str| {
+        let chars: Vec<char> =
+            s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect::<Vec<_>>()
+    };
+    let (a, mut b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let total = a.len() + b.len();
+    let mut shared = 0;
+    for bigram in a {
+        if let Some(i) = b.iter().position(|x| *x == bigram) {
+            b.swap_remove(i);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f32 / total as f32
+}
+
+/// The `#rrggbb` colors of a theme file.
+fn hex_colors(theme: &str) -> Vec<Color> {
+    theme
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+        .filter(|w| w.starts_with('#'))
+        .filter_map(|w| w.parse().ok())
+        .collect()
+}
+
+/// The average perceptual distance of the colors to their nearest palette color, `None` if either
+/// is empty.
+fn palette_distance(colors: &[Color], palette: &[Color]) -> Option<f32> {
+    if colors.is_empty() || palette.is_empty() {
+        return None;
+    }
+    let total: f32 =
+        colors.iter().map(|c| palette.iter().map(|p| c.distance(p)).fold(f32::MAX, f32::min)).sum();
+    Some(total / colors.len() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(name_similarity("gruvbox-dark", "GruvboxDark"), 1.0);
+        assert!(name_similarity("gruvbox-dark", "gruvbox_dark_hard") > 0.7);
+        assert!(name_similarity("gruvbox-dark", "nord") < 0.2);
+        assert_eq!(theme_name("~/.config/kitty/themes/Gruvbox_Dark.conf"), "Gruvbox_Dark");
+        assert_eq!(theme_name("Monokai Extended"), "Monokai Extended");
+    }
+
+    #[test]
+    fn palettes() {
+        let theme = "background #282828\nforeground #ebdbb2\ncolor1 #cc241d # red\n";
+        let palette = hex_colors(theme);
+        assert_eq!(palette.len(), 3);
+
+        let same: Vec<Color> = ["#282828", "#ebdbb2"].map(|c| c.parse().unwrap()).to_vec();
+        assert_eq!(palette_distance(&same, &palette), Some(0.0));
+        assert_eq!(palette_distance(&same, &[]), None);
+    }
+}
@@ -6,21 +6,180 @@ use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+use crate::Colorscheme;
+
 pub fn reload_tmux(
     config_file: impl AsRef<Path>,
     selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
     colorscheme: impl AsRef<str>,
 ) -> anyhow::Result<()> {
+    let config = render_tmux(selector, scheme_dir, colorscheme)?;
+    fs::write(config_file.as_ref(), config)?;
+    notify_tmux(config_file)
+}
+
+/// Redraws the status line of all attached tmux clients.
+pub fn refresh_tmux() -> anyhow::Result<()> {
+    let output = Command::new("tmux").arg("refresh-client").arg("-S").output()?;
+    if !output.status.success() {
+        bail!("Error refreshing tmux clients");
+    }
+    Ok(())
+}
+
+/// Recolors a single tmux pane using its pane style and palette, leaving other panes untouched.
+pub fn style_tmux_pane(pane: &str, colorscheme: &Colorscheme) -> anyhow::Result<()> {
+    let mut style = Vec::new();
+    if let Some(fg) = colorscheme.foreground {
+        style.push(format!("fg={}", fg));
+    }
+    if let Some(bg) = colorscheme.background {
+        style.push(format!("bg={}", bg));
+    }
+
+    let mut cmd = Command::new("tmux");
+    cmd.args(["select-pane", "-t", pane, "-P", &style.join(",")]);
+    for (i, c) in colorscheme.ansi().enumerate() {
+        if let Some(c) = c {
+            let option = format!("pane-colours[{}]", i);
+            cmd.args([";", "set-option", "-p", "-t", pane, &option, &c.to_string()]);
+        }
+    }
+
+    let output = cmd.output()?;
+    if !output.status.success() {
+        bail!("Error styling tmux pane:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Resets the pane style and palette [`style_tmux_pane`] set, so the pane uses the colors of its
+/// window again.
+pub(crate) fn unstyle_tmux_pane(pane: &str) -> anyhow::Result<()> {
+    let mut cmd = Command::new("tmux");
+    cmd.args(["set-option", "-p", "-u", "-t", pane, "window-style"]);
+    cmd.args([";", "set-option", "-p", "-u", "-t", pane, "window-active-style"]);
+    cmd.args([";", "set-option", "-p", "-u", "-t", pane, "pane-colours"]);
+
+    let output = cmd.output()?;
+    if !output.status.success() {
+        bail!("Error resetting tmux pane:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Splits a tmux pane horizontally without selecting the new pane, which runs `command`. Returns
+/// the id of the new pane.
+pub(crate) fn split_tmux_pane(pane: &str, command: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("tmux")
+        .args(["split-window", "-h", "-d", "-P", "-F", "#{pane_id}", "-t", pane])
+        .args(command)
+        .output()?;
+    if !output.status.success() {
+        bail!("Error splitting tmux pane:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+pub(crate) fn kill_tmux_pane(pane: &str) -> anyhow::Result<()> {
+    let output = Command::new("tmux").args(["kill-pane", "-t", pane]).output()?;
+    if !output.status.success() {
+        bail!("Error closing tmux pane:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Recolors the status line and pane borders of a single tmux session using session and window
+/// options, which take precedence over the global options of the configuration file.
+pub fn style_tmux_session(session: &str, colorscheme: &Colorscheme) -> anyhow::Result<()> {
+    // `=` only matches the exact session name
+    let target = format!("={}:", session);
+    let output = Command::new("tmux")
+        .args(["list-windows", "-t", &target, "-F", "#{window_id}"])
+        .output()?;
+    if !output.status.success() {
+        bail!("Error listing windows of tmux session {}", session);
+    }
+    let windows = String::from_utf8_lossy(&output.stdout);
+
+    let mut status = Vec::new();
+    if let Some(fg) = colorscheme.foreground {
+        status.push(format!("fg={}", fg));
+    }
+    if let Some(bg) = colorscheme.background {
+        status.push(format!("bg={}", bg));
+    }
+
+    let mut cmd = Command::new("tmux");
+    cmd.args(["set-option", "-t", &target, "status-style", &status.join(",")]);
+    let borders = [
+        ("pane-border-style", colorscheme.background),
+        ("pane-active-border-style", colorscheme.foreground),
+    ];
+    for window in windows.lines() {
+        for (option, color) in borders {
+            if let Some(c) = color {
+                let style = format!("fg={}", c);
+                cmd.args([";", "set-option", "-w", "-t", window, option, &style]);
+            }
+        }
+    }
+
+    let output = cmd.output()?;
+    if !output.status.success() {
+        bail!("Error styling tmux session:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// The names of all sessions of the running tmux server, empty if there is none.
+pub fn tmux_sessions() -> anyhow::Result<Vec<String>> {
+    let output = Command::new("tmux").args(["list-sessions", "-F", "#{session_name}"]).output()?;
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().map(str::to_owned).collect())
+}
+
+/// Makes the tmux server source `config_file`.
+pub fn notify_tmux(config_file: impl AsRef<Path>) -> anyhow::Result<()> {
+    Command::new("tmux").arg("source-file").arg(config_file.as_ref()).output()?;
+    Ok(())
+}
+
+/// Checks whether a tmux server is running.
+pub fn ping_tmux() -> anyhow::Result<()> {
+    let output = Command::new("tmux").arg("has-session").output()?;
+    if !output.status.success() {
+        bail!("No tmux server running");
+    }
+    Ok(())
+}
+
+pub fn render_tmux(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
     let selector_str = fs::read_to_string(selector.as_ref())
         .map_err(|_| anyhow!("Error reading tmux selector"))?;
     let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
 
-    match super::selector(&selector, colorscheme.as_ref()) {
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
         Some(s) => {
-            fs::copy(tilde(s).as_ref(), config_file.as_ref())?;
-            Command::new("tmux").arg("source-file").arg(config_file.as_ref()).output()?;
+            let mut config = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading tmux colorscheme file"))?;
+
+            let overrides = super::target_overrides(scheme_dir, colorscheme.as_ref(), "tmux")?;
+            if !overrides.is_empty() && !config.ends_with('\n') {
+                config.push('\n');
+            }
+            for (k, v) in overrides {
+                config.push_str(&format!("set -g {} \"{}\"\n", k.replace('_', "-"), v));
+            }
 
-            Ok(())
+            Ok(config)
         }
         None => bail!("Missing mapping in tmux selector"),
     }
@@ -0,0 +1,69 @@
+use anyhow::{anyhow, bail};
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Sets the colorscheme mapped by the selector in all running Vim servers, using `--remote-send`.
+/// Nothing is done if no server is running or Vim was compiled without `+clientserver`.
+pub fn reload_vim(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let vim_colorscheme = vim_colorscheme(selector, scheme_dir, colorscheme)?;
+    let keys = remote_keys(&vim_colorscheme);
+    for server in servers() {
+        let output = Command::new("vim")
+            .args(["--servername", &server, "--remote-send", &keys])
+            .output()
+            .map_err(|e| anyhow!("Error running vim:\n{}", e))?;
+        if !output.status.success() {
+            bail!(
+                "Error setting vim colorscheme:\n{}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn ping_vim() -> anyhow::Result<()> {
+    if servers().is_empty() {
+        bail!("No vim servers running");
+    }
+    Ok(())
+}
+
+/// The names of the running Vim servers listed by `vim --serverlist`.
+fn servers() -> Vec<String> {
+    match Command::new("vim").arg("--serverlist").output() {
+        Ok(o) if o.status.success() => {
+            let stdout = String::from_utf8_lossy(&o.stdout);
+            stdout.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_owned).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// The name of the Vim colorscheme the selector maps the colorscheme to.
+fn vim_colorscheme(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str =
+        fs::read_to_string(selector.as_ref()).map_err(|_| anyhow!("Error reading vim selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(c) => Ok(c),
+        None => bail!("Missing mapping in vim selector"),
+    }
+}
+
+/// The keys that leave insert or visual mode and set the colorscheme. A `<` in the name is sent as
+/// `<lt>`, so it isn't read as a special key.
+pub(crate) fn remote_keys(colorscheme: &str) -> String {
+    format!("<C-\\><C-N>:colorscheme {}<CR>", colorscheme.replace('<', "<lt>"))
+}
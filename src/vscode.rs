@@ -0,0 +1,215 @@
+use anyhow::{anyhow, bail};
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// The setting containing the name of the color theme.
+const THEME_KEY: &str = "workbench.colorTheme";
+
+pub fn reload_vscode(
+    settings_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let settings = render_vscode(&settings_file, selector, &scheme_dir, colorscheme)?;
+    fs::write(settings_file.as_ref(), settings)?;
+    Ok(())
+}
+
+/// Sets the color theme mapped by the selector in the settings file. VS Code reloads its settings
+/// when the file changes, so nothing has to be notified.
+pub fn render_vscode(
+    settings_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading vscode selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+    let theme = match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(t) => t,
+        None => bail!("Missing mapping in vscode selector"),
+    };
+
+    let settings = match fs::read_to_string(settings_file.as_ref()) {
+        Ok(s) => s,
+        Err(e) if e.kind() == ErrorKind::NotFound => "{}\n".to_owned(),
+        Err(e) => bail!("Error reading vscode settings file:\n{}", e),
+    };
+    set_setting(&settings, THEME_KEY, &theme)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Token {
+    String,
+    Colon,
+    Comma,
+    Open,
+    Close,
+    Literal,
+}
+
+/// The tokens of a JSONC document with their byte ranges, without whitespace and comments.
+fn tokenize(json: &str) -> anyhow::Result<Vec<(Token, usize, usize)>> {
+    let bytes = json.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let token = match bytes[i] {
+            b if b.is_ascii_whitespace() => {
+                i += 1;
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = json[i..].find('\n').map_or(bytes.len(), |n| i + n);
+                continue;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                match json[i + 2..].find("*/") {
+                    Some(n) => i += n + 4,
+                    None => bail!("Unterminated comment in vscode settings file"),
+                }
+                continue;
+            }
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                if i >= bytes.len() {
+                    bail!("Unterminated string in vscode settings file");
+                }
+                i += 1;
+                Token::String
+            }
+            b':' => {
+                i += 1;
+                Token::Colon
+            }
+            b',' => {
+                i += 1;
+                Token::Comma
+            }
+            b'{' | b'[' => {
+                i += 1;
+                Token::Open
+            }
+            b'}' | b']' => {
+                i += 1;
+                Token::Close
+            }
+            _ => {
+                while i < bytes.len() && !b" \t\r\n,:{}[]\"/".contains(&bytes[i]) {
+                    i += 1;
+                }
+                Token::Literal
+            }
+        };
+        tokens.push((token, start, i));
+    }
+    Ok(tokens)
+}
+
+/// Sets a top-level string setting of a JSONC document. The value of an existing setting is
+/// replaced, otherwise the setting is added after the last one. Comments, formatting and all
+/// other settings are kept.
+fn set_setting(settings: &str, key: &str, value: &str) -> anyhow::Result<String> {
+    let tokens = tokenize(settings)?;
+    if !matches!(tokens.first(), Some((Token::Open, s, _)) if settings[*s..].starts_with('{')) {
+        bail!("The vscode settings file doesn't contain an object");
+    }
+    let value = serde_json::to_string(value)?;
+
+    let mut depth = 0;
+    let mut close = None;
+    let mut indent = None;
+    for (i, (token, start, end)) in tokens.iter().enumerate() {
+        match token {
+            Token::Open => depth += 1,
+            Token::Close => {
+                depth -= 1;
+                if depth == 0 {
+                    close = Some(i);
+                    break;
+                }
+            }
+            Token::String
+                if depth == 1 && matches!(tokens.get(i + 1), Some((Token::Colon, ..))) =>
+            {
+                let line_start = settings[..*start].rfind('\n').map_or(0, |n| n + 1);
+                indent.get_or_insert(&settings[line_start..*start]);
+                if serde_json::from_str::<String>(&settings[*start..*end]).ok().as_deref()
+                    != Some(key)
+                {
+                    continue;
+                }
+                let (_, value_start, mut value_end) = match tokens.get(i + 2) {
+                    Some(t) => *t,
+                    None => bail!("Missing value of {} in vscode settings file", key),
+                };
+                // objects and arrays end at their matching bracket
+                let mut value_depth = 0;
+                for (token, _, end) in tokens[i + 2..].iter() {
+                    match token {
+                        Token::Open => value_depth += 1,
+                        Token::Close => value_depth -= 1,
+                        _ => (),
+                    }
+                    value_end = *end;
+                    if value_depth <= 0 {
+                        break;
+                    }
+                }
+                return Ok(format!(
+                    "{}{}{}",
+                    &settings[..value_start],
+                    value,
+                    &settings[value_end..]
+                ));
+            }
+            _ => (),
+        }
+    }
+
+    let close = match close {
+        Some(c) => c,
+        None => bail!("Unterminated object in vscode settings file"),
+    };
+    let indent = indent.filter(|i| i.trim().is_empty()).unwrap_or("    ");
+    let setting = format!("{}{}: {}", indent, serde_json::to_string(key)?, value);
+    let (last, _, last_end) = tokens[close - 1];
+    let close_start = tokens[close].1;
+    if last == Token::Open {
+        let gap = &settings[last_end..close_start];
+        let gap = if gap.trim().is_empty() { "\n" } else { gap };
+        return Ok(format!(
+            "{}\n{}{}{}",
+            &settings[..last_end],
+            setting,
+            gap,
+            &settings[close_start..]
+        ));
+    }
+
+    // the setting is added after comments on the line of the last one
+    let comma = if last == Token::Comma { "" } else { "," };
+    let line_end = settings[last_end..close_start].find('\n').map(|n| last_end + n);
+    Ok(match line_end {
+        Some(line_end) => format!(
+            "{}{}{}\n{}{}",
+            &settings[..last_end],
+            comma,
+            &settings[last_end..line_end],
+            setting,
+            &settings[line_end..]
+        ),
+        None => {
+            format!("{}{}\n{}\n{}", &settings[..last_end], comma, setting, &settings[last_end..])
+        }
+    })
+}
@@ -0,0 +1,89 @@
+use anyhow::anyhow;
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::{Color, Options};
+
+/// The size images are scaled down to before extracting their palette.
+const THUMBNAIL_SIZE: u32 = 64;
+/// The number of colors extracted from an image.
+const PALETTE_SIZE: usize = 8;
+
+/// Extracts the most common colors of an image together with the share of pixels they cover,
+/// sorted from most to least common.
+pub fn extract_palette(image_file: impl AsRef<Path>) -> anyhow::Result<Vec<(Color, f32)>> {
+    let image = image::open(image_file.as_ref())
+        .map_err(|e| anyhow!("Error reading image {}:\n{}", image_file.as_ref().display(), e))?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .to_rgb8();
+
+    // group similar colors by only looking at the upper 4 bits of each channel
+    let mut buckets: HashMap<(u8, u8, u8), ([u32; 3], u32)> = HashMap::new();
+    for p in image.pixels() {
+        let [r, g, b] = p.0;
+        let (sum, count) = buckets.entry((r >> 4, g >> 4, b >> 4)).or_default();
+        sum[0] += r as u32;
+        sum[1] += g as u32;
+        sum[2] += b as u32;
+        *count += 1;
+    }
+
+    let total = image.pixels().len().max(1) as f32;
+    let mut buckets: Vec<_> = buckets.into_values().collect();
+    buckets.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+    let palette = buckets
+        .into_iter()
+        .take(PALETTE_SIZE)
+        .map(|(sum, count)| {
+            let avg = |s: u32| (s / count) as u8;
+            (Color::new(avg(sum[0]), avg(sum[1]), avg(sum[2])), count as f32 / total)
+        })
+        .collect();
+
+    Ok(palette)
+}
+
+/// Ranks all colorschemes in the colors file by how well they match the palette of an image,
+/// from best to worst. The score is the average perceptual distance of the background to the
+/// dominant color and of the palette colors to their nearest colorscheme color, lower is better.
+/// Colorschemes whose colors couldn't be loaded are skipped.
+pub fn suggest_colorschemes(
+    opts: &Options,
+    colors_file: impl AsRef<Path>,
+    image_file: impl AsRef<Path>,
+) -> anyhow::Result<Vec<(String, f32)>> {
+    let palette = extract_palette(image_file)?;
+    let dominant = match palette.first() {
+        Some((c, _)) => *c,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut ranked = Vec::new();
+    for colorscheme in crate::list(colors_file)? {
+        let scheme = match crate::load_colorscheme(opts, &colorscheme) {
+            Ok(s) if !s.is_empty() => s.map(|c| opts.map_color(c)),
+            _ => continue,
+        };
+
+        let colors: Vec<_> = scheme.colors().map(|(_, c)| c).collect();
+        let coverage: f32 = palette
+            .iter()
+            .map(|(p, share)| {
+                let nearest = colors.iter().map(|c| c.distance(p)).fold(f32::MAX, f32::min);
+                share * nearest
+            })
+            .sum::<f32>()
+            / palette.iter().map(|(_, share)| share).sum::<f32>();
+
+        let score = match scheme.background {
+            Some(bg) => (bg.distance(&dominant) + coverage) / 2.0,
+            None => coverage,
+        };
+        ranked.push((colorscheme, score));
+    }
+    ranked.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+
+    Ok(ranked)
+}
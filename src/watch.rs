@@ -0,0 +1,33 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::Event;
+
+/// How often the modification times of the watched files are checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Sends an [`Event::Reload`] whenever one of `files` is modified, created or removed.
+pub fn watch_files(files: Vec<PathBuf>, sender: Sender<Event>) -> anyhow::Result<()> {
+    let modified = |files: &[PathBuf]| -> Vec<Option<SystemTime>> {
+        files.iter().map(|f| fs::metadata(f).and_then(|m| m.modified()).ok()).collect()
+    };
+
+    let mut last = modified(&files);
+    thread::spawn(move || loop {
+        thread::sleep(POLL_INTERVAL);
+        let current = modified(&files);
+        if current == last {
+            continue;
+        }
+        last = current;
+
+        if sender.send(Event::Reload).is_err() {
+            break;
+        }
+    });
+
+    Ok(())
+}
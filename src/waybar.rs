@@ -0,0 +1,55 @@
+use anyhow::bail;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::colorscheme::COLOR_NAMES;
+use crate::{Color, Colorscheme};
+
+pub fn reload_waybar(
+    file: impl AsRef<Path>,
+    colors: &Colorscheme,
+    roles: &BTreeMap<String, Color>,
+) -> anyhow::Result<()> {
+    let style = render_waybar(colors, roles)?;
+    fs::write(file.as_ref(), style)?;
+    notify_waybar()
+}
+
+/// Sends `USR2` to the running waybar processes, which makes them reload their style.
+pub fn notify_waybar() -> anyhow::Result<()> {
+    let output = Command::new("pkill").args(["-USR2", "-x", "waybar"]).output()?;
+    // pkill exits with 1 if no process matched
+    if !matches!(output.status.code(), Some(0 | 1)) {
+        bail!("Error sending USR2 to waybar");
+    }
+    Ok(())
+}
+
+/// Renders a stylesheet that defines the colors and the color roles as named GTK colors, like
+/// `@define-color bright_red #fb4934;`. Colors that aren't set are left out.
+pub fn render_waybar(
+    colors: &Colorscheme,
+    roles: &BTreeMap<String, Color>,
+) -> anyhow::Result<String> {
+    let named = [
+        ("foreground".to_owned(), colors.foreground),
+        ("background".to_owned(), colors.background),
+        ("cursor".to_owned(), colors.cursor),
+    ];
+    let ansi = colors.ansi().enumerate().map(|(i, c)| {
+        let prefix = if i < 8 { "" } else { "bright_" };
+        (format!("{}{}", prefix, COLOR_NAMES[i % 8]), c)
+    });
+    let roles = roles.iter().map(|(n, c)| (n.replace('-', "_"), Some(*c)));
+
+    let mut style = String::new();
+    for (name, color) in named.into_iter().chain(ansi).chain(roles) {
+        if let Some(c) = color {
+            style.push_str(&format!("@define-color {} {};\n", name, c));
+        }
+    }
+    Ok(style)
+}
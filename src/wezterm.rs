@@ -0,0 +1,53 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::YamlLoader;
+
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::SystemTime;
+
+pub fn reload_wezterm(
+    colors_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let colors = render_wezterm(selector, &scheme_dir, colorscheme)?;
+    fs::write(colors_file.as_ref(), colors)?;
+    notify_wezterm(config_file)
+}
+
+/// Makes running wezterm instances reload their configuration, by updating the modification time
+/// of `config_file`, which wezterm watches.
+pub fn notify_wezterm(config_file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let config_file = config_file.as_ref();
+    if Path::exists(config_file) {
+        File::options()
+            .append(true)
+            .open(config_file)
+            .and_then(|f| f.set_modified(SystemTime::now()))
+            .map_err(|e| anyhow!("Error touching wezterm configuration file:\n{}", e))?;
+    }
+
+    Ok(())
+}
+
+pub fn render_wezterm(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading wezterm selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => {
+            let colors = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading wezterm colorscheme file"))?;
+            Ok(colors)
+        }
+        None => bail!("Missing mapping in wezterm selector"),
+    }
+}
@@ -0,0 +1,234 @@
+use anyhow::{anyhow, bail};
+use serde_json::Value;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::mpsc::Sender;
+
+use crate::focus::{hyprland_socket, spawn, spawn_reader};
+use crate::Event;
+
+/// A terminal window with the terminal devices of the shells running in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TerminalWindow {
+    pub workspace: String,
+    pub output: String,
+    pub ttys: Vec<PathBuf>,
+}
+
+/// Watches the windows and workspaces using the ipc of hyprland or sway, and sends an
+/// [`Event::Workspaces`] every time a window or workspace is moved, opened or closed.
+pub fn watch_workspaces(sender: Sender<Event>) -> anyhow::Result<()> {
+    if let Ok(signature) = env::var("HYPRLAND_INSTANCE_SIGNATURE") {
+        let stream = UnixStream::connect(hyprland_socket(&signature))
+            .map_err(|_| anyhow!("Error connecting to hyprland socket"))?;
+        spawn_reader(stream, sender, hyprland_event);
+    } else if env::var_os("SWAYSOCK").is_some() {
+        let events = "[\"window\", \"workspace\", \"output\"]";
+        let stdout = spawn("swaymsg", &["-r", "-m", "-t", "subscribe", events])?;
+        spawn_reader(stdout, sender, sway_event);
+    } else {
+        bail!("No supported compositor found, supported are sway and hyprland");
+    }
+
+    Ok(())
+}
+
+/// Parses `openwindow>>…`, `movewindow>>…` and similar events.
+fn hyprland_event(line: &str) -> Option<Event> {
+    let (event, _) = line.split_once(">>")?;
+    let events = [
+        "openwindow",
+        "closewindow",
+        "movewindow",
+        "movewindowv2",
+        "moveworkspace",
+        "moveworkspacev2",
+        "monitoradded",
+        "monitorremoved",
+    ];
+    events.contains(&event).then_some(Event::Workspaces)
+}
+
+/// Window events are only relevant if a window was opened, closed or moved, output events don't
+/// say what changed.
+fn sway_event(line: &str) -> Option<Event> {
+    let event: Value = serde_json::from_str(line).ok()?;
+    let change = event["change"].as_str()?;
+    ["new", "close", "move", "unspecified"].contains(&change).then_some(Event::Workspaces)
+}
+
+/// The windows whose processes have child processes running in a pseudo terminal. Terminals that
+/// run all their windows in one process can't be told apart, the terminal devices of all their
+/// windows are listed for every window.
+pub fn terminal_windows() -> anyhow::Result<Vec<TerminalWindow>> {
+    let windows = if env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        let clients = hyprctl("clients")?;
+        let monitors = hyprctl("monitors")?;
+        hyprland_windows(&clients, &monitors)
+    } else if env::var_os("SWAYSOCK").is_some() {
+        let output = Command::new("swaymsg")
+            .args(["-r", "-t", "get_tree"])
+            .output()
+            .map_err(|e| anyhow!("Error running swaymsg:\n{}", e))?;
+        let tree = serde_json::from_slice(&output.stdout)
+            .map_err(|e| anyhow!("Error parsing sway tree:\n{}", e))?;
+        sway_windows(&tree)
+    } else {
+        bail!("No supported compositor found, supported are sway and hyprland");
+    };
+
+    let parents = parent_pids();
+    let windows = windows
+        .into_iter()
+        .filter_map(|(pid, workspace, output)| {
+            let ttys = child_ttys(pid, &parents);
+            (!ttys.is_empty()).then_some(TerminalWindow { workspace, output, ttys })
+        })
+        .collect();
+    Ok(windows)
+}
+
+fn hyprctl(command: &str) -> anyhow::Result<Value> {
+    let output = Command::new("hyprctl")
+        .args(["-j", command])
+        .output()
+        .map_err(|e| anyhow!("Error running hyprctl:\n{}", e))?;
+    serde_json::from_slice(&output.stdout)
+        .map_err(|e| anyhow!("Error parsing hyprctl {} output:\n{}", command, e))
+}
+
+/// The pid, workspace and monitor of the clients listed by `hyprctl -j clients`.
+fn hyprland_windows(clients: &Value, monitors: &Value) -> Vec<(u32, String, String)> {
+    let monitor_names: BTreeMap<_, _> = monitors
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|m| Some((m["id"].as_i64()?, m["name"].as_str()?)))
+        .collect();
+    clients
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|c| {
+            let pid = u32::try_from(c["pid"].as_i64()?).ok()?;
+            let workspace = c["workspace"]["name"].as_str()?;
+            let monitor = monitor_names.get(&c["monitor"].as_i64()?).copied().unwrap_or_default();
+            Some((pid, workspace.to_owned(), monitor.to_owned()))
+        })
+        .collect()
+}
+
+/// The pid, workspace and output of the windows in the tree returned by `swaymsg -t get_tree`.
+/// Windows in the scratchpad are skipped.
+fn sway_windows(tree: &Value) -> Vec<(u32, String, String)> {
+    fn walk(node: &Value, workspace: &str, output: &str, windows: &mut Vec<(u32, String, String)>) {
+        let (workspace, output) = match node["type"].as_str() {
+            Some("output") => (workspace, node["name"].as_str().unwrap_or_default()),
+            Some("workspace") => (node["name"].as_str().unwrap_or_default(), output),
+            _ => (workspace, output),
+        };
+        if output == "__i3" {
+            return;
+        }
+        if let Some(pid) = node["pid"].as_u64().and_then(|p| u32::try_from(p).ok()) {
+            windows.push((pid, workspace.to_owned(), output.to_owned()));
+        }
+        let children = node["nodes"].as_array().into_iter().flatten();
+        let floating = node["floating_nodes"].as_array().into_iter().flatten();
+        for child in children.chain(floating) {
+            walk(child, workspace, output, windows);
+        }
+    }
+
+    let mut windows = Vec::new();
+    walk(tree, "", "", &mut windows);
+    windows
+}
+
+/// The parent pid of every process.
+fn parent_pids() -> Vec<(u32, u32)> {
+    let entries = match fs::read_dir("/proc") {
+        Ok(e) => e,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .flatten()
+        .filter_map(|e| {
+            let pid = e.file_name().to_str()?.parse().ok()?;
+            let stat = fs::read_to_string(e.path().join("stat")).ok()?;
+            // the command name in parentheses can contain spaces, the parent pid is the second
+            // field after it
+            let ppid = stat.rsplit_once(')')?.1.split_whitespace().nth(1)?.parse().ok()?;
+            Some((pid, ppid))
+        })
+        .collect()
+}
+
+/// The pseudo terminals the child processes of `pid` are reading from.
+fn child_ttys(pid: u32, parents: &[(u32, u32)]) -> Vec<PathBuf> {
+    let mut ttys: Vec<_> = parents
+        .iter()
+        .filter(|(_, ppid)| *ppid == pid)
+        .filter_map(|(child, _)| fs::read_link(format!("/proc/{}/fd/0", child)).ok())
+        .filter(|t| t.starts_with("/dev/pts"))
+        .collect();
+    ttys.sort();
+    ttys.dedup();
+    ttys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sway_tree() {
+        let tree = serde_json::json!({
+            "type": "root",
+            "nodes": [
+                {
+                    "type": "output",
+                    "name": "__i3",
+                    "nodes": [{
+                        "type": "workspace",
+                        "name": "__i3_scratch",
+                        "nodes": [{ "pid": 1 }],
+                    }],
+                },
+                {
+                    "type": "output",
+                    "name": "DP-1",
+                    "nodes": [{
+                        "type": "workspace",
+                        "name": "2",
+                        "nodes": [{ "type": "con", "nodes": [{ "type": "con", "pid": 2 }] }],
+                        "floating_nodes": [{ "type": "floating_con", "pid": 3 }],
+                    }],
+                },
+            ],
+        });
+        let windows =
+            [(2, "2".to_owned(), "DP-1".to_owned()), (3, "2".to_owned(), "DP-1".to_owned())];
+        assert_eq!(sway_windows(&tree), windows);
+    }
+
+    #[test]
+    fn hyprland_clients() {
+        let clients = serde_json::json!([
+            { "pid": 4, "workspace": { "id": 1, "name": "1" }, "monitor": 1 },
+            { "pid": 5, "workspace": { "id": 9, "name": "mail" }, "monitor": 0 },
+        ]);
+        let monitors =
+            serde_json::json!([{ "id": 0, "name": "eDP-1" }, { "id": 1, "name": "HDMI-A-1" }]);
+        let windows = [
+            (4, "1".to_owned(), "HDMI-A-1".to_owned()),
+            (5, "mail".to_owned(), "eDP-1".to_owned()),
+        ];
+        assert_eq!(hyprland_windows(&clients, &monitors), windows);
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::{anyhow, bail};
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::Colorscheme;
+
+pub fn reload_xresources(file: impl AsRef<Path>, colors: &Colorscheme) -> anyhow::Result<()> {
+    let resources = render_xresources(colors)?;
+    fs::write(file.as_ref(), resources)?;
+    notify_xresources(file)
+}
+
+/// Merges the file into the resource database of the X server using `xrdb -merge`, so X
+/// applications started afterwards use the new colors. Nothing is done without an X server.
+pub fn notify_xresources(file: impl AsRef<Path>) -> anyhow::Result<()> {
+    if env::var_os("DISPLAY").is_none() {
+        return Ok(());
+    }
+
+    let output = Command::new("xrdb")
+        .arg("-merge")
+        .arg(file.as_ref())
+        .output()
+        .map_err(|e| anyhow!("Error running xrdb:\n{}", e))?;
+    if !output.status.success() {
+        bail!("Error merging xresources:\n{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Renders the foreground, background and cursor colors and the 16 terminal colors as wildcard
+/// resources, which URxvt, xterm and most other X applications read. Colors that aren't set are
+/// left out.
+pub fn render_xresources(colors: &Colorscheme) -> anyhow::Result<String> {
+    let mut resources = [
+        ("foreground", colors.foreground),
+        ("background", colors.background),
+        ("cursorColor", colors.cursor),
+    ]
+    .map(|(name, color)| (name.to_owned(), color))
+    .to_vec();
+    let palette = colors.normal.iter().chain(colors.bright.iter());
+    resources.extend(palette.enumerate().map(|(i, color)| (format!("color{}", i), *color)));
+
+    let resources: String = resources
+        .into_iter()
+        .filter_map(|(name, color)| Some(format!("*.{}: {}\n", name, color?)))
+        .collect();
+    Ok(resources)
+}
@@ -0,0 +1,127 @@
+use anyhow::{anyhow, bail};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::{Color, Colorscheme};
+
+/// The prefix of the bus names zathura instances own, followed by their pid.
+const BUS_NAME_PREFIX: &str = "org.pwmt.zathura.PID-";
+
+pub fn reload_zathura(
+    file: impl AsRef<Path>,
+    colors: &Colorscheme,
+    roles: &BTreeMap<String, Color>,
+) -> anyhow::Result<()> {
+    let config = render_zathura(colors, roles)?;
+    fs::write(file.as_ref(), config)?;
+    notify_zathura(file)
+}
+
+/// Runs the `set` commands of the include file in every running zathura instance, through the
+/// `ExecuteCommand` method of their D-Bus interface. Open documents are recolored right away,
+/// nothing is done if no instance is running.
+pub fn notify_zathura(file: impl AsRef<Path>) -> anyhow::Result<()> {
+    let output = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus.ListNames",
+        ])
+        .output()
+        .map_err(|e| anyhow!("Error running dbus-send:\n{}", e))?;
+    // without a session bus there are no instances that could be reached
+    if !output.status.success() {
+        return Ok(());
+    }
+    let names = String::from_utf8_lossy(&output.stdout);
+    let instances: Vec<&str> = names
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("string \"")?.strip_suffix('"'))
+        .filter(|n| n.starts_with(BUS_NAME_PREFIX))
+        .collect();
+    if instances.is_empty() {
+        return Ok(());
+    }
+
+    let config = fs::read_to_string(file.as_ref())
+        .map_err(|_| anyhow!("Error reading zathura colors file"))?;
+    for instance in instances {
+        for command in zathura_commands(&config) {
+            let output = Command::new("dbus-send")
+                .args([
+                    "--session",
+                    "--type=method_call",
+                    &format!("--dest={}", instance),
+                    "/org/pwmt/zathura",
+                    "org.pwmt.zathura.ExecuteCommand",
+                    &format!("string:{}", command),
+                ])
+                .output()
+                .map_err(|e| anyhow!("Error running dbus-send:\n{}", e))?;
+            if !output.status.success() {
+                bail!(
+                    "Error setting zathura colors:\n{}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The `set` commands of a rendered include file, like `set default-bg "#282828"`.
+pub(crate) fn zathura_commands(config: &str) -> Vec<&str> {
+    config.lines().map(str::trim).filter(|l| l.starts_with("set ")).collect()
+}
+
+/// Renders `set` commands for the colors of the document background, the status and input bars,
+/// notifications, search highlights and the completion, and the colors `recolor` maps the
+/// documents to. The bars use the `surface` role and highlights the `accent` and `warning` roles.
+/// Colors that aren't set are left out.
+pub fn render_zathura(
+    colors: &Colorscheme,
+    roles: &BTreeMap<String, Color>,
+) -> anyhow::Result<String> {
+    let (fg, bg) = (colors.foreground, colors.background);
+    let role = |name: &str| roles.get(name).copied();
+    let surface = role("surface").or(bg);
+    let options = [
+        ("default-bg", bg),
+        ("default-fg", fg),
+        ("statusbar-bg", surface),
+        ("statusbar-fg", fg),
+        ("inputbar-bg", bg),
+        ("inputbar-fg", fg),
+        ("notification-bg", bg),
+        ("notification-fg", fg),
+        ("notification-error-bg", bg),
+        ("notification-error-fg", role("error")),
+        ("notification-warning-bg", bg),
+        ("notification-warning-fg", role("warning")),
+        ("highlight-color", role("warning")),
+        ("highlight-active-color", role("accent")),
+        ("completion-bg", surface),
+        ("completion-fg", fg),
+        ("completion-highlight-bg", role("accent")),
+        ("completion-highlight-fg", bg),
+        ("index-bg", bg),
+        ("index-fg", fg),
+        ("index-active-bg", role("accent")),
+        ("index-active-fg", bg),
+        ("recolor-lightcolor", bg),
+        ("recolor-darkcolor", fg),
+    ];
+
+    let mut config = String::new();
+    for (option, color) in options {
+        if let Some(c) = color {
+            config.push_str(&format!("set {} \"{}\"\n", option, c));
+        }
+    }
+    Ok(config)
+}
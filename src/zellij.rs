@@ -0,0 +1,97 @@
+use anyhow::{anyhow, bail};
+use shellexpand::tilde;
+use yaml_rust::YamlLoader;
+
+use std::fs;
+use std::path::Path;
+
+pub fn reload_zellij(
+    theme_file: impl AsRef<Path>,
+    selector: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<()> {
+    let theme = render_zellij(selector, &scheme_dir, colorscheme)?;
+    fs::write(theme_file.as_ref(), theme)?;
+    notify_zellij(theme_file, config_file)
+}
+
+/// Switches zellij to the theme defined in `theme_file`, by setting `theme` in `config_file`.
+/// Running sessions reload their configuration when it changes.
+pub fn notify_zellij(
+    theme_file: impl AsRef<Path>,
+    config_file: impl AsRef<Path>,
+) -> anyhow::Result<()> {
+    let theme = fs::read_to_string(theme_file.as_ref())
+        .map_err(|_| anyhow!("Error reading zellij theme file"))?;
+    let name = match theme_name(&theme) {
+        Some(n) => n,
+        None => bail!("No theme defined in zellij theme file"),
+    };
+
+    let config_file = config_file.as_ref();
+    if !config_file.exists() {
+        return Ok(());
+    }
+    let config = fs::read_to_string(config_file)
+        .map_err(|_| anyhow!("Error reading zellij configuration file"))?;
+    let new_config = set_theme(&config, name);
+    // every write makes running sessions reload
+    if new_config != config {
+        fs::write(config_file, new_config)?;
+    }
+
+    Ok(())
+}
+
+pub fn render_zellij(
+    selector: impl AsRef<Path>,
+    scheme_dir: impl AsRef<Path>,
+    colorscheme: impl AsRef<str>,
+) -> anyhow::Result<String> {
+    let selector_str = fs::read_to_string(selector.as_ref())
+        .map_err(|_| anyhow!("Error reading zellij selector"))?;
+    let selector = YamlLoader::load_from_str(&selector_str)?.remove(0);
+
+    match super::selector(&selector, &scheme_dir, colorscheme.as_ref()) {
+        Some(s) => {
+            let theme = fs::read_to_string(tilde(&s).as_ref())
+                .map_err(|_| anyhow!("Error reading zellij theme file"))?;
+            Ok(theme)
+        }
+        None => bail!("Missing mapping in zellij selector"),
+    }
+}
+
+/// The name of the first theme in the `themes` block of a kdl theme file.
+fn theme_name(theme: &str) -> Option<&str> {
+    let rest = &theme[theme.find("themes")? + "themes".len()..];
+    let rest = rest.trim_start().strip_prefix('{')?;
+    let name = rest.split(|c: char| c.is_whitespace() || c == '{').find(|n| !n.is_empty())?;
+    Some(name.trim_matches('"'))
+}
+
+/// Replaces the top level `theme` node of a zellij configuration, or appends one.
+fn set_theme(config: &str, name: &str) -> String {
+    let node = format!("theme \"{}\"", name);
+    let mut found = false;
+    let mut lines: Vec<String> = config
+        .lines()
+        .map(|l| {
+            if !found && l.starts_with("theme ") {
+                found = true;
+                node.clone()
+            } else {
+                l.to_owned()
+            }
+        })
+        .collect();
+    if !found {
+        lines.push(node);
+    }
+
+    let mut new_config = lines.join("\n");
+    new_config.push('\n');
+    new_config
+}
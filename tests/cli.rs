@@ -0,0 +1,36 @@
+use alco::test_util::FixtureHome;
+
+use std::process::{Command, Output};
+
+/// Runs the alco binary with the fixture home as `$HOME`.
+fn alco(home: &FixtureHome, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_alco"))
+        .args(args)
+        .env("HOME", home.path())
+        .env("LANG", "C")
+        .env_remove("LC_ALL")
+        .env_remove("LC_MESSAGES")
+        .output()
+        .unwrap()
+}
+
+/// Asserts that the command is rejected before anything is sent to the daemon.
+fn assert_not_sent(home: &FixtureHome, args: &[&str]) {
+    let output = alco(home, args);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "{:?}: {}", args, stdout);
+    assert!(stdout.contains("can be sent to the daemon"), "{:?}: {}", args, stdout);
+}
+
+#[test]
+fn remote_apply_options() {
+    let home = FixtureHome::new().unwrap();
+    assert_not_sent(&home, &["--remote", "apply", "nord", "--here"]);
+    assert_not_sent(&home, &["--remote", "apply", "nord", "--accent", "#ff0000"]);
+    // a dry run must not apply the colorscheme
+    assert_not_sent(&home, &["--remote", "apply", "nord", "--dry-run"]);
+    assert_not_sent(&home, &["--remote", "apply", "nord", "--dry-run", "--json"]);
+    // colorschemes that aren't in the colorscheme dir have no name to send
+    assert_not_sent(&home, &["--remote", "apply", "--stdin"]);
+    assert_not_sent(&home, &["--remote", "apply", "--file", "scheme.yml"]);
+}
@@ -0,0 +1,1164 @@
+use alco::test_util::FixtureHome;
+use alco::{Strategy, Target};
+
+fn fixture() -> FixtureHome {
+    let home = FixtureHome::new().unwrap();
+    home.colorschemes(&["dark", "light"]).unwrap();
+    home
+}
+
+fn assert_ok(results: Vec<(Target, anyhow::Result<()>)>) {
+    for (target, result) in results {
+        if let Err(e) = result {
+            panic!("Error reloading {}: {:?}", target, e);
+        }
+    }
+}
+
+/// `content` inside the block managed by alco.
+fn block(content: &str) -> String {
+    format!("{}\n{}{}\n", alco::BLOCK_BEGIN, content, alco::BLOCK_END)
+}
+
+#[test]
+fn resolve() {
+    let home = fixture();
+    home.selector(
+        Target::Tmux,
+        &[("light", ".config/tmux/light.conf"), ("else", ".config/tmux/dark.conf")],
+    )
+    .unwrap();
+    home.selector(Target::Kitty, &[("light", ".config/kitty/light.conf")]).unwrap();
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+    opts.kitty.reload = true;
+    opts.bat.reload = true;
+
+    let resolved = |colorscheme| -> Vec<(Target, String)> {
+        let resolutions = alco::resolve(&opts, colorscheme).into_iter();
+        resolutions.map(|(t, r)| (t, r.map_or_else(|e| e.to_string(), |r| r.to_string()))).collect()
+    };
+    let light = home.join(".config/tmux/light.conf");
+    assert_eq!(
+        resolved("light"),
+        [
+            (Target::Kitty, home.join(".config/kitty/light.conf").display().to_string()),
+            (Target::Tmux, light.display().to_string()),
+            (Target::Bat, "Error reading bat selector".to_owned()),
+        ]
+    );
+    let dark = format!("missing → else → {}", home.join(".config/tmux/dark.conf").display());
+    assert_eq!(
+        resolved("dark"),
+        [
+            (Target::Kitty, "missing → else → none".to_owned()),
+            (Target::Tmux, dark),
+            (Target::Bat, "Error reading bat selector".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn install_pack() {
+    let home = fixture();
+    home.write("my-pack/schemes/nord.yml", "colors: {}\n").unwrap();
+    home.write("my-pack/selectors/tmux.yml", "nord: tmux/nord.conf\nelse: tmux/nord.conf\n")
+        .unwrap();
+    home.write("my-pack/files/tmux/nord.conf", "set -g status-style bg=blue\n").unwrap();
+    let opts = home.options();
+
+    let pack = home.join("my-pack").display().to_string();
+    let installed =
+        alco::install_pack(&pack, home.colors_file(), home.join("packs"), &opts).unwrap();
+    assert_eq!(installed.colorschemes, ["nord"]);
+    let conf = home.join("packs/my-pack/tmux/nord.conf");
+    home.assert_file(&opts.tmux.selector, format!("nord: {}\n", conf.display()));
+
+    home.write("evil/selectors/tmux.yml", "evil: ../../.bashrc\n").unwrap();
+    let pack = home.join("evil").display().to_string();
+    assert!(alco::install_pack(&pack, home.colors_file(), home.join("packs"), &opts).is_err());
+    home.assert_file(&opts.tmux.selector, format!("nord: {}\n", conf.display()));
+}
+
+#[test]
+fn apply_updates_state() {
+    let home = fixture();
+    let opts = home.options();
+
+    let before = std::time::SystemTime::now();
+    assert_ok(home.apply(&opts, "light").unwrap());
+    assert_eq!(home.current().unwrap(), "light");
+    assert!(alco::status(home.state_file()).unwrap().changed >= before);
+
+    assert!(home.apply(&opts, "missing").is_err());
+    assert_eq!(home.current().unwrap(), "light");
+
+    home.apply(&opts, "dark").unwrap();
+    home.apply(&opts, "dark").unwrap();
+    assert_eq!(alco::status(home.state_file()).unwrap().previous.as_deref(), Some("light"));
+}
+
+#[test]
+fn list_entries() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/light.yml",
+        "version: 2\nmeta:\n  variant: light\n  tags: [warm]\ncolors:\n  background: '#fdf6e3'\n",
+    )
+    .unwrap();
+    let opts = home.options();
+    home.apply(&opts, "light").unwrap();
+
+    let entries =
+        alco::list_entries(home.colors_file(), home.state_file(), &opts.scheme_dir).unwrap();
+    let [dark, light] = entries.as_slice() else {
+        panic!("expected two entries, got {:?}", entries);
+    };
+    assert_eq!((dark.name.as_str(), &dark.path, dark.applied), ("dark", &None, None));
+    assert_eq!(light.name, "light");
+    assert_eq!(light.path.as_deref(), Some(opts.scheme_dir.join("light.yml").as_path()));
+    assert_eq!(light.variant.as_deref(), Some("light"));
+    assert_eq!(light.tags, ["warm"]);
+    assert!(light.applied.is_some());
+}
+
+#[test]
+fn list_sort() {
+    let home = fixture();
+    home.write(home.colors_file(), "colors:\n- scheme10\n- Scheme2\n- scheme1\n").unwrap();
+    assert_eq!(alco::list(home.colors_file()).unwrap(), ["scheme1", "Scheme2", "scheme10"]);
+
+    home.apply(&home.options(), "Scheme2").unwrap();
+    let opts = home.options();
+    let toggled = alco::toggle(home.colors_file(), home.state_file(), &opts.scheme_dir, false);
+    assert_eq!(toggled.unwrap(), "scheme10");
+
+    home.write(home.colors_file(), "sort: lexical\ncolors:\n- scheme10\n- Scheme2\n- scheme1\n")
+        .unwrap();
+    assert_eq!(alco::list(home.colors_file()).unwrap(), ["Scheme2", "scheme1", "scheme10"]);
+
+    home.write(home.colors_file(), "sort: file\ncolors:\n- scheme10\n- Scheme2\n- scheme1\n")
+        .unwrap();
+    assert_eq!(alco::list(home.colors_file()).unwrap(), ["scheme10", "Scheme2", "scheme1"]);
+}
+
+#[test]
+fn toggle_skips_disabled() {
+    let home = fixture();
+    home.write(home.colors_file(), "colors:\n- a\n- b\n- c\n- d\n").unwrap();
+    home.write(".config/alco/schemes/b.yml", "version: 2\nmeta:\n  disabled: true\n").unwrap();
+    home.write(".config/alco/schemes/disabled/c.yml", "version: 2\n").unwrap();
+    home.write(".config/alco/schemes/d.yml", "version: 3\n").unwrap();
+    let opts = home.options();
+
+    let enabled = alco::list_enabled(home.colors_file(), &opts.scheme_dir).unwrap();
+    assert_eq!(enabled, ["a", "d"]);
+
+    home.apply(&opts, "a").unwrap();
+    let toggled = alco::toggle(home.colors_file(), home.state_file(), &opts.scheme_dir, false);
+    assert_eq!(toggled.unwrap(), "a");
+}
+
+#[test]
+fn template() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\ncolors:\n  background: '#282828'\n  normal:\n    red: '#cc241d'\n",
+    )
+    .unwrap();
+    home.write(
+        ".config/alco/templates/bspwm.tmpl",
+        "# {{name}}\nbspc config normal_border_color '{{ colors.primary.background }}'\n\
+         bspc config focused_border_color '{{colors.normal.red}}'\n",
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    let output = home.join(".config/bspwm/colors.sh").display().to_string();
+    let template = alco::TemplateConfig { output, command: Some("exit 1".into()) };
+    opts.templates.insert("bspwm".into(), template);
+    let results = alco::render_templates(&opts, "dark");
+
+    assert!(matches!(results.as_slice(), [(name, Ok(()))] if name == "bspwm"));
+    home.assert_file(
+        ".config/bspwm/colors.sh",
+        "# dark\nbspc config normal_border_color '#282828'\n\
+         bspc config focused_border_color '#cc241d'\n",
+    );
+}
+
+#[test]
+fn post_apply_hooks() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = fixture();
+    home.write(".config/alco/schemes/light.yml", "version: 2\nmeta:\n  variant: light\n").unwrap();
+    let out = home.join("out");
+    let hooks = [
+        ("10-fail", "#!/bin/sh\necho broken >&2\nexit 3\n".to_string()),
+        (
+            "20-env",
+            format!("#!/bin/sh\necho \"$ALCO_COLORSCHEME $ALCO_VARIANT\" > {}\n", out.display()),
+        ),
+        ("30-disabled", "#!/bin/sh\nexit 1\n".to_string()),
+    ];
+    for (name, script) in &hooks {
+        let file = home.join(format!(".config/alco/hooks/post-apply.d/{}", name));
+        home.write(&file, script).unwrap();
+        let mode = if *name == "30-disabled" { 0o644 } else { 0o755 };
+        std::fs::set_permissions(&file, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    let results = alco::run_post_apply_hooks(&home.options(), "light").unwrap();
+    let [(fail, Err(e)), (env, Ok(()))] = results.as_slice() else {
+        panic!("unexpected hook results {:?}", results);
+    };
+    assert_eq!((fail.as_str(), env.as_str()), ("10-fail", "20-env"));
+    assert!(e.to_string().contains("broken"));
+    home.assert_file(&out, "light light\n");
+}
+
+#[cfg(feature = "alacritty")]
+#[test]
+fn alacritty() {
+    let home = fixture();
+    home.write(
+        ".config/alacritty/alacritty.yml.in",
+        "font:\n  size: 11\ncolors:\n  primary:\n    background: '#000000'\n    foreground: '#ffffff'\n",
+    )
+    .unwrap();
+    home.write(
+        ".config/alacritty/colors/light.yml",
+        "colors:\n  primary:\n    background: '#fdf6e3'\n    foreground: '#657b83'\n",
+    )
+    .unwrap();
+    home.selector(Target::Alacritty, &[("light", ".config/alacritty/colors/light.yml")]).unwrap();
+
+    let mut opts = home.options();
+    opts.alacritty.reload = true;
+    assert_ok(home.apply(&opts, "light").unwrap());
+
+    home.assert_file(
+        ".config/alacritty/alacritty.yml",
+        "font:\n  size: 11\ncolors:\n  primary:\n    background: '#fdf6e3'\n    foreground: '#657b83'\n",
+    );
+}
+
+#[cfg(feature = "alacritty")]
+#[test]
+fn alacritty_font() {
+    let home = fixture();
+    home.write(
+        ".config/alacritty/alacritty.yml.in",
+        "font:\n  normal:\n    family: Hack\n  size: 11\ncolors:\n  primary:\n    background: '#000000'\n",
+    )
+    .unwrap();
+    home.write(
+        ".config/alacritty/colors/dark.yml",
+        "colors:\n  primary:\n    background: '#282828'\n",
+    )
+    .unwrap();
+    home.selector(Target::Alacritty, &[("dark", ".config/alacritty/colors/dark.yml")]).unwrap();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\nfont:\n  family: Terminus\n  size: 12\ncolors:\n  background: '#282828'\n",
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    opts.alacritty.reload = true;
+    // the font of the profile takes precedence
+    opts.font.size = Some(14.5);
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(
+        ".config/alacritty/alacritty.yml",
+        "font:\n  normal:\n    family: Terminus\n  size: 14.5\ncolors:\n  primary:\n    background: '#282828'\n",
+    );
+}
+
+#[cfg(feature = "alacritty")]
+#[test]
+fn alacritty_only_colors() {
+    let home = fixture();
+    home.write(
+        ".config/alacritty/alacritty.yml.in",
+        "colors:\n  primary:\n    background: '#000000'\n  bright:\n    red: '#ff0000'\n",
+    )
+    .unwrap();
+    home.write(
+        ".config/alacritty/colors/light.yml",
+        "colors:\n  primary:\n    background: '#fdf6e3'\n  bright:\n    red: '#dc322f'\n",
+    )
+    .unwrap();
+    home.selector(Target::Alacritty, &[("light", ".config/alacritty/colors/light.yml")]).unwrap();
+
+    let mut opts = home.options();
+    opts.alacritty.reload = true;
+    opts.alacritty.only_colors = vec!["colors.primary.*".into()];
+    assert_ok(home.apply(&opts, "light").unwrap());
+
+    home.assert_file(
+        ".config/alacritty/alacritty.yml",
+        "colors:\n  primary:\n    background: '#fdf6e3'\n  bright:\n    red: '#ff0000'\n",
+    );
+}
+
+#[cfg(feature = "alacritty")]
+#[test]
+fn alacritty_only_colors_cached() {
+    let home = fixture();
+    home.write(
+        ".config/alacritty/alacritty.yml.in",
+        "colors:\n  primary:\n    background: '#000000'\n  bright:\n    red: '#ff0000'\n",
+    )
+    .unwrap();
+    home.write(
+        ".config/alacritty/colors/light.yml",
+        "colors:\n  primary:\n    background: '#fdf6e3'\n  bright:\n    red: '#dc322f'\n",
+    )
+    .unwrap();
+    home.selector(Target::Alacritty, &[("light", ".config/alacritty/colors/light.yml")]).unwrap();
+
+    let mut opts = home.options();
+    opts.alacritty.reload = true;
+    opts.strategy = Strategy::Symlink;
+    assert_ok(home.apply(&opts, "light").unwrap());
+    home.assert_file(
+        ".config/alacritty/alacritty.yml",
+        "colors:\n  primary:\n    background: '#fdf6e3'\n  bright:\n    red: '#dc322f'\n",
+    );
+
+    opts.alacritty.only_colors = vec!["colors.primary.*".into()];
+    assert_ok(home.apply(&opts, "light").unwrap());
+    home.assert_file(
+        ".config/alacritty/alacritty.yml",
+        "colors:\n  primary:\n    background: '#fdf6e3'\n  bright:\n    red: '#ff0000'\n",
+    );
+}
+
+#[cfg(feature = "kitty")]
+#[test]
+fn kitty() {
+    let home = fixture();
+    home.write(".config/kitty/colors/dark.conf", "background #000000\n").unwrap();
+    home.selector(Target::Kitty, &[("dark", ".config/kitty/colors/dark.conf")]).unwrap();
+
+    let mut opts = home.options();
+    opts.kitty.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/kitty/colors/current.conf", "background #000000\n");
+}
+
+#[cfg(feature = "konsole")]
+#[test]
+fn konsole() {
+    let home = fixture();
+    home.write(".config/alco/konsole-selector.yml", "dark: Breeze Dark\n").unwrap();
+
+    let mut opts = home.options();
+    opts.konsole.reload = true;
+    opts.notify = true;
+    let plan = alco::plan(&opts, "dark");
+
+    let change = match plan.as_slice() {
+        [(Target::Konsole, Ok(c))] => c,
+        _ => panic!("unexpected plan"),
+    };
+    let commands = [
+        "qdbus <konsole instance> /Sessions/<session> org.kde.konsole.Session.setProfile 'Breeze Dark'",
+        "qdbus <konsole instance> /Windows/<window> org.kde.konsole.Window.setDefaultProfile 'Breeze Dark'",
+    ];
+    assert_eq!(change.commands, commands);
+    assert_eq!(change.path, None);
+}
+
+#[cfg(feature = "emacs")]
+#[test]
+fn emacs() {
+    let home = fixture();
+    home.write(".config/alco/emacs-selector.yml", "else: \"{scheme}-theme\"\n").unwrap();
+
+    let mut opts = home.options();
+    opts.emacs.reload = true;
+    opts.notify = true;
+    let plan = alco::plan(&opts, "dark");
+
+    let change = match plan.as_slice() {
+        [(Target::Emacs, Ok(c))] => c,
+        _ => panic!("unexpected plan"),
+    };
+    let commands = [
+        "emacsclient --eval '(progn (mapc #'\\''disable-theme custom-enabled-themes) (load-theme (intern \"dark-theme\") t))'",
+    ];
+    assert_eq!(change.commands, commands);
+    assert_eq!(change.path, None);
+}
+
+#[cfg(feature = "vim")]
+#[test]
+fn vim() {
+    let home = fixture();
+    home.write(".config/alco/vim-selector.yml", "dark: desert\n").unwrap();
+
+    let mut opts = home.options();
+    opts.vim.reload = true;
+    opts.notify = true;
+    let plan = alco::plan(&opts, "dark");
+
+    let change = match plan.as_slice() {
+        [(Target::Vim, Ok(c))] => c,
+        _ => panic!("unexpected plan"),
+    };
+    let commands =
+        ["vim --servername <vim server> --remote-send '<C-\\><C-N>:colorscheme desert<CR>'"];
+    assert_eq!(change.commands, commands);
+    assert_eq!(change.path, None);
+}
+
+#[cfg(feature = "tmux")]
+#[test]
+fn tmux_fallback() {
+    let home = fixture();
+    home.write(".config/tmux/colors/dark.conf", "set -g status-style bg=black\n").unwrap();
+    home.write(".config/tmux/colors/light.conf", "set -g status-style bg=white\n").unwrap();
+    home.selector(
+        Target::Tmux,
+        &[("light", ".config/tmux/colors/light.conf"), ("else", ".config/tmux/colors/dark.conf")],
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+
+    assert_ok(home.apply(&opts, "light").unwrap());
+    home.assert_file(".config/tmux/colors/current.conf", block("set -g status-style bg=white\n"));
+
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    home.assert_file(".config/tmux/colors/current.conf", block("set -g status-style bg=black\n"));
+}
+
+#[cfg(feature = "tmux")]
+#[test]
+fn selector_placeholders() {
+    let home = fixture();
+    home.write(".config/alco/schemes/dark.yml", "version: 2\nmeta:\n  variant: dark\ncolors: {}\n")
+        .unwrap();
+    home.write(".config/tmux/colors/dark-dark.conf", "set -g status-style bg=black\n").unwrap();
+    home.selector(Target::Tmux, &[("else", ".config/tmux/colors/{scheme}-{variant}.conf")])
+        .unwrap();
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    home.assert_file(".config/tmux/colors/current.conf", block("set -g status-style bg=black\n"));
+
+    // light has no variant the value could be expanded with
+    let resolved: Vec<_> = alco::resolve(&opts, "light").into_iter().map(|(_, r)| r.ok()).collect();
+    assert_eq!(resolved, [Some(alco::Resolution::Missing)]);
+}
+
+#[cfg(feature = "tmux")]
+#[test]
+fn audit_log() {
+    let home = fixture();
+    home.write(".config/tmux/colors/dark.conf", "set -g status-style bg=black\n").unwrap();
+    home.write(".config/tmux/colors/light.conf", "set -g status-style bg=white\n").unwrap();
+    home.selector(
+        Target::Tmux,
+        &[("dark", ".config/tmux/colors/dark.conf"), ("light", ".config/tmux/colors/light.conf")],
+    )
+    .unwrap();
+    let log = home.join(".local/state/alco/audit.log");
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+    for colorscheme in ["light", "dark"] {
+        let before = alco::read_files(&opts);
+        assert_ok(home.apply(&opts, colorscheme).unwrap());
+        alco::log_changes(&log, colorscheme, before).unwrap();
+    }
+
+    let entries = alco::audit_entries(&log).unwrap();
+    let names: Vec<_> = entries.iter().map(|e| e.colorscheme.as_str()).collect();
+    assert_eq!(names, ["dark", "light"]);
+    let lines = [
+        " # BEGIN alco",
+        "-set -g status-style bg=white",
+        "+set -g status-style bg=black",
+        " # END alco",
+    ];
+    assert!(matches!(entries[0].changes.as_slice(), [c] if c.target == Target::Tmux
+        && matches!(c.hunks.as_slice(), [h] if h.lines == lines)));
+}
+
+#[cfg(feature = "tmux")]
+#[test]
+fn managed_block() {
+    let home = fixture();
+    home.write(".config/tmux/colors/dark.conf", "set -g status-style bg=black\n").unwrap();
+    home.selector(Target::Tmux, &[("dark", ".config/tmux/colors/dark.conf")]).unwrap();
+    let user = |block: &str| format!("set -g status-position top\n{}set -g mouse on\n", block);
+    home.write(".config/tmux/colors/current.conf", user(&block("set -g status-style bg=white\n")))
+        .unwrap();
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    home.assert_file(
+        ".config/tmux/colors/current.conf",
+        user(&block("set -g status-style bg=black\n")),
+    );
+}
+
+#[cfg(feature = "tmux")]
+#[test]
+fn symlink_strategy() {
+    let home = fixture();
+    home.write(".config/tmux/colors/dark.conf", "set -g status-style bg=black\n").unwrap();
+    home.selector(Target::Tmux, &[("dark", ".config/tmux/colors/dark.conf")]).unwrap();
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+    opts.strategy = Strategy::Symlink;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    let file = home.join(".config/tmux/colors/current.conf");
+    assert!(file.symlink_metadata().unwrap().file_type().is_symlink());
+    assert!(std::fs::read_link(&file).unwrap().starts_with(&opts.cache_dir));
+    home.assert_file(&file, block("set -g status-style bg=black\n"));
+}
+
+#[cfg(feature = "tmux")]
+#[test]
+fn prerendered_files_follow_mapped_files() {
+    let home = fixture();
+    let scheme = ".config/tmux/colors/dark.conf";
+    home.write(scheme, "set -g status-style bg=black\n").unwrap();
+    home.selector(Target::Tmux, &[("dark", scheme)]).unwrap();
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+    alco::prerender(home.colors_file(), &opts).unwrap();
+
+    home.write(scheme, "set -g status-style bg=blue\n").unwrap();
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(10);
+    let file = std::fs::File::options().write(true).open(home.join(scheme)).unwrap();
+    file.set_modified(later).unwrap();
+
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    home.assert_file(".config/tmux/colors/current.conf", block("set -g status-style bg=blue\n"));
+}
+
+#[cfg(feature = "starship")]
+#[test]
+fn starship() {
+    let home = fixture();
+    home.write(
+        ".config/starship/starship.toml.in",
+        "[character]\nsuccess_symbol = \"[>](<green>)\"\n",
+    )
+    .unwrap();
+    home.write(".config/starship/dark.yml", "green: \"#98971a\"\n").unwrap();
+    home.selector(Target::Starship, &[("dark", ".config/starship/dark.yml")]).unwrap();
+
+    let mut opts = home.options();
+    opts.starship.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/starship.toml", "[character]\nsuccess_symbol = \"[>](#98971a)\"\n");
+}
+
+#[cfg(feature = "starship")]
+#[test]
+fn starship_module_cached() {
+    let home = fixture();
+    home.write(".config/starship/starship.toml.in", "add_newline = false\n").unwrap();
+    home.write(".config/starship/dark.yml", "green: \"#98971a\"\n").unwrap();
+    home.selector(Target::Starship, &[("dark", ".config/starship/dark.yml")]).unwrap();
+
+    let mut opts = home.options();
+    opts.starship.reload = true;
+    opts.strategy = Strategy::Symlink;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    home.assert_file(".config/starship.toml", "add_newline = false\n");
+
+    opts.starship.module = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    let starship = std::fs::read_to_string(home.join(".config/starship.toml")).unwrap();
+    assert!(starship.contains("[custom.alco]"), "{}", starship);
+}
+
+#[cfg(feature = "bat")]
+#[test]
+fn bat() {
+    let home = fixture();
+    home.write(".config/bat/config.in", "--theme=\"<theme>\"\n--style=plain\n").unwrap();
+    home.selector(Target::Bat, &[("light", "GitHub"), ("else", "gruvbox-dark")]).unwrap();
+
+    let mut opts = home.options();
+    opts.bat.reload = true;
+    assert_ok(home.apply(&opts, "light").unwrap());
+
+    home.assert_file(".config/bat/config", "--theme=\"GitHub\"\n--style=plain\n");
+}
+
+#[cfg(feature = "bat")]
+#[test]
+fn bat_custom_theme() {
+    let home = fixture();
+    home.write(".config/bat/config.in", "--theme=\"<theme>\"\n").unwrap();
+    home.selector(Target::Bat, &[("dark", "~/colors/gruvbox.tmTheme")]).unwrap();
+
+    let mut opts = home.options();
+    opts.bat.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/bat/config", "--theme=\"gruvbox\"\n");
+}
+
+#[cfg(feature = "delta")]
+#[test]
+fn delta() {
+    let home = fixture();
+    home.write(".config/delta/colors/dark.gitconfig", "[delta]\n    syntax-theme = gruvbox-dark\n")
+        .unwrap();
+    home.selector(Target::Delta, &[("dark", ".config/delta/colors/dark.gitconfig")]).unwrap();
+
+    let mut opts = home.options();
+    opts.delta.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(
+        ".config/delta/colors/current.gitconfig",
+        block("[delta]\n    syntax-theme = gruvbox-dark\n"),
+    );
+}
+
+#[cfg(feature = "wezterm")]
+#[test]
+fn wezterm() {
+    let home = fixture();
+    home.write(".config/wezterm/colors/dark.toml", "[colors]\nbackground = \"#282828\"\n").unwrap();
+    home.write(".config/wezterm/wezterm.lua", "return {}\n").unwrap();
+    home.selector(Target::Wezterm, &[("dark", ".config/wezterm/colors/dark.toml")]).unwrap();
+
+    let mut opts = home.options();
+    opts.wezterm.reload = true;
+    opts.notify = true;
+    let before = std::time::SystemTime::now();
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/wezterm/colors/current.toml", "[colors]\nbackground = \"#282828\"\n");
+    let config = home.join(".config/wezterm/wezterm.lua");
+    assert!(std::fs::metadata(config).unwrap().modified().unwrap() >= before);
+}
+
+#[cfg(feature = "foot")]
+#[test]
+fn foot() {
+    let home = fixture();
+    home.write(".config/foot/colors/light.ini", "[colors]\nbackground=fdf6e3\n").unwrap();
+    home.selector(Target::Foot, &[("light", ".config/foot/colors/light.ini")]).unwrap();
+
+    let mut opts = home.options();
+    opts.foot.reload = true;
+    assert_ok(home.apply(&opts, "light").unwrap());
+
+    home.assert_file(".config/foot/colors/current.ini", "[colors]\nbackground=fdf6e3\n");
+}
+
+#[cfg(feature = "zellij")]
+#[test]
+fn zellij() {
+    let home = fixture();
+    let theme = "themes {\n    gruvbox-dark {\n        fg \"#ebdbb2\"\n    }\n}\n";
+    home.write(".config/zellij/colors/gruvbox-dark.kdl", theme).unwrap();
+    home.write(".config/zellij/config.kdl", "// theme \"dracula\"\ntheme \"dracula\"\n").unwrap();
+    home.selector(Target::Zellij, &[("dark", ".config/zellij/colors/gruvbox-dark.kdl")]).unwrap();
+
+    let mut opts = home.options();
+    opts.zellij.reload = true;
+    opts.notify = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/zellij/themes/alco.kdl", theme);
+    home.assert_file(".config/zellij/config.kdl", "// theme \"dracula\"\ntheme \"gruvbox-dark\"\n");
+}
+
+#[cfg(feature = "fish")]
+#[test]
+fn fish() {
+    let home = fixture();
+    let theme = "fish_color_normal ebdbb2\nfish_color_command b8bb26 --bold\n";
+    home.write(".config/fish/colors/gruvbox.theme", theme).unwrap();
+    home.selector(Target::Fish, &[("dark", ".config/fish/colors/gruvbox.theme")]).unwrap();
+
+    let mut opts = home.options();
+    opts.fish.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/fish/themes/alco.theme", theme);
+}
+
+#[cfg(feature = "fzf")]
+#[test]
+fn fzf() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\ncolors:\n  foreground: '#ebdbb2'\n  normal:\n    red: '#cc241d'\n",
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    opts.fzf.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(
+        ".config/alco/fzf-colors.sh",
+        "export FZF_DEFAULT_OPTS=\"$FZF_DEFAULT_OPTS --color=fg:#ebdbb2,fg+:#ebdbb2,pointer:#cc241d\"\n",
+    );
+}
+
+#[cfg(feature = "rofi")]
+#[test]
+fn rofi() {
+    let home = fixture();
+    home.write(".config/rofi/colors/dark.rasi", "* {\n    background: #282828;\n}\n").unwrap();
+    home.selector(Target::Rofi, &[("dark", ".config/rofi/colors/dark.rasi")]).unwrap();
+
+    let mut opts = home.options();
+    opts.rofi.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/rofi/colors/current.rasi", "* {\n    background: #282828;\n}\n");
+}
+
+#[cfg(feature = "dunst")]
+#[test]
+fn dunst() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\ncolors:\n  background: '#282828'\n  normal:\n    red: '#cc241d'\n",
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    opts.dunst.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(
+        ".config/dunst/dunstrc.d/alco.conf",
+        block("[urgency_low]\n    background = \"#282828\"\n\n[urgency_normal]\n    background = \"#282828\"\n\n\
+         [urgency_critical]\n    background = \"#282828\"\n    frame_color = \"#cc241d\"\n"));
+}
+
+#[cfg(feature = "xresources")]
+#[test]
+fn xresources() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\ncolors:\n  background: '#282828'\n  normal:\n    red: '#cc241d'\n  bright:\n    red: '#fb4934'\n",
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    opts.xresources.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(
+        ".config/alco/Xresources",
+        "*.background: #282828\n*.color1: #cc241d\n*.color9: #fb4934\n",
+    );
+}
+
+#[cfg(feature = "waybar")]
+#[test]
+fn waybar() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\ncolors:\n  background: '#282828'\n  bright:\n    red: '#fb4934'\n",
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    opts.waybar.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(
+        ".config/waybar/colors.css",
+        "@define-color background #282828;\n@define-color bright_red #fb4934;\n\
+         @define-color surface #282828;\n",
+    );
+}
+
+#[cfg(feature = "tmux")]
+#[test]
+fn accent_override() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\ncolors:\n  normal:\n    blue: '#458588'\n",
+    )
+    .unwrap();
+    home.write(".config/tmux/colors/dark.conf", "set -g status-style bg=#458588\n").unwrap();
+    home.selector(Target::Tmux, &[("dark", ".config/tmux/colors/dark.conf")]).unwrap();
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+    opts.accent = "#ff8800".parse().ok();
+    opts.override_accent("dark").unwrap();
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/tmux/colors/current.conf", block("set -g status-style bg=#ff8800\n"));
+    assert_eq!(opts.roles["accent"], "#ff8800");
+}
+
+#[cfg(feature = "polybar")]
+#[test]
+fn polybar() {
+    let home = fixture();
+    home.write(".config/polybar/colors/dark.ini", "[colors]\nbackground = #282828\n").unwrap();
+    home.selector(Target::Polybar, &[("dark", ".config/polybar/colors/dark.ini")]).unwrap();
+
+    let mut opts = home.options();
+    opts.polybar.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/polybar/colors.ini", block("[colors]\nbackground = #282828\n"));
+}
+
+#[cfg(all(feature = "wezterm", feature = "picom"))]
+#[test]
+fn opacity() {
+    let home = fixture();
+    home.write(".config/wezterm/colors/dark.toml", "[colors]\nbackground = '#000000'\n").unwrap();
+    home.selector(Target::Wezterm, &[("dark", ".config/wezterm/colors/dark.toml")]).unwrap();
+
+    let mut opts = home.options();
+    opts.wezterm.reload = true;
+    opts.picom.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    home.assert_file(
+        ".config/picom/alco.conf",
+        block("active-opacity = 1.0;\ninactive-opacity = 1.0;\n"),
+    );
+
+    assert!(alco::set_opacity(home.state_file(), Some(1.5)).is_err());
+    assert_eq!(alco::set_opacity(home.state_file(), Some(0.9)).unwrap(), "dark");
+    home.apply(&opts, "light").unwrap();
+    // applying a colorscheme keeps the opacity
+    opts.opacity = alco::opacity(home.state_file());
+    assert_eq!(opts.opacity, Some(0.9));
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(
+        ".config/wezterm/colors/current.toml",
+        "window_background_opacity = 0.9\n\n[colors]\nbackground = '#000000'\n",
+    );
+    home.assert_file(
+        ".config/picom/alco.conf",
+        block("active-opacity = 0.9;\ninactive-opacity = 0.9;\n"),
+    );
+}
+
+#[cfg(feature = "i3")]
+#[test]
+fn i3() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\ncolors:\n  background: '#282828'\n  normal:\n    red: '#cc241d'\n",
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    opts.i3.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    home.assert_file(
+        ".config/i3/colors.conf",
+        block("client.urgent           #cc241d #cc241d #282828 #cc241d #cc241d\n"),
+    );
+
+    home.write(".config/i3/dark.conf", "client.urgent #ff0000 #ff0000 #ffffff\n").unwrap();
+    home.selector(Target::I3, &[("dark", ".config/i3/dark.conf")]).unwrap();
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    home.assert_file(".config/i3/colors.conf", block("client.urgent #ff0000 #ff0000 #ffffff\n"));
+}
+
+#[cfg(feature = "hyprland")]
+#[test]
+fn hyprland() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\ncolors:\n  background: '#282828'\n  normal:\n    blue: '#458588'\n",
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    opts.hyprland.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(
+        ".config/hypr/colors.conf",
+        block("$background = rgb(282828)\n$blue = rgb(458588)\n$accent = rgb(458588)\n$surface = rgb(282828)\n\n\
+         general {\n    col.active_border = rgb(458588)\n}\n\n\
+         group {\n    col.border_active = rgb(458588)\n}\n"));
+}
+
+#[cfg(feature = "zathura")]
+#[test]
+fn zathura() {
+    let home = fixture();
+    home.write(
+        ".config/alco/schemes/dark.yml",
+        "version: 2\ncolors:\n  foreground: '#ebdbb2'\n  background: '#282828'\n",
+    )
+    .unwrap();
+
+    let mut opts = home.options();
+    opts.zathura.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    let fg = |o: &str| format!("set {} \"#ebdbb2\"\n", o);
+    let bg = |o: &str| format!("set {} \"#282828\"\n", o);
+    let config = [
+        bg("default-bg"),
+        fg("default-fg"),
+        bg("statusbar-bg"),
+        fg("statusbar-fg"),
+        bg("inputbar-bg"),
+        fg("inputbar-fg"),
+        bg("notification-bg"),
+        fg("notification-fg"),
+        bg("notification-error-bg"),
+        bg("notification-warning-bg"),
+        bg("completion-bg"),
+        fg("completion-fg"),
+        bg("completion-highlight-fg"),
+        bg("index-bg"),
+        fg("index-fg"),
+        bg("index-active-fg"),
+        bg("recolor-lightcolor"),
+        fg("recolor-darkcolor"),
+    ];
+    home.assert_file(".config/zathura/alco", block(&config.concat()));
+}
+
+#[cfg(feature = "qutebrowser")]
+#[test]
+fn qutebrowser() {
+    let home = fixture();
+    let colors = "c.colors.webpage.bg = '#282828'\n";
+    home.write(".config/qutebrowser/colors/dark.py", colors).unwrap();
+    home.selector(Target::Qutebrowser, &[("dark", ".config/qutebrowser/colors/dark.py")]).unwrap();
+
+    let mut opts = home.options();
+    opts.qutebrowser.reload = true;
+    assert_ok(home.apply(&opts, "dark").unwrap());
+
+    home.assert_file(".config/qutebrowser/colors.py", block(colors));
+}
+
+#[cfg(feature = "vscode")]
+#[test]
+fn vscode() {
+    let home = fixture();
+    let settings = "{\n  // keep this\n  \"editor.fontSize\": 14, /* and this */\n}\n";
+    home.write(".config/Code/User/settings.json", settings).unwrap();
+    home.write(".config/alco/vscode-selector.yml", "dark: Default Dark+\nlight: Solarized Light\n")
+        .unwrap();
+
+    let mut opts = home.options();
+    opts.vscode.reload = true;
+
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    let expected = "{\n  // keep this\n  \"editor.fontSize\": 14, /* and this */\n  \"workbench.colorTheme\": \"Default Dark+\"\n}\n";
+    home.assert_file(".config/Code/User/settings.json", expected);
+
+    assert_ok(home.apply(&opts, "light").unwrap());
+    let expected = expected.replace("Default Dark+", "Solarized Light");
+    home.assert_file(".config/Code/User/settings.json", expected);
+}
+
+#[cfg(feature = "mako")]
+#[test]
+fn mako() {
+    let home = fixture();
+    let config = "font=monospace 10\nbackground-color=#ffffff\n\n[urgency=high]\n# keep this\nborder-color=#ff0000\n";
+    home.write(".config/mako/config", config).unwrap();
+    home.write(
+        ".config/mako/themes/dark",
+        "background-color=#282828\ntext-color=#ebdbb2\n\n[urgency=high]\nborder-color=#fb4934\n\n[urgency=low]\nborder-color=#928374\n",
+    )
+    .unwrap();
+    home.selector(Target::Mako, &[("dark", ".config/mako/themes/dark")]).unwrap();
+
+    let mut opts = home.options();
+    opts.mako.reload = true;
+
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    let expected = "font=monospace 10\nbackground-color=#282828\ntext-color=#ebdbb2\n\n[urgency=high]\n# keep this\nborder-color=#fb4934\n\n[urgency=low]\nborder-color=#928374\n";
+    home.assert_file(".config/mako/config", expected);
+
+    // applying again doesn't add anything
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    home.assert_file(".config/mako/config", expected);
+}
+
+#[cfg(feature = "ghostty")]
+#[test]
+fn ghostty() {
+    let home = fixture();
+    home.write(".config/ghostty/config", "# font\nfont-size = 12\n").unwrap();
+    home.write(".config/alco/ghostty-selector.yml", "dark: Gruvbox Dark\nlight: Gruvbox Light\n")
+        .unwrap();
+
+    let mut opts = home.options();
+    opts.ghostty.reload = true;
+
+    assert_ok(home.apply(&opts, "dark").unwrap());
+    let expected = "# font\nfont-size = 12\ntheme = Gruvbox Dark\n";
+    home.assert_file(".config/ghostty/config", expected);
+
+    assert_ok(home.apply(&opts, "light").unwrap());
+    let expected = expected.replace("Gruvbox Dark", "Gruvbox Light");
+    home.assert_file(".config/ghostty/config", expected);
+}
+
+#[cfg(feature = "fzf")]
+#[test]
+fn adhoc_scheme() {
+    let home = fixture();
+    let mut opts = home.options();
+    opts.fzf.reload = true;
+
+    let scheme = "version: 2\nmeta:\n  name: generated\ncolors:\n  background: '#282828'\n";
+    let name = alco::load_adhoc_scheme(&mut opts, scheme, "stdin").unwrap();
+    assert_eq!(name, "generated");
+    assert!(!home.join(".config/alco/schemes/generated.yml").exists());
+
+    let rendered = alco::render_target(&opts, Target::Fzf, &name).unwrap().unwrap();
+    assert_eq!(rendered, "export FZF_DEFAULT_OPTS=\"$FZF_DEFAULT_OPTS --color=bg:#282828\"\n");
+    assert!(alco::load_adhoc_scheme(&mut opts, "not a scheme", "stdin").is_err());
+}
+
+#[cfg(feature = "tmux")]
+#[test]
+fn plan() {
+    let home = fixture();
+    home.write(".config/tmux/colors/dark.conf", "set -g status-style bg=black\n").unwrap();
+    home.write(".config/tmux/colors/current.conf", block("set -g status-style bg=white\n"))
+        .unwrap();
+    home.selector(Target::Tmux, &[("dark", ".config/tmux/colors/dark.conf")]).unwrap();
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+    let plan = alco::plan(&opts, "dark");
+
+    let change = match plan.as_slice() {
+        [(Target::Tmux, Ok(c))] => c,
+        _ => panic!("unexpected plan"),
+    };
+    let lines = [
+        " # BEGIN alco",
+        "-set -g status-style bg=white",
+        "+set -g status-style bg=black",
+        " # END alco",
+    ];
+    assert!(matches!(change.hunks.as_slice(), [h] if h.lines == lines));
+    home.assert_file(".config/tmux/colors/current.conf", block("set -g status-style bg=white\n"));
+}
+
+#[cfg(feature = "gtk")]
+#[test]
+fn gtk() {
+    let home = fixture();
+    home.write(".config/alco/schemes/dark.yml", "version: 2\nmeta:\n  tags: [dark]\ncolors: {}\n")
+        .unwrap();
+    home.write(".config/alco/gtk-selector.yml", "dark: Adwaita-dark\n").unwrap();
+
+    let mut opts = home.options();
+    opts.gtk.reload = true;
+    opts.notify = true;
+    let plan = alco::plan(&opts, "dark");
+
+    let change = match plan.as_slice() {
+        [(Target::Gtk, Ok(c))] => c,
+        _ => panic!("unexpected plan"),
+    };
+    let commands = [
+        "gsettings set org.gnome.desktop.interface color-scheme 'prefer-dark'",
+        "gsettings set org.gnome.desktop.interface gtk-theme 'Adwaita-dark'",
+    ];
+    assert_eq!(change.commands, commands);
+    assert_eq!(change.path, None);
+}
+
+#[cfg(feature = "tmux")]
+#[test]
+fn missing_mapping() {
+    let home = fixture();
+    home.write(".config/tmux/colors/dark.conf", "set -g status-style bg=black\n").unwrap();
+    home.selector(Target::Tmux, &[("dark", ".config/tmux/colors/dark.conf")]).unwrap();
+
+    let mut opts = home.options();
+    opts.tmux.reload = true;
+    let results = home.apply(&opts, "light").unwrap();
+
+    assert!(matches!(results.as_slice(), [(Target::Tmux, Err(_))]));
+    assert!(!home.join(".config/tmux/colors/current.conf").exists());
+}
+
+#[test]
+fn convert() {
+    use alco::PaletteFormat;
+
+    let base16 = "scheme: gruvbox\nbase00: \"282828\"\nbase05: \"d5c4a1\"\nbase08: \"fb4934\"\n";
+    let kitty = alco::convert(base16, PaletteFormat::Base16, PaletteFormat::Kitty).unwrap();
+    assert_eq!(
+        kitty,
+        "foreground #d5c4a1\nbackground #282828\ncursor #d5c4a1\ncolor0 #282828\ncolor1 #fb4934\n\
+         color7 #d5c4a1\ncolor9 #fb4934\n"
+    );
+
+    let scheme = alco::convert(&kitty, PaletteFormat::Kitty, PaletteFormat::Alco).unwrap();
+    let back = alco::convert(&scheme, PaletteFormat::Alco, PaletteFormat::Kitty).unwrap();
+    assert_eq!(back, kitty);
+    assert!(alco::convert("", PaletteFormat::Kitty, PaletteFormat::Alco).is_err());
+}
+
+#[test]
+fn roles() {
+    let colors = alco::Colorscheme {
+        background: "#282828".parse().ok(),
+        normal: [
+            None,
+            "#cc241d".parse().ok(),
+            None,
+            None,
+            "#458588".parse().ok(),
+            None,
+            None,
+            None,
+        ],
+        ..Default::default()
+    };
+    let rules = [("accent".to_owned(), "colors.bright.blue | colors.normal.red".to_owned())];
+    let roles = alco::color_roles(&colors, &rules.into_iter().collect()).unwrap();
+
+    let rendered =
+        alco::render_template("{{roles.accent}} {{roles.surface}}", "dark", &colors, &roles);
+    assert_eq!(rendered.unwrap(), "#cc241d #282828");
+    assert!(alco::render_template("{{roles.warning}}", "dark", &colors, &roles).is_err());
+}